@@ -0,0 +1,74 @@
+extern crate clap;
+use clap::{App, Arg};
+
+pub struct Config {
+    pub genesis: String,
+    pub host: String,
+    pub port: Option<u16>,
+    pub nodes: usize,
+}
+
+pub fn load_config() -> Result<Config, String> {
+    let app = App::new("indy-node-emulator")
+        .version("0.1.0")
+        .about("Emulate a pool of Hyperledger Indy-Node validators for local testing")
+        .arg(
+            Arg::with_name("genesis")
+                .short("g")
+                .long("genesis")
+                .takes_value(true)
+                .value_name("GENESIS")
+                .help("Path to write the pool genesis transactions"),
+        )
+        .arg(
+            Arg::with_name("host")
+                .short("h")
+                .long("host")
+                .takes_value(true)
+                .value_name("HOST")
+                .default_value("127.0.0.1")
+                .help("Set the local address to listen on"),
+        )
+        .arg(
+            Arg::with_name("port")
+                .short("p")
+                .long("port")
+                .takes_value(true)
+                .value_name("PORT")
+                .help("Set the first port to listen on (ephemeral ports are used by default)"),
+        )
+        .arg(
+            Arg::with_name("nodes")
+                .short("n")
+                .long("nodes")
+                .takes_value(true)
+                .value_name("NODES")
+                .default_value("4")
+                .help("Set the number of validator nodes to emulate"),
+        );
+
+    let matches = app.get_matches();
+
+    let genesis = matches
+        .value_of("genesis")
+        .unwrap_or("genesis.txn")
+        .to_owned();
+    let host = matches.value_of("host").unwrap().to_owned();
+    let port = if let Some(port) = matches.value_of("port") {
+        Some(port.parse::<u16>().map_err(|_| "Invalid port number")?)
+    } else {
+        None
+    };
+    let nodes = matches
+        .value_of("nodes")
+        .unwrap()
+        .parse::<usize>()
+        .map_err(|_| "Invalid number of nodes")?;
+
+    Ok(Config {
+        genesis,
+        host,
+        port,
+        nodes,
+    })
+}
@@ -0,0 +1,216 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use serde_json::Value as SJsonValue;
+
+use indy_vdr::common::error::prelude::*;
+use indy_vdr::pool::PoolTransactions;
+
+use super::genesis::{bls_generator, domain_genesis, pool_genesis, NodeIdentity};
+use super::state::EmulatorState;
+
+static EMULATOR_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Configuration options for an emulated validator pool
+#[derive(Clone, Debug)]
+pub struct EmulatorConfig {
+    /// The number of validator nodes to emulate
+    pub node_count: usize,
+    /// The interface to listen on and advertise in the genesis transactions
+    pub host: String,
+    /// The first port to allocate. When not set, ephemeral ports are used
+    pub base_port: Option<u16>,
+}
+
+impl Default for EmulatorConfig {
+    fn default() -> Self {
+        Self {
+            node_count: 4,
+            host: "127.0.0.1".to_owned(),
+            base_port: None,
+        }
+    }
+}
+
+/// A running set of emulated validator nodes sharing a single ledger state
+pub struct Emulator {
+    nodes: Vec<NodeIdentity>,
+    pool_txns: Vec<SJsonValue>,
+    domain_txns: Vec<SJsonValue>,
+    cmd_send: zmq::Socket,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl Emulator {
+    /// Bind the node sockets and start answering client requests on a background thread.
+    pub fn start(config: EmulatorConfig) -> VdrResult<Self> {
+        if config.node_count == 0 {
+            return Err(input_err("At least one node is required"));
+        }
+        let gen = bls_generator()?;
+        let zmq_ctx = zmq::Context::new();
+        let mut nodes = Vec::with_capacity(config.node_count);
+        let mut sockets = Vec::with_capacity(config.node_count);
+        for idx in 0..config.node_count {
+            let mut node = NodeIdentity::new(idx + 1, &gen)?;
+            node.host = config.host.clone();
+            let socket = zmq_ctx.socket(zmq::ROUTER)?;
+            socket.set_curve_server(true)?;
+            socket.set_curve_secretkey(&node.curve_secret_key)?;
+            socket.set_linger(0)?;
+            if let Some(base_port) = config.base_port {
+                node.node_port = base_port + 2 * idx as u16;
+                node.client_port = node.node_port + 1;
+                socket
+                    .bind(&format!("tcp://{}:{}", node.host, node.client_port))
+                    .with_input_err(format!(
+                        "Error binding {} to port {}",
+                        node.alias, node.client_port
+                    ))?;
+            } else {
+                socket
+                    .bind(&format!("tcp://{}:*", node.host))
+                    .with_input_err(format!("Error binding {}", node.alias))?;
+                node.client_port = _bound_port(&socket)?;
+                // node-to-node traffic is never exchanged, so the client port is advertised twice
+                node.node_port = node.client_port;
+            }
+            debug!(
+                "{} listening on {}:{}",
+                node.alias, node.host, node.client_port
+            );
+            nodes.push(node);
+            sockets.push(socket);
+        }
+
+        let pool_txns = pool_genesis(&nodes);
+        let domain_txns = domain_genesis(&nodes)?;
+        let state = EmulatorState::new(&nodes, pool_txns.clone(), domain_txns.clone())?;
+        let aliases = nodes.iter().map(|node| node.alias.clone()).collect();
+
+        let inproc_name = format!(
+            "inproc://emulator_{}",
+            EMULATOR_COUNTER.fetch_add(1, Ordering::SeqCst)
+        );
+        let cmd_recv = zmq_ctx.socket(zmq::PAIR)?;
+        cmd_recv.bind(&inproc_name)?;
+        let cmd_send = zmq_ctx.socket(zmq::PAIR)?;
+        cmd_send.connect(&inproc_name)?;
+
+        let worker = thread::spawn(move || {
+            if let Err(err) = _run(state, aliases, sockets, cmd_recv) {
+                warn!("Emulator exited with error: {}", err)
+            } else {
+                trace!("Emulator exited");
+            }
+        });
+
+        Ok(Self {
+            nodes,
+            pool_txns,
+            domain_txns,
+            cmd_send,
+            worker: Some(worker),
+        })
+    }
+
+    /// Get the identities of the emulated nodes.
+    pub fn nodes(&self) -> &[NodeIdentity] {
+        &self.nodes
+    }
+
+    /// Get the genesis pool transactions as JSON strings.
+    pub fn genesis_transactions(&self) -> Vec<String> {
+        self.pool_txns.iter().map(SJsonValue::to_string).collect()
+    }
+
+    /// Get the genesis domain transactions.
+    pub fn domain_transactions(&self) -> &[SJsonValue] {
+        &self.domain_txns
+    }
+
+    /// Get the genesis pool transactions for use with a `PoolBuilder`.
+    pub fn pool_transactions(&self) -> VdrResult<PoolTransactions> {
+        PoolTransactions::from_json_transactions(self.genesis_transactions())
+    }
+
+    /// Write the genesis pool transactions to a file, one per line.
+    pub fn write_genesis_file<P: AsRef<Path>>(&self, path: P) -> VdrResult<()> {
+        let path = path.as_ref();
+        let mut file = File::create(path)
+            .with_input_err(format!("Error creating genesis file: {}", path.display()))?;
+        for txn in self.genesis_transactions() {
+            writeln!(file, "{}", txn).with_input_err("Error writing genesis file")?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Emulator {
+    fn drop(&mut self) {
+        if self.cmd_send.send("exit", 0).is_err() {
+            trace!("Emulator command socket already closed")
+        }
+        if let Some(worker) = self.worker.take() {
+            debug!("Drop emulator thread");
+            worker.join().unwrap()
+        }
+    }
+}
+
+fn _bound_port(socket: &zmq::Socket) -> VdrResult<u16> {
+    let endpoint = socket
+        .get_last_endpoint()?
+        .map_err(|_| input_err("Invalid socket endpoint"))?;
+    endpoint
+        .rsplit(':')
+        .next()
+        .and_then(|port| port.parse().ok())
+        .ok_or_else(|| input_err(format!("Invalid socket endpoint: {}", endpoint)))
+}
+
+fn _run(
+    mut state: EmulatorState,
+    aliases: Vec<String>,
+    sockets: Vec<zmq::Socket>,
+    cmd_recv: zmq::Socket,
+) -> VdrResult<()> {
+    loop {
+        let mut poll_items = sockets
+            .iter()
+            .map(|socket| socket.as_poll_item(zmq::POLLIN))
+            .collect::<Vec<_>>();
+        poll_items.push(cmd_recv.as_poll_item(zmq::POLLIN));
+        zmq::poll(&mut poll_items, -1)?;
+
+        if poll_items[sockets.len()].is_readable() {
+            let cmd = cmd_recv.recv_bytes(zmq::DONTWAIT)?;
+            if cmd == b"exit" {
+                break;
+            }
+        }
+        for ((alias, socket), item) in aliases.iter().zip(&sockets).zip(&poll_items) {
+            if !item.is_readable() {
+                continue;
+            }
+            while let Ok(parts) = socket.recv_multipart(zmq::DONTWAIT) {
+                if parts.len() != 2 {
+                    debug!("{}: dropping message with {} parts", alias, parts.len());
+                    continue;
+                }
+                trace!("{}: received {}", alias, String::from_utf8_lossy(&parts[1]));
+                for reply in state.handle_message(alias, &parts[1]) {
+                    trace!("{}: sending {}", alias, reply);
+                    socket.send_multipart(
+                        vec![parts[0].as_slice(), reply.as_bytes()],
+                        zmq::DONTWAIT,
+                    )?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
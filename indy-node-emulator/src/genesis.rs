@@ -0,0 +1,234 @@
+use serde_json::Value as SJsonValue;
+
+use ursa::bls::{Generator, ProofOfPossession, SignKey as BlsSignKey, VerKey as BlsVerKey};
+
+use indy_utils::base58;
+use indy_utils::hash::SHA256;
+use indy_vdr::common::error::prelude::*;
+use indy_vdr::utils::did::generate_did;
+
+/// The BLS generator used by the verifier (matches the library default)
+pub(crate) const DEFAULT_GENERATOR: &str = "3LHpUjiyFC2q2hD7MnwwNmVXiuaFbQx2XkAFJWzswCjgN1utjsCeLzHsKk1nJvFEaS4fcrUmVAkdhtPCYbrVyATZcmzwJReTcJqwqBCPTmTQ9uWPwz6rEncKb2pYYYFcdHa8N17HzVyTqKfgPi4X9pMetfT3A5xCHq54R2pDNYWVLDX";
+
+/// Seed of the trustee DID registered in the emulated domain ledger
+pub const TRUSTEE_SEED: &str = "000000000000000000000000Trustee1";
+
+/// Build the conventional 32 byte seed for a named identity, such as `Node1` or `Steward1`.
+pub fn identity_seed(name: &str) -> VdrResult<[u8; 32]> {
+    let padded = format!("{:0>32}", name);
+    if padded.len() != 32 {
+        return Err(input_err(format!("Identity name too long: {}", name)));
+    }
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(padded.as_bytes());
+    Ok(seed)
+}
+
+pub(crate) fn bls_generator() -> VdrResult<Generator> {
+    let gen = base58::decode(DEFAULT_GENERATOR)?;
+    Generator::from_bytes(&gen).map_err(|err| {
+        err_msg(
+            VdrErrorKind::Resource,
+            format!("Error loading generator: {}", err),
+        )
+    })
+}
+
+/// A DID registered in the genesis domain ledger
+#[derive(Clone, Debug)]
+pub struct GenesisDid {
+    pub did: String,
+    pub verkey: String,
+    pub role: Option<String>,
+}
+
+impl GenesisDid {
+    /// Derive a DID and verkey from a seed.
+    pub fn from_seed(seed: &[u8], role: Option<&str>) -> VdrResult<Self> {
+        let (did, _, verkey) = generate_did(Some(seed))?;
+        Ok(Self {
+            did: (*did).to_owned(),
+            verkey: base58::encode(verkey.key_bytes()),
+            role: role.map(str::to_owned),
+        })
+    }
+
+    /// Build the genesis NYM transaction for this DID.
+    pub fn nym_transaction(&self, seq_no: usize, from: Option<&str>) -> SJsonValue {
+        let mut metadata = json!({});
+        if let Some(from) = from {
+            metadata["from"] = SJsonValue::from(from);
+        }
+        let mut data = json!({
+            "dest": self.did,
+            "verkey": self.verkey,
+        });
+        if let Some(ref role) = self.role {
+            data["role"] = SJsonValue::from(role.as_str());
+        }
+        json!({
+            "reqSignature": {},
+            "txn": {
+                "data": data,
+                "metadata": metadata,
+                "type": "1",
+            },
+            "txnMetadata": {"seqNo": seq_no},
+            "ver": "1",
+        })
+    }
+}
+
+/// The keys and addresses of an emulated validator node
+pub struct NodeIdentity {
+    pub alias: String,
+    pub host: String,
+    pub client_port: u16,
+    pub node_port: u16,
+    /// The base58-encoded ED25519 verkey of the node, used as the NODE transaction `dest`
+    pub verkey: String,
+    pub steward: GenesisDid,
+    pub(crate) curve_secret_key: Vec<u8>,
+    pub(crate) bls_sign_key: BlsSignKey,
+    pub(crate) bls_ver_key: BlsVerKey,
+    bls_pop: ProofOfPossession,
+}
+
+impl NodeIdentity {
+    /// Derive the keys of a node and its steward from the node index (starting from 1).
+    pub fn new(index: usize, gen: &Generator) -> VdrResult<Self> {
+        let alias = format!("Node{}", index);
+        let seed = identity_seed(&alias)?;
+        let (_, private_key, verkey) = generate_did(Some(&seed[..]))?;
+        let curve_secret_key = private_key.key_exchange()?.key_bytes().to_vec();
+        let bls_err = |err: ursa::errors::UrsaCryptoError| {
+            err_msg(
+                VdrErrorKind::Resource,
+                format!("Error deriving BLS keys: {}", err),
+            )
+        };
+        let bls_sign_key = BlsSignKey::new(Some(&seed[..])).map_err(bls_err)?;
+        let bls_ver_key = BlsVerKey::new(gen, &bls_sign_key).map_err(bls_err)?;
+        let bls_pop = ProofOfPossession::new(&bls_ver_key, &bls_sign_key).map_err(bls_err)?;
+        let steward = GenesisDid::from_seed(
+            &identity_seed(&format!("Steward{}", index))?,
+            Some(indy_vdr::ledger::constants::STEWARD),
+        )?;
+        Ok(Self {
+            alias,
+            host: "127.0.0.1".to_owned(),
+            client_port: 0,
+            node_port: 0,
+            verkey: base58::encode(verkey.key_bytes()),
+            steward,
+            curve_secret_key,
+            bls_sign_key,
+            bls_ver_key,
+            bls_pop,
+        })
+    }
+
+    /// Build the genesis NODE transaction for this node.
+    pub fn node_transaction(&self, seq_no: usize) -> SJsonValue {
+        json!({
+            "reqSignature": {},
+            "txn": {
+                "data": {
+                    "data": {
+                        "alias": self.alias,
+                        "blskey": base58::encode(self.bls_ver_key.as_bytes()),
+                        "blskey_pop": base58::encode(self.bls_pop.as_bytes()),
+                        "client_ip": self.host,
+                        "client_port": self.client_port,
+                        "node_ip": self.host,
+                        "node_port": self.node_port,
+                        "services": ["VALIDATOR"],
+                    },
+                    "dest": self.verkey,
+                },
+                "metadata": {"from": self.steward.did},
+                "type": "0",
+            },
+            "txnMetadata": {
+                "seqNo": seq_no,
+                "txnId": hex::encode(SHA256::digest(self.alias.as_bytes())),
+            },
+            "ver": "1",
+        })
+    }
+}
+
+impl std::fmt::Debug for NodeIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "NodeIdentity {{ alias: {}, verkey: {}, client: {}:{} }}",
+            self.alias, self.verkey, self.host, self.client_port
+        )
+    }
+}
+
+/// Build the genesis domain ledger: the trustee followed by the steward of each node.
+pub fn domain_genesis(nodes: &[NodeIdentity]) -> VdrResult<Vec<SJsonValue>> {
+    let trustee = GenesisDid::from_seed(
+        TRUSTEE_SEED.as_bytes(),
+        Some(indy_vdr::ledger::constants::TRUSTEE),
+    )?;
+    let mut txns = vec![trustee.nym_transaction(1, None)];
+    for node in nodes {
+        let seq_no = txns.len() + 1;
+        txns.push(node.steward.nym_transaction(seq_no, Some(&trustee.did)));
+    }
+    Ok(txns)
+}
+
+/// Build the genesis pool ledger with one NODE transaction per node.
+pub fn pool_genesis(nodes: &[NodeIdentity]) -> Vec<SJsonValue> {
+    nodes
+        .iter()
+        .enumerate()
+        .map(|(idx, node)| node.node_transaction(idx + 1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use indy_vdr::pool::PoolTransactions;
+
+    #[test]
+    fn identity_seed_pads_name() {
+        assert_eq!(&identity_seed("Trustee1").unwrap(), TRUSTEE_SEED.as_bytes());
+        assert!(identity_seed(&"x".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn domain_genesis_registers_trustee() {
+        let gen = bls_generator().unwrap();
+        let nodes = vec![NodeIdentity::new(1, &gen).unwrap()];
+        let txns = domain_genesis(&nodes).unwrap();
+        assert_eq!(txns.len(), 2);
+        assert_eq!(txns[0]["txn"]["data"]["dest"], "V4SGRU86Z58d6TV7PBUe6f");
+        assert_eq!(txns[1]["txn"]["metadata"]["from"], "V4SGRU86Z58d6TV7PBUe6f");
+    }
+
+    #[test]
+    fn pool_genesis_loads_verifiers() {
+        let gen = bls_generator().unwrap();
+        let mut nodes = (1..=4)
+            .map(|idx| NodeIdentity::new(idx, &gen).unwrap())
+            .collect::<Vec<_>>();
+        for (idx, node) in nodes.iter_mut().enumerate() {
+            node.node_port = 9701 + 2 * idx as u16;
+            node.client_port = node.node_port + 1;
+        }
+        let txns = pool_genesis(&nodes)
+            .iter()
+            .map(SJsonValue::to_string)
+            .collect::<Vec<_>>();
+        let pool_txns = PoolTransactions::from_json_transactions(&txns).unwrap();
+        assert_eq!(pool_txns.len(), 4);
+        assert_eq!(pool_txns.encode_json().unwrap(), txns);
+    }
+}
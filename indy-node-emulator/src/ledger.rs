@@ -0,0 +1,250 @@
+use serde_json::Value as SJsonValue;
+
+use indy_utils::hash::SHA256;
+
+/// Ledger identifier for the pool ledger
+pub const POOL_LEDGER_ID: u8 = 0;
+/// Ledger identifier for the domain ledger
+pub const DOMAIN_LEDGER_ID: u8 = 1;
+/// Ledger identifier for the config ledger
+pub const CONFIG_LEDGER_ID: u8 = 2;
+
+/// An append-only transaction log with an RFC 6962 compatible merkle tree
+#[derive(Clone, Debug, Default)]
+pub struct Ledger {
+    txns: Vec<SJsonValue>,
+    leaves: Vec<Vec<u8>>,
+}
+
+impl Ledger {
+    /// Create a new, empty ledger.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a transaction along with the serialized form used to derive its leaf hash.
+    /// Returns the sequence number of the new transaction.
+    pub fn append(&mut self, txn: SJsonValue, leaf_data: &[u8]) -> usize {
+        self.txns.push(txn);
+        self.leaves.push(hash_leaf(leaf_data));
+        self.txns.len()
+    }
+
+    /// Get the number of transactions in the ledger.
+    pub fn len(&self) -> usize {
+        self.txns.len()
+    }
+
+    /// Check whether the ledger has no transactions.
+    pub fn is_empty(&self) -> bool {
+        self.txns.is_empty()
+    }
+
+    /// Fetch a transaction by its (1-based) sequence number.
+    pub fn get(&self, seq_no: usize) -> Option<&SJsonValue> {
+        if seq_no == 0 {
+            None
+        } else {
+            self.txns.get(seq_no - 1)
+        }
+    }
+
+    /// Iterate over the transactions in the ledger.
+    pub fn iter(&self) -> impl Iterator<Item = &SJsonValue> {
+        self.txns.iter()
+    }
+
+    /// Get the current merkle root hash.
+    pub fn root_hash(&self) -> Vec<u8> {
+        subtree_root(&self.leaves)
+    }
+
+    /// Get the merkle root hash of the first `size` transactions.
+    pub fn root_hash_at(&self, size: usize) -> Option<Vec<u8>> {
+        if size > self.leaves.len() {
+            None
+        } else {
+            Some(subtree_root(&self.leaves[..size]))
+        }
+    }
+
+    /// Get the audit path for a transaction, ordered from the leaf upwards.
+    pub fn audit_path(&self, seq_no: usize) -> Option<Vec<Vec<u8>>> {
+        if seq_no == 0 || seq_no > self.leaves.len() {
+            None
+        } else {
+            Some(audit_path(seq_no - 1, &self.leaves))
+        }
+    }
+
+    /// Get the consistency proof between a prior ledger size and a later one.
+    pub fn consistency_proof(&self, old_size: usize, new_size: usize) -> Option<Vec<Vec<u8>>> {
+        if old_size == 0 || old_size > new_size || new_size > self.leaves.len() {
+            None
+        } else {
+            Some(subproof(old_size, &self.leaves[..new_size], true))
+        }
+    }
+}
+
+fn hash_leaf(data: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(data.len() + 1);
+    buf.push(0x00);
+    buf.extend_from_slice(data);
+    SHA256::digest(buf.as_slice())
+}
+
+fn hash_nodes(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(left.len() + right.len() + 1);
+    buf.push(0x01);
+    buf.extend_from_slice(left);
+    buf.extend_from_slice(right);
+    SHA256::digest(buf.as_slice())
+}
+
+// the largest power of two smaller than `size`, where `size` > 1
+fn split_point(size: usize) -> usize {
+    size.next_power_of_two() / 2
+}
+
+fn subtree_root(leaves: &[Vec<u8>]) -> Vec<u8> {
+    match leaves.len() {
+        0 => SHA256::digest(&[][..]),
+        1 => leaves[0].clone(),
+        size => {
+            let k = split_point(size);
+            hash_nodes(&subtree_root(&leaves[..k]), &subtree_root(&leaves[k..]))
+        }
+    }
+}
+
+fn audit_path(idx: usize, leaves: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let size = leaves.len();
+    if size <= 1 {
+        return vec![];
+    }
+    let k = split_point(size);
+    let (mut path, sibling) = if idx < k {
+        (audit_path(idx, &leaves[..k]), subtree_root(&leaves[k..]))
+    } else {
+        (
+            audit_path(idx - k, &leaves[k..]),
+            subtree_root(&leaves[..k]),
+        )
+    };
+    path.push(sibling);
+    path
+}
+
+fn subproof(old_size: usize, leaves: &[Vec<u8>], complete: bool) -> Vec<Vec<u8>> {
+    let size = leaves.len();
+    if old_size == size {
+        return if complete {
+            vec![]
+        } else {
+            vec![subtree_root(leaves)]
+        };
+    }
+    let k = split_point(size);
+    let (mut proof, sibling) = if old_size <= k {
+        (
+            subproof(old_size, &leaves[..k], complete),
+            subtree_root(&leaves[k..]),
+        )
+    } else {
+        (
+            subproof(old_size - k, &leaves[k..], false),
+            subtree_root(&leaves[..k]),
+        )
+    };
+    proof.push(sibling);
+    proof
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use indy_vdr::common::merkle_tree::MerkleTree;
+
+    fn _txns(count: usize) -> Vec<Vec<u8>> {
+        (1..=count)
+            .map(|idx| format!("transaction {}", idx).into_bytes())
+            .collect()
+    }
+
+    fn _ledger(txns: &[Vec<u8>]) -> Ledger {
+        let mut ledger = Ledger::new();
+        for txn in txns {
+            ledger.append(SJsonValue::Null, txn);
+        }
+        ledger
+    }
+
+    #[test]
+    fn ledger_root_matches_merkle_tree() {
+        for count in 1..=9 {
+            let txns = _txns(count);
+            let ledger = _ledger(&txns);
+            let tree = MerkleTree::from_vec(txns).unwrap();
+            assert_eq!(&ledger.root_hash(), tree.root_hash());
+        }
+    }
+
+    #[test]
+    fn ledger_audit_path_rebuilds_root() {
+        let txns = _txns(7);
+        let ledger = _ledger(&txns);
+        for seq_no in 1..=txns.len() {
+            let path = ledger.audit_path(seq_no).unwrap();
+            let (mut idx, mut size) = (seq_no - 1, txns.len());
+            // collect turns from the root down, then apply them bottom up
+            let mut turns = vec![];
+            while size > 1 {
+                let k = split_point(size);
+                turns.push(idx < k);
+                if idx < k {
+                    size = k;
+                } else {
+                    idx -= k;
+                    size -= k;
+                }
+            }
+            turns.reverse();
+            assert_eq!(turns.len(), path.len());
+            let root = path.iter().zip(turns).fold(
+                hash_leaf(&txns[seq_no - 1]),
+                |hash, (sibling, is_left)| {
+                    if is_left {
+                        hash_nodes(&hash, sibling)
+                    } else {
+                        hash_nodes(sibling, &hash)
+                    }
+                },
+            );
+            assert_eq!(root, ledger.root_hash());
+        }
+    }
+
+    #[test]
+    fn ledger_consistency_proof_accepted_by_merkle_tree() {
+        let txns = _txns(9);
+        let ledger = _ledger(&txns);
+        for old_size in 1..txns.len() {
+            let tree = MerkleTree::from_vec(txns[..old_size].to_vec()).unwrap();
+            let proof = ledger.consistency_proof(old_size, txns.len()).unwrap();
+            assert!(tree
+                .consistency_proof(&ledger.root_hash(), txns.len(), &proof)
+                .unwrap());
+        }
+    }
+
+    #[test]
+    fn ledger_consistency_proof_rejects_invalid_range() {
+        let ledger = _ledger(&_txns(3));
+        assert!(ledger.consistency_proof(0, 3).is_none());
+        assert!(ledger.consistency_proof(3, 2).is_none());
+        assert!(ledger.consistency_proof(2, 4).is_none());
+        assert_eq!(ledger.consistency_proof(3, 3), Some(vec![]));
+    }
+}
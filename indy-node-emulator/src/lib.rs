@@ -0,0 +1,23 @@
+//! An in-process emulator for a pool of Hyperledger Indy validator nodes.
+//!
+//! The emulated nodes accept CurveZMQ client connections and answer ledger status,
+//! catchup, read and write requests from a shared in-memory ledger, producing state
+//! proofs and BLS multi-signatures which can be verified by `indy-vdr`.
+
+#[macro_use]
+extern crate log;
+#[macro_use]
+extern crate serde_json;
+
+mod emulator;
+mod genesis;
+mod ledger;
+mod state;
+mod trie;
+
+pub use emulator::{Emulator, EmulatorConfig};
+pub use genesis::{
+    domain_genesis, identity_seed, pool_genesis, GenesisDid, NodeIdentity, TRUSTEE_SEED,
+};
+pub use ledger::{Ledger, CONFIG_LEDGER_ID, DOMAIN_LEDGER_ID, POOL_LEDGER_ID};
+pub use trie::Trie;
@@ -0,0 +1,42 @@
+mod app;
+
+use std::process::exit;
+use std::thread;
+
+use indy_node_emulator::{Emulator, EmulatorConfig};
+
+fn main() {
+    let config = app::load_config().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(1);
+    });
+
+    env_logger::init();
+
+    let emulator = Emulator::start(EmulatorConfig {
+        node_count: config.nodes,
+        host: config.host,
+        base_port: config.port,
+    })
+    .unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(1);
+    });
+    if let Err(err) = emulator.write_genesis_file(&config.genesis) {
+        eprintln!("{}", err);
+        exit(1);
+    }
+
+    for node in emulator.nodes() {
+        println!(
+            "{} listening on {}:{}",
+            node.alias, node.host, node.client_port
+        );
+    }
+    println!("Genesis transactions written to {}", config.genesis);
+
+    // the emulator runs on a background thread until the process is stopped
+    loop {
+        thread::park();
+    }
+}
@@ -0,0 +1,781 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::Value as SJsonValue;
+
+use ursa::bls::{Bls, MultiSignature, SignKey as BlsSignKey};
+
+use indy_utils::hash::SHA256;
+use indy_utils::{base58, base64};
+use indy_vdr::common::error::prelude::*;
+use indy_vdr::ledger::constants::{
+    ATTRIB, CRED_DEF, GET_ATTR, GET_CRED_DEF, GET_NYM, GET_SCHEMA, GET_TXN, NYM, SCHEMA,
+};
+
+use super::genesis::NodeIdentity;
+use super::ledger::{Ledger, CONFIG_LEDGER_ID, DOMAIN_LEDGER_ID, POOL_LEDGER_ID};
+use super::trie::Trie;
+
+/// Multi-signatures are renewed after this many seconds, well within the client freshness threshold
+const SIGNATURE_REFRESH_SECS: u64 = 60;
+
+const PROTOCOL_VERSION: u64 = 2;
+
+/// The shared ledger state behind all of the emulated nodes
+pub(crate) struct EmulatorState {
+    signers: Vec<(String, BlsSignKey)>,
+    pool_ledger: Ledger,
+    domain_ledger: Ledger,
+    config_ledger: Ledger,
+    domain_state: DomainState,
+    write_replies: HashMap<(String, u64), SJsonValue>,
+    signed_states: HashMap<u8, SignedState>,
+}
+
+struct StateEntry {
+    value: String,
+    data: SJsonValue,
+    seq_no: SJsonValue,
+    txn_time: SJsonValue,
+}
+
+#[derive(Default)]
+struct DomainState {
+    entries: BTreeMap<Vec<u8>, StateEntry>,
+    trie: Option<Trie>,
+}
+
+impl DomainState {
+    fn get(&self, key: &[u8]) -> Option<&StateEntry> {
+        self.entries.get(key)
+    }
+
+    fn insert(&mut self, key: Vec<u8>, entry: StateEntry) {
+        self.entries.insert(key, entry);
+        self.trie.take();
+    }
+
+    fn trie(&mut self) -> &Trie {
+        let entries = &self.entries;
+        self.trie.get_or_insert_with(|| {
+            let values = entries
+                .iter()
+                .map(|(key, entry)| (key.as_slice(), entry.value.as_bytes()))
+                .collect::<BTreeMap<_, _>>();
+            Trie::build(&values)
+        })
+    }
+}
+
+struct SignedState {
+    state_root: Vec<u8>,
+    txn_root: Vec<u8>,
+    timestamp: u64,
+    multi_signature: SJsonValue,
+}
+
+impl EmulatorState {
+    pub fn new(
+        nodes: &[NodeIdentity],
+        pool_txns: Vec<SJsonValue>,
+        domain_txns: Vec<SJsonValue>,
+    ) -> VdrResult<Self> {
+        let signers = nodes
+            .iter()
+            .map(|node| {
+                BlsSignKey::from_bytes(node.bls_sign_key.as_bytes())
+                    .map(|sign_key| (node.alias.clone(), sign_key))
+                    .map_err(|err| {
+                        err_msg(
+                            VdrErrorKind::Resource,
+                            format!("Error loading BLS sign key: {}", err),
+                        )
+                    })
+            })
+            .collect::<VdrResult<Vec<_>>>()?;
+        let mut state = Self {
+            signers,
+            pool_ledger: Ledger::new(),
+            domain_ledger: Ledger::new(),
+            config_ledger: Ledger::new(),
+            domain_state: DomainState::default(),
+            write_replies: HashMap::new(),
+            signed_states: HashMap::new(),
+        };
+        for txn in pool_txns {
+            let leaf = rmp_serde::to_vec_named(&txn)
+                .with_input_err("Can't encode pool transaction as msgpack")?;
+            state.pool_ledger.append(txn, &leaf);
+        }
+        for txn in domain_txns {
+            state.append_domain_txn(txn)?;
+        }
+        Ok(state)
+    }
+
+    /// Process a message received by a node, returning the messages to send back.
+    pub fn handle_message(&mut self, node_alias: &str, msg: &[u8]) -> Vec<String> {
+        let msg = match std::str::from_utf8(msg) {
+            Ok(msg) => msg,
+            Err(_) => {
+                debug!("{}: dropping non-UTF8 message", node_alias);
+                return vec![];
+            }
+        };
+        if msg == "pi" {
+            return vec!["po".to_owned()];
+        }
+        let msg: SJsonValue = match serde_json::from_str(msg) {
+            Ok(msg) => msg,
+            Err(_) => {
+                debug!("{}: dropping malformed message", node_alias);
+                return vec![];
+            }
+        };
+        let replies = if let Some(op) = msg["op"].as_str() {
+            match op {
+                "LEDGER_STATUS" => self.handle_ledger_status(&msg),
+                "CATCHUP_REQ" => self.handle_catchup_request(&msg),
+                _ => {
+                    debug!("{}: unhandled message type: {}", node_alias, op);
+                    vec![]
+                }
+            }
+        } else if msg["operation"].is_object() {
+            self.handle_request(&msg)
+        } else {
+            debug!("{}: dropping unrecognized message", node_alias);
+            vec![]
+        };
+        replies.iter().map(SJsonValue::to_string).collect()
+    }
+
+    fn ledger(&self, ledger_id: u8) -> Option<&Ledger> {
+        match ledger_id {
+            POOL_LEDGER_ID => Some(&self.pool_ledger),
+            DOMAIN_LEDGER_ID => Some(&self.domain_ledger),
+            CONFIG_LEDGER_ID => Some(&self.config_ledger),
+            _ => None,
+        }
+    }
+
+    fn handle_ledger_status(&self, msg: &SJsonValue) -> Vec<SJsonValue> {
+        let ledger_id = msg["ledgerId"].as_u64().unwrap_or(0) as u8;
+        let ledger = match self.ledger(ledger_id) {
+            Some(ledger) => ledger,
+            None => return vec![],
+        };
+        let size = ledger.len();
+        let root = base58::encode(ledger.root_hash());
+        if let (Some(their_size), Some(their_root)) =
+            (msg["txnSeqNo"].as_u64(), msg["merkleRoot"].as_str())
+        {
+            let their_size = their_size as usize;
+            let known_root = ledger.root_hash_at(their_size).map(base58::encode);
+            if their_size < size && known_root.as_deref() == Some(their_root) {
+                if let Some(hashes) = ledger.consistency_proof(their_size, size) {
+                    return vec![json!({
+                        "op": "CONSISTENCY_PROOF",
+                        "ledgerId": ledger_id,
+                        "seqNoStart": their_size,
+                        "seqNoEnd": size,
+                        "viewNo": 0,
+                        "ppSeqNo": 0,
+                        "oldMerkleRoot": their_root,
+                        "newMerkleRoot": root,
+                        "hashes": hashes.iter().map(base58::encode).collect::<Vec<_>>(),
+                    })];
+                }
+            }
+        }
+        vec![json!({
+            "op": "LEDGER_STATUS",
+            "ledgerId": ledger_id,
+            "txnSeqNo": size,
+            "merkleRoot": root,
+            "viewNo": null,
+            "ppSeqNo": null,
+            "protocolVersion": PROTOCOL_VERSION,
+        })]
+    }
+
+    fn handle_catchup_request(&self, msg: &SJsonValue) -> Vec<SJsonValue> {
+        let ledger_id = msg["ledgerId"].as_u64().unwrap_or(0) as u8;
+        let ledger = match self.ledger(ledger_id) {
+            Some(ledger) => ledger,
+            None => return vec![],
+        };
+        let (start, end, till) = match (
+            msg["seqNoStart"].as_u64(),
+            msg["seqNoEnd"].as_u64(),
+            msg["catchupTill"].as_u64(),
+        ) {
+            (Some(start), Some(end), Some(till)) => (start as usize, end as usize, till as usize),
+            _ => {
+                debug!("Dropping incomplete catchup request");
+                return vec![];
+            }
+        };
+        if start == 0 || start > end || end > till || till > ledger.len() {
+            debug!("Dropping catchup request for unavailable transactions");
+            return vec![];
+        }
+        let txns = (start..=end)
+            .filter_map(|seq_no| {
+                ledger
+                    .get(seq_no)
+                    .map(|txn| (seq_no.to_string(), txn.clone()))
+            })
+            .collect::<serde_json::Map<_, _>>();
+        let cons_proof = ledger.consistency_proof(end, till).unwrap_or_default();
+        vec![json!({
+            "op": "CATCHUP_REP",
+            "ledgerId": ledger_id,
+            "txns": txns,
+            "consProof": cons_proof.iter().map(base58::encode).collect::<Vec<_>>(),
+        })]
+    }
+
+    fn handle_request(&mut self, req: &SJsonValue) -> Vec<SJsonValue> {
+        let req_id = match req["reqId"].as_u64() {
+            Some(req_id) => req_id,
+            None => {
+                debug!("Dropping request without reqId");
+                return vec![];
+            }
+        };
+        let identifier = req["identifier"]
+            .as_str()
+            .or_else(|| req["endorser"].as_str())
+            .unwrap_or_default()
+            .to_owned();
+        let txn_type = req["operation"]["type"].as_str().unwrap_or_default();
+        let result = match txn_type {
+            NYM | ATTRIB | SCHEMA | CRED_DEF => {
+                return self.handle_write(req, identifier, req_id, txn_type);
+            }
+            GET_NYM => self.get_nym(req),
+            GET_ATTR => self.get_attrib(req),
+            GET_SCHEMA => self.get_schema(req),
+            GET_CRED_DEF => self.get_cred_def(req),
+            GET_TXN => self.get_txn(req),
+            _ => Err(input_err(format!(
+                "Unsupported transaction type: {:?}",
+                txn_type
+            ))),
+        };
+        match result {
+            Ok(result) => vec![json!({"op": "REPLY", "result": result})],
+            Err(err) => vec![_nack(&identifier, req_id, &err)],
+        }
+    }
+
+    fn handle_write(
+        &mut self,
+        req: &SJsonValue,
+        identifier: String,
+        req_id: u64,
+        txn_type: &str,
+    ) -> Vec<SJsonValue> {
+        let ack = json!({"op": "REQACK", "identifier": identifier, "reqId": req_id});
+        let reply_key = (identifier, req_id);
+        // each node receives its own copy of the request, but it is only applied once
+        if let Some(result) = self.write_replies.get(&reply_key) {
+            return vec![ack, json!({"op": "REPLY", "result": result})];
+        }
+        match self.apply_write(req, &reply_key.0, req_id, txn_type) {
+            Ok(result) => {
+                let reply = json!({"op": "REPLY", "result": result});
+                self.write_replies.insert(reply_key, result);
+                vec![ack, reply]
+            }
+            Err(err) => vec![_nack(&reply_key.0, req_id, &err)],
+        }
+    }
+
+    fn apply_write(
+        &mut self,
+        req: &SJsonValue,
+        identifier: &str,
+        req_id: u64,
+        txn_type: &str,
+    ) -> VdrResult<SJsonValue> {
+        let mut data = req["operation"].clone();
+        if let Some(data) = data.as_object_mut() {
+            data.remove("type");
+        }
+        let mut metadata = json!({"from": identifier, "reqId": req_id});
+        if let Some(endorser) = req["endorser"].as_str() {
+            metadata["endorser"] = SJsonValue::from(endorser);
+        }
+        let mut txn = json!({
+            "txn": {
+                "type": txn_type,
+                "data": data,
+                "metadata": metadata,
+            },
+            "txnMetadata": {
+                "seqNo": self.domain_ledger.len() + 1,
+                "txnTime": _now(),
+            },
+            "ver": "1",
+            "reqSignature": _request_signature(req, identifier),
+        });
+        if let Some(protocol_version) = req.get("protocolVersion") {
+            txn["txn"]["protocolVersion"] = protocol_version.clone();
+        }
+
+        let seq_no = self.append_domain_txn(txn.clone())?;
+        let ledger = &self.domain_ledger;
+        let audit_path = ledger.audit_path(seq_no).unwrap_or_default();
+        txn["auditPath"] = audit_path
+            .iter()
+            .map(base58::encode)
+            .collect::<Vec<_>>()
+            .into();
+        txn["rootHash"] = SJsonValue::from(base58::encode(ledger.root_hash()));
+        Ok(txn)
+    }
+
+    /// Validate a domain transaction, update the state and append it to the ledger.
+    fn append_domain_txn(&mut self, txn: SJsonValue) -> VdrResult<usize> {
+        let updates = self.state_updates(&txn)?;
+        let leaf = rmp_serde::to_vec(&_ledger_form(&txn))
+            .with_input_err("Can't encode transaction as msgpack")?;
+        for (key, entry) in updates {
+            self.domain_state.insert(key, entry);
+        }
+        Ok(self.domain_ledger.append(txn, &leaf))
+    }
+
+    fn state_updates(&self, txn: &SJsonValue) -> VdrResult<Vec<(Vec<u8>, StateEntry)>> {
+        let data = &txn["txn"]["data"];
+        let submitter = &txn["txn"]["metadata"]["from"];
+        let seq_no = txn["txnMetadata"]["seqNo"].clone();
+        let txn_time = txn["txnMetadata"]["txnTime"].clone();
+        let dest = data["dest"].as_str();
+
+        let update = match txn["txn"]["type"].as_str().unwrap_or_default() {
+            NYM => {
+                let dest = dest.ok_or_else(|| input_err("Missing dest for NYM"))?;
+                let key = SHA256::digest(dest.as_bytes());
+                let (identifier, mut verkey, mut role, mut diddoc_content, mut version) =
+                    match self.domain_state.get(&key) {
+                        Some(entry) => (
+                            entry.data["identifier"].clone(),
+                            entry.data["verkey"].clone(),
+                            entry.data["role"].clone(),
+                            entry.data["diddocContent"].clone(),
+                            entry.data["version"].clone(),
+                        ),
+                        None => (
+                            submitter.clone(),
+                            SJsonValue::Null,
+                            SJsonValue::Null,
+                            SJsonValue::Null,
+                            SJsonValue::Null,
+                        ),
+                    };
+                if let Some(new_verkey) = data.get("verkey") {
+                    verkey = new_verkey.clone();
+                }
+                if let Some(new_role) = data.get("role") {
+                    role = match new_role.as_str() {
+                        Some("") => SJsonValue::Null,
+                        _ => new_role.clone(),
+                    };
+                }
+                if let Some(new_content) = data.get("diddocContent") {
+                    diddoc_content = new_content.clone();
+                }
+                if let Some(new_version) = data.get("version") {
+                    version = new_version.clone();
+                }
+                // same field order as the state proof verifier
+                let mut value = json!({});
+                value["seqNo"] = seq_no.clone();
+                value["txnTime"] = txn_time.clone();
+                value["identifier"] = identifier.clone();
+                value["role"] = role.clone();
+                value["verkey"] = verkey.clone();
+                let mut data = json!({
+                    "dest": dest,
+                    "identifier": identifier,
+                    "role": role,
+                    "seqNo": seq_no,
+                    "txnTime": txn_time,
+                    "verkey": verkey,
+                });
+                if !diddoc_content.is_null() {
+                    value["diddocContent"] = diddoc_content.clone();
+                    data["diddocContent"] = diddoc_content;
+                }
+                if !version.is_null() {
+                    value["version"] = version.clone();
+                    data["version"] = version;
+                }
+                (key, value.to_string(), data)
+            }
+            ATTRIB => {
+                let dest = dest.ok_or_else(|| input_err("Missing dest for ATTRIB"))?;
+                let (name, attr) = if let Some(raw) = data["raw"].as_str() {
+                    let parsed: SJsonValue = serde_json::from_str(raw)
+                        .with_input_err("Invalid raw attribute, expected a JSON object")?;
+                    match parsed.as_object() {
+                        Some(attrs) if attrs.len() == 1 => {
+                            (attrs.keys().next().unwrap().clone(), raw.to_owned())
+                        }
+                        _ => {
+                            return Err(input_err(
+                                "Raw attribute must contain exactly one attribute name",
+                            ))
+                        }
+                    }
+                } else if let Some(value) = data["enc"].as_str().or_else(|| data["hash"].as_str()) {
+                    (value.to_owned(), value.to_owned())
+                } else {
+                    return Err(input_err("Missing attribute data"));
+                };
+                let key = format!(
+                    "{}:1:{}",
+                    dest,
+                    hex::encode(SHA256::digest(name.as_bytes()))
+                );
+                let value = _stored_value(
+                    &seq_no,
+                    &txn_time,
+                    SJsonValue::from(hex::encode(SHA256::digest(attr.as_bytes()))),
+                );
+                (key.into_bytes(), value, SJsonValue::from(attr))
+            }
+            SCHEMA => {
+                let schema = &data["data"];
+                let (name, version) = match (schema["name"].as_str(), schema["version"].as_str()) {
+                    (Some(name), Some(version)) => (name, version),
+                    _ => return Err(input_err("Missing schema name or version")),
+                };
+                let submitter = submitter.as_str().unwrap_or_default();
+                let key = format!("{}:2:{}:{}", submitter, name, version).into_bytes();
+                if self.domain_state.get(&key).is_some() {
+                    return Err(input_err("Schema already exists"));
+                }
+                let mut val = schema.clone();
+                if let Some(val) = val.as_object_mut() {
+                    val.remove("name");
+                    val.remove("version");
+                }
+                (key, _stored_value(&seq_no, &txn_time, val), schema.clone())
+            }
+            CRED_DEF => {
+                let (schema_ref, signature_type) =
+                    match (data["ref"].as_u64(), data["signature_type"].as_str()) {
+                        (Some(schema_ref), Some(signature_type)) => (schema_ref, signature_type),
+                        _ => return Err(input_err("Missing credential definition reference")),
+                    };
+                let tag = data["tag"].as_str().unwrap_or("tag");
+                let submitter = submitter.as_str().unwrap_or_default();
+                let key = format!("{}:3:{}:{}:{}", submitter, signature_type, schema_ref, tag)
+                    .into_bytes();
+                if self.domain_state.get(&key).is_some() {
+                    return Err(input_err("Credential definition already exists"));
+                }
+                let cred_def = data["data"].clone();
+                (
+                    key,
+                    _stored_value(&seq_no, &txn_time, cred_def.clone()),
+                    cred_def,
+                )
+            }
+            _ => return Ok(vec![]),
+        };
+
+        let (key, value, data) = update;
+        Ok(vec![(
+            key,
+            StateEntry {
+                value,
+                data,
+                seq_no,
+                txn_time,
+            },
+        )])
+    }
+
+    fn get_nym(&mut self, req: &SJsonValue) -> VdrResult<SJsonValue> {
+        let dest = req["operation"]["dest"]
+            .as_str()
+            .ok_or_else(|| input_err("Missing dest"))?;
+        let key = SHA256::digest(dest.as_bytes());
+        let mut result = _read_result(req);
+        result["dest"] = SJsonValue::from(dest);
+        self.attach_entry(&mut result, &key, |entry| {
+            SJsonValue::from(entry.data.to_string())
+        });
+        self.attach_state_proof(&mut result, &key)?;
+        Ok(result)
+    }
+
+    fn get_attrib(&mut self, req: &SJsonValue) -> VdrResult<SJsonValue> {
+        let operation = &req["operation"];
+        let dest = operation["dest"]
+            .as_str()
+            .ok_or_else(|| input_err("Missing dest"))?;
+        let (field, name) = ["raw", "enc", "hash"]
+            .iter()
+            .find_map(|field| operation[*field].as_str().map(|name| (*field, name)))
+            .ok_or_else(|| input_err("Missing attribute name"))?;
+        let key = format!(
+            "{}:1:{}",
+            dest,
+            hex::encode(SHA256::digest(name.as_bytes()))
+        )
+        .into_bytes();
+        let mut result = _read_result(req);
+        result["dest"] = SJsonValue::from(dest);
+        result[field] = SJsonValue::from(name);
+        self.attach_entry(&mut result, &key, |entry| entry.data.clone());
+        self.attach_state_proof(&mut result, &key)?;
+        Ok(result)
+    }
+
+    fn get_schema(&mut self, req: &SJsonValue) -> VdrResult<SJsonValue> {
+        let operation = &req["operation"];
+        let (dest, name, version) = match (
+            operation["dest"].as_str(),
+            operation["data"]["name"].as_str(),
+            operation["data"]["version"].as_str(),
+        ) {
+            (Some(dest), Some(name), Some(version)) => (dest, name, version),
+            _ => return Err(input_err("Missing schema dest, name or version")),
+        };
+        let key = format!("{}:2:{}:{}", dest, name, version).into_bytes();
+        let mut result = _read_result(req);
+        result["dest"] = SJsonValue::from(dest);
+        self.attach_entry(&mut result, &key, |entry| entry.data.clone());
+        if result["data"].is_null() {
+            result["data"] = json!({"name": name, "version": version});
+        }
+        self.attach_state_proof(&mut result, &key)?;
+        Ok(result)
+    }
+
+    fn get_cred_def(&mut self, req: &SJsonValue) -> VdrResult<SJsonValue> {
+        let operation = &req["operation"];
+        let (origin, schema_ref, signature_type) = match (
+            operation["origin"].as_str(),
+            operation["ref"].as_u64(),
+            operation["signature_type"].as_str(),
+        ) {
+            (Some(origin), Some(schema_ref), Some(signature_type)) => {
+                (origin, schema_ref, signature_type)
+            }
+            _ => return Err(input_err("Missing credential definition reference")),
+        };
+        let tag = operation["tag"].as_str().unwrap_or("tag");
+        let key = format!("{}:3:{}:{}:{}", origin, signature_type, schema_ref, tag).into_bytes();
+        let mut result = _read_result(req);
+        result["origin"] = SJsonValue::from(origin);
+        result["ref"] = SJsonValue::from(schema_ref);
+        result["signature_type"] = SJsonValue::from(signature_type);
+        result["tag"] = SJsonValue::from(tag);
+        self.attach_entry(&mut result, &key, |entry| entry.data.clone());
+        self.attach_state_proof(&mut result, &key)?;
+        Ok(result)
+    }
+
+    fn get_txn(&mut self, req: &SJsonValue) -> VdrResult<SJsonValue> {
+        let operation = &req["operation"];
+        let ledger_id = operation["ledgerId"]
+            .as_u64()
+            .unwrap_or(DOMAIN_LEDGER_ID as u64) as u8;
+        let seq_no = operation["data"]
+            .as_u64()
+            .ok_or_else(|| input_err("Missing transaction sequence number"))?
+            as usize;
+        let ledger = self
+            .ledger(ledger_id)
+            .ok_or_else(|| input_err(format!("Unknown ledger: {}", ledger_id)))?;
+        let mut result = _read_result(req);
+        result["seqNo"] = SJsonValue::from(seq_no);
+        result["data"] = SJsonValue::Null;
+        if let Some(txn) = ledger.get(seq_no) {
+            let mut data = txn.clone();
+            data["auditPath"] = ledger
+                .audit_path(seq_no)
+                .unwrap_or_default()
+                .iter()
+                .map(base58::encode)
+                .collect::<Vec<_>>()
+                .into();
+            data["ledgerSize"] = SJsonValue::from(ledger.len());
+            data["rootHash"] = SJsonValue::from(base58::encode(ledger.root_hash()));
+            result["data"] = data;
+            result["state_proof"] = json!({
+                "multi_signature": self.multi_signature(ledger_id)?,
+            });
+        }
+        Ok(result)
+    }
+
+    fn attach_entry<F>(&self, result: &mut SJsonValue, key: &[u8], data: F)
+    where
+        F: FnOnce(&StateEntry) -> SJsonValue,
+    {
+        if let Some(entry) = self.domain_state.get(key) {
+            result["data"] = data(entry);
+            result["seqNo"] = entry.seq_no.clone();
+            result["txnTime"] = entry.txn_time.clone();
+        } else {
+            result["data"] = SJsonValue::Null;
+            result["seqNo"] = SJsonValue::Null;
+            result["txnTime"] = SJsonValue::Null;
+        }
+    }
+
+    fn attach_state_proof(&mut self, result: &mut SJsonValue, key: &[u8]) -> VdrResult<()> {
+        let trie = self.domain_state.trie();
+        let root_hash = trie.root_hash();
+        let proof_nodes = trie.proof(key);
+        result["state_proof"] = json!({
+            "root_hash": base58::encode(root_hash),
+            "proof_nodes": base64::encode(proof_nodes),
+            "multi_signature": self.multi_signature(DOMAIN_LEDGER_ID)?,
+        });
+        Ok(())
+    }
+
+    /// Produce a BLS multi-signature from all nodes over the current roots of a ledger.
+    fn multi_signature(&mut self, ledger_id: u8) -> VdrResult<SJsonValue> {
+        let state_root = if ledger_id == DOMAIN_LEDGER_ID {
+            self.domain_state.trie().root_hash()
+        } else {
+            Trie::default().root_hash()
+        };
+        let txn_root = self
+            .ledger(ledger_id)
+            .ok_or_else(|| input_err(format!("Unknown ledger: {}", ledger_id)))?
+            .root_hash();
+        let now = _now();
+        if let Some(signed) = self.signed_states.get(&ledger_id) {
+            if signed.state_root == state_root
+                && signed.txn_root == txn_root
+                && now < signed.timestamp + SIGNATURE_REFRESH_SECS
+            {
+                return Ok(signed.multi_signature.clone());
+            }
+        }
+
+        let value = json!({
+            "ledger_id": ledger_id,
+            "pool_state_root_hash": base58::encode(Trie::default().root_hash()),
+            "state_root_hash": base58::encode(&state_root),
+            "timestamp": now,
+            "txn_root_hash": base58::encode(&txn_root),
+        });
+        let message = rmp_serde::to_vec_named(&value)
+            .with_input_err("Can't encode multi-signature value as msgpack")?;
+        let bls_err = |err: ursa::errors::UrsaCryptoError| {
+            err_msg(
+                VdrErrorKind::Resource,
+                format!("Error creating BLS multi-signature: {}", err),
+            )
+        };
+        let signatures = self
+            .signers
+            .iter()
+            .map(|(_, sign_key)| Bls::sign(&message, sign_key).map_err(bls_err))
+            .collect::<VdrResult<Vec<_>>>()?;
+        let multi_sig =
+            MultiSignature::new(&signatures.iter().collect::<Vec<_>>()).map_err(bls_err)?;
+        let multi_signature = json!({
+            "participants": self.signers.iter().map(|(alias, _)| alias).collect::<Vec<_>>(),
+            "signature": base58::encode(multi_sig.as_bytes()),
+            "value": value,
+        });
+        self.signed_states.insert(
+            ledger_id,
+            SignedState {
+                state_root,
+                txn_root,
+                timestamp: now,
+                multi_signature: multi_signature.clone(),
+            },
+        );
+        Ok(multi_signature)
+    }
+}
+
+fn _now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time has gone backwards")
+        .as_secs()
+}
+
+fn _nack(identifier: &str, req_id: u64, err: &VdrError) -> SJsonValue {
+    json!({
+        "op": "REQNACK",
+        "identifier": identifier,
+        "reqId": req_id,
+        "reason": err.to_string(),
+    })
+}
+
+fn _read_result(req: &SJsonValue) -> SJsonValue {
+    json!({
+        "type": req["operation"]["type"],
+        "identifier": req["identifier"],
+        "reqId": req["reqId"],
+    })
+}
+
+fn _request_signature(req: &SJsonValue, identifier: &str) -> SJsonValue {
+    if let Some(signature) = req["signature"].as_str() {
+        json!({
+            "type": "ED25519",
+            "values": [{"from": identifier, "value": signature}],
+        })
+    } else if let Some(signatures) = req["signatures"].as_object() {
+        json!({
+            "type": "ED25519",
+            "values": signatures
+                .iter()
+                .map(|(from, value)| json!({"from": from, "value": value}))
+                .collect::<Vec<_>>(),
+        })
+    } else {
+        json!({})
+    }
+}
+
+// the {lsn, lut, val} wrapper used for most state values, in verifier field order
+fn _stored_value(seq_no: &SJsonValue, txn_time: &SJsonValue, val: SJsonValue) -> String {
+    let mut value = json!({});
+    value["lsn"] = seq_no.clone();
+    value["lut"] = txn_time.clone();
+    value["val"] = val;
+    value.to_string()
+}
+
+// the form of a transaction hashed into the ledger, as reconstructed by the verifier
+fn _ledger_form(txn: &SJsonValue) -> SJsonValue {
+    let mut value = json!({});
+    for field in &["txn", "txnMetadata", "ver", "reqSignature"] {
+        if !txn[*field].is_null() {
+            value[*field] = txn[*field].clone();
+        }
+    }
+    // attribute values are stored as hashes
+    if value["txn"]["type"].as_str() == Some(ATTRIB) {
+        for field in &["raw", "enc"] {
+            if let Some(attr) = value["txn"]["data"][*field].as_str() {
+                if !attr.is_empty() {
+                    value["txn"]["data"][*field] =
+                        SJsonValue::from(hex::encode(SHA256::digest(attr.as_bytes())));
+                }
+                break;
+            }
+        }
+    }
+    value
+}
@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+
+use rlp::RlpStream;
+use sha3::{Digest, Sha3_256};
+
+const RADIX: usize = 16;
+const IS_LEAF_MASK: u8 = 0x20;
+const IS_PATH_ODD_MASK: u8 = 0x10;
+
+/// An immutable Patricia Merkle trie in the encoding expected by the state proof verifier
+///
+/// Child nodes are always referenced by hash, so a proof consists of every
+/// node on the path from the root to the requested key.
+#[derive(Debug, Default)]
+pub struct Trie {
+    root: Option<TrieNode>,
+}
+
+#[derive(Debug)]
+struct TrieNode {
+    kind: NodeKind,
+    encoded: Vec<u8>,
+    hash: Vec<u8>,
+}
+
+#[derive(Debug)]
+enum NodeKind {
+    Leaf,
+    Extension { path: Vec<u8>, next: Box<TrieNode> },
+    Branch { children: Vec<Option<TrieNode>> },
+}
+
+impl Trie {
+    /// Build a trie from a set of key-value pairs.
+    pub fn build<K, V>(entries: &BTreeMap<K, V>) -> Self
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let nibbles = entries
+            .iter()
+            .map(|(key, value)| (path_to_nibbles(key.as_ref()), value.as_ref()))
+            .collect::<Vec<_>>();
+        Self {
+            root: build_node(&nibbles, 0),
+        }
+    }
+
+    /// Get the root hash of the trie.
+    pub fn root_hash(&self) -> Vec<u8> {
+        match self.root {
+            Some(ref root) => root.hash.clone(),
+            None => sha3(&blank_node()),
+        }
+    }
+
+    /// Get the RLP-encoded list of trie nodes needed to look up a key, whether or not
+    /// the key is present.
+    pub fn proof(&self, key: &[u8]) -> Vec<u8> {
+        let mut nodes = vec![];
+        match self.root {
+            Some(ref root) => collect_proof(root, &path_to_nibbles(key), &mut nodes),
+            None => nodes.push(blank_node()),
+        }
+        let mut s = RlpStream::new_list(nodes.len());
+        for node in nodes {
+            s.append_raw(&node, 1);
+        }
+        s.out().to_vec()
+    }
+}
+
+impl TrieNode {
+    fn new(kind: NodeKind, encoded: Vec<u8>) -> Self {
+        let hash = sha3(&encoded);
+        Self {
+            kind,
+            encoded,
+            hash,
+        }
+    }
+
+    fn leaf(path: &[u8], value: &[u8]) -> Self {
+        let mut s = RlpStream::new_list(2);
+        s.append(&encode_path(path, true));
+        s.append(&encode_value(value));
+        Self::new(NodeKind::Leaf, s.out().to_vec())
+    }
+
+    fn extension(path: &[u8], next: TrieNode) -> Self {
+        let mut s = RlpStream::new_list(2);
+        s.append(&encode_path(path, false));
+        s.append(&next.hash.as_slice());
+        Self::new(
+            NodeKind::Extension {
+                path: path.to_vec(),
+                next: Box::new(next),
+            },
+            s.out().to_vec(),
+        )
+    }
+
+    fn branch(children: Vec<Option<TrieNode>>, value: Option<&[u8]>) -> Self {
+        let mut s = RlpStream::new_list(RADIX + 1);
+        for child in &children {
+            if let Some(child) = child {
+                s.append(&child.hash.as_slice());
+            } else {
+                s.append_empty_data();
+            }
+        }
+        if let Some(value) = value {
+            s.append(&encode_value(value));
+        } else {
+            s.append_empty_data();
+        }
+        Self::new(NodeKind::Branch { children }, s.out().to_vec())
+    }
+}
+
+fn build_node(entries: &[(Vec<u8>, &[u8])], depth: usize) -> Option<TrieNode> {
+    match entries.len() {
+        0 => None,
+        1 => {
+            let (path, value) = &entries[0];
+            Some(TrieNode::leaf(&path[depth..], value))
+        }
+        _ => {
+            // entries are sorted, so the common prefix of the first and last covers all of them
+            let first = &entries[0].0[depth..];
+            let last = &entries[entries.len() - 1].0[depth..];
+            let common = first
+                .iter()
+                .zip(last.iter())
+                .take_while(|(a, b)| a == b)
+                .count();
+            if common > 0 {
+                let next = build_node(entries, depth + common)?;
+                return Some(TrieNode::extension(&first[..common], next));
+            }
+
+            let mut value = None;
+            let mut children: Vec<Option<TrieNode>> = (0..RADIX).map(|_| None).collect();
+            let mut start = 0;
+            if entries[0].0.len() == depth {
+                value = Some(entries[0].1);
+                start = 1;
+            }
+            while start < entries.len() {
+                let nibble = entries[start].0[depth];
+                let end = start
+                    + entries[start..]
+                        .iter()
+                        .take_while(|(path, _)| path[depth] == nibble)
+                        .count();
+                children[nibble as usize] = build_node(&entries[start..end], depth + 1);
+                start = end;
+            }
+            Some(TrieNode::branch(children, value))
+        }
+    }
+}
+
+fn collect_proof(node: &TrieNode, path: &[u8], nodes: &mut Vec<Vec<u8>>) {
+    nodes.push(node.encoded.clone());
+    match node.kind {
+        NodeKind::Leaf => (),
+        NodeKind::Extension {
+            path: ref ext_path,
+            ref next,
+        } => {
+            if path.starts_with(ext_path) {
+                collect_proof(next, &path[ext_path.len()..], nodes)
+            }
+        }
+        NodeKind::Branch { ref children } => {
+            if let Some((nibble, rest)) = path.split_first() {
+                if let Some(child) = &children[*nibble as usize] {
+                    collect_proof(child, rest, nodes)
+                }
+            }
+        }
+    }
+}
+
+fn path_to_nibbles(path: &[u8]) -> Vec<u8> {
+    let mut nibbles = Vec::with_capacity(path.len() * 2);
+    for byte in path {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0x0F);
+    }
+    nibbles
+}
+
+// hex-prefix encoding of a nibble path
+fn encode_path(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let mut flags = if is_leaf { IS_LEAF_MASK } else { 0 };
+    let mut result = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let rest = if nibbles.len() % 2 == 1 {
+        flags |= IS_PATH_ODD_MASK;
+        result.push(flags | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        result.push(flags);
+        nibbles
+    };
+    for pair in rest.chunks(2) {
+        result.push((pair[0] << 4) | pair[1]);
+    }
+    result
+}
+
+// stored values are wrapped in a single-element RLP list
+fn encode_value(value: &[u8]) -> Vec<u8> {
+    let mut s = RlpStream::new_list(1);
+    s.append(&value.to_vec());
+    s.out().to_vec()
+}
+
+fn blank_node() -> Vec<u8> {
+    let mut s = RlpStream::new();
+    s.append_empty_data();
+    s.out().to_vec()
+}
+
+fn sha3(data: &[u8]) -> Vec<u8> {
+    Sha3_256::digest(data).to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rlp::UntrustedRlp;
+
+    fn _entries() -> BTreeMap<Vec<u8>, Vec<u8>> {
+        vec![
+            ("did:1:abc", "first"),
+            ("did:1:abd", "second"),
+            ("did:2", "third"),
+            ("did:2:x", "fourth"),
+            ("other", "fifth"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.as_bytes().to_vec(), v.as_bytes().to_vec()))
+        .collect()
+    }
+
+    #[test]
+    fn trie_empty_root_and_proof() {
+        let trie = Trie::build(&BTreeMap::<Vec<u8>, Vec<u8>>::new());
+        assert_eq!(trie.root_hash(), Trie::default().root_hash());
+        assert_eq!(trie.proof(b"key"), vec![0xc1, 0x80]);
+        assert_eq!(trie.root_hash(), sha3(&[0x80]));
+    }
+
+    #[test]
+    fn trie_proof_starts_at_root() {
+        let trie = Trie::build(&_entries());
+        for key in &[&b"did:1:abc"[..], b"did:2", b"did:2:x", b"missing"] {
+            let proof = trie.proof(key);
+            let nodes = UntrustedRlp::new(&proof);
+            let first: Vec<u8> = nodes.at(0).unwrap().as_raw().to_vec();
+            assert_eq!(sha3(&first), trie.root_hash());
+        }
+    }
+
+    #[test]
+    fn trie_root_depends_on_values() {
+        let mut entries = _entries();
+        let root = Trie::build(&entries).root_hash();
+        entries.insert(b"did:2".to_vec(), b"changed".to_vec());
+        assert_ne!(Trie::build(&entries).root_hash(), root);
+    }
+
+    #[test]
+    fn trie_encode_path_works() {
+        assert_eq!(encode_path(&[], true), vec![0x20]);
+        assert_eq!(encode_path(&[1, 2, 3], true), vec![0x31, 0x23]);
+        assert_eq!(encode_path(&[1, 2], false), vec![0x00, 0x12]);
+        assert_eq!(encode_path(&[0xf], false), vec![0x1f]);
+    }
+}
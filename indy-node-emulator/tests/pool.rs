@@ -0,0 +1,340 @@
+use std::collections::HashSet;
+
+use futures_executor::block_on;
+use serde_json::json;
+
+use indy_vdr::ledger::identifiers::SchemaId;
+use indy_vdr::ledger::requests::schema::{AttributeNames, Schema, SchemaV1};
+use indy_vdr::ledger::{parse_get_nym_response, parse_get_schema_response};
+use indy_vdr::pool::helpers::{perform_get_txn, perform_ledger_request, perform_refresh};
+use indy_vdr::pool::{LocalPool, Pool, PoolBuilder, PoolRegistry, PreparedRequest, RequestResult};
+use indy_vdr::resolver::PoolResolver;
+use indy_vdr::utils::did::{generate_did, DidValue};
+use indy_vdr::utils::keys::PrivateKey;
+
+use indy_node_emulator::{Emulator, EmulatorConfig, TRUSTEE_SEED};
+
+fn _start() -> (Emulator, LocalPool) {
+    let emulator = Emulator::start(EmulatorConfig::default()).unwrap();
+    let pool = PoolBuilder::default()
+        .transactions(emulator.pool_transactions().unwrap())
+        .unwrap()
+        .into_local()
+        .unwrap();
+    (emulator, pool)
+}
+
+fn _trustee() -> (DidValue, PrivateKey) {
+    let (did, private_key, _) = generate_did(Some(TRUSTEE_SEED.as_bytes())).unwrap();
+    (DidValue((*did).to_owned()), private_key)
+}
+
+fn _sign(request: &mut PreparedRequest, key: &PrivateKey) {
+    let signature = key
+        .sign(request.get_signature_input().unwrap().as_bytes())
+        .unwrap();
+    request.set_signature(&signature).unwrap();
+}
+
+fn _reply(pool: &LocalPool, request: &PreparedRequest) -> serde_json::Value {
+    match block_on(perform_ledger_request(pool, request)).unwrap().0 {
+        RequestResult::Reply(reply) => serde_json::from_str(&reply).unwrap(),
+        RequestResult::Failed(err) => panic!("Request failed: {}", err),
+    }
+}
+
+#[test]
+fn emulator_refresh_not_required() {
+    let (_emulator, pool) = _start();
+    let (txns, _timing) = block_on(perform_refresh(&pool)).unwrap();
+    assert!(txns.is_none());
+}
+
+#[test]
+fn emulator_get_txn() {
+    let (emulator, pool) = _start();
+    let (result, _timing) = block_on(perform_get_txn(&pool, 1, 1)).unwrap();
+    let reply = match result {
+        RequestResult::Reply(reply) => serde_json::from_str::<serde_json::Value>(&reply).unwrap(),
+        RequestResult::Failed(err) => panic!("Request failed: {}", err),
+    };
+    assert_eq!(
+        reply["result"]["data"]["txn"],
+        emulator.domain_transactions()[0]["txn"]
+    );
+}
+
+#[test]
+fn emulator_get_nym_trustee() {
+    let (_emulator, pool) = _start();
+    let (trustee, _) = _trustee();
+    let request = pool
+        .get_request_builder()
+        .build_get_nym_request(None, &trustee, None, None)
+        .unwrap();
+    let reply = _reply(&pool, &request);
+    let data: serde_json::Value =
+        serde_json::from_str(reply["result"]["data"].as_str().unwrap()).unwrap();
+    assert_eq!(data["dest"], trustee.0);
+    assert_eq!(data["role"], "0");
+}
+
+#[test]
+fn emulator_write_and_read_nym() {
+    let (_emulator, pool) = _start();
+    let (trustee, trustee_key) = _trustee();
+    let (did, _, verkey) = generate_did(None).unwrap();
+    let did = DidValue((*did).to_owned());
+    let builder = pool.get_request_builder();
+
+    let mut request = builder
+        .build_nym_request(
+            &trustee,
+            &did,
+            Some(verkey.as_base58().unwrap().to_string()),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    _sign(&mut request, &trustee_key);
+    let reply = _reply(&pool, &request);
+    assert_eq!(reply["result"]["txn"]["data"]["dest"], did.0);
+
+    let request = builder
+        .build_get_nym_request(None, &did, None, None)
+        .unwrap();
+    let reply = _reply(&pool, &request);
+    let data: serde_json::Value =
+        serde_json::from_str(reply["result"]["data"].as_str().unwrap()).unwrap();
+    assert_eq!(data["identifier"], trustee.0);
+    assert_eq!(data["verkey"], verkey.as_base58().unwrap().to_string());
+}
+
+#[test]
+fn emulator_resolve_did() {
+    let (_emulator, pool) = _start();
+    let (trustee, trustee_key) = _trustee();
+    let builder = pool.get_request_builder();
+    let resolver = PoolResolver::new(pool.clone()).namespace(Some("test".to_string()));
+
+    // a legacy DID with an endpoint attribute
+    let (did, _, verkey) = generate_did(None).unwrap();
+    let did = DidValue((*did).to_owned());
+    let verkey = verkey.as_base58().unwrap().to_string();
+    let mut request = builder
+        .build_nym_request(&trustee, &did, Some(verkey.clone()), None, None, None, None)
+        .unwrap();
+    _sign(&mut request, &trustee_key);
+    _reply(&pool, &request);
+    let endpoint = json!({"endpoint": {"endpoint": "https://agent.example.com"}});
+    let mut request = builder
+        .build_attrib_request(&trustee, &did, None, Some(&endpoint), None)
+        .unwrap();
+    _sign(&mut request, &trustee_key);
+    _reply(&pool, &request);
+
+    let result = block_on(resolver.resolve(&format!("did:sov:{}", did.0))).unwrap();
+    let doc = result.did_document.unwrap();
+    assert_eq!(doc["id"], format!("did:sov:{}", did.0));
+    assert_eq!(doc["verificationMethod"][0]["publicKeyBase58"], verkey);
+    assert_eq!(doc["keyAgreement"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        doc["service"][0]["serviceEndpoint"],
+        "https://agent.example.com"
+    );
+    assert!(result.did_document_metadata.version_id.is_some());
+
+    // a did:indy DID with DID document content
+    let (did, _, verkey) = generate_did(None).unwrap();
+    let did = DidValue((*did).to_owned());
+    let content = json!({
+        "service": [{
+            "id": "#didcomm",
+            "type": "DIDCommMessaging",
+            "serviceEndpoint": "https://agent.example.com",
+        }]
+    });
+    let mut request = builder
+        .build_nym_request(
+            &trustee,
+            &did,
+            Some(verkey.as_base58().unwrap().to_string()),
+            None,
+            None,
+            Some(&content),
+            Some(2),
+        )
+        .unwrap();
+    _sign(&mut request, &trustee_key);
+    _reply(&pool, &request);
+
+    let result = block_on(resolver.resolve(&format!("did:indy:test:{}", did.0))).unwrap();
+    let doc = result.did_document.unwrap();
+    assert_eq!(doc["service"], content["service"]);
+    assert!(doc.get("keyAgreement").is_none());
+
+    // an unknown DID and a DID in another namespace
+    let (did, _, _) = generate_did(None).unwrap();
+    let result = block_on(resolver.resolve(&*did)).unwrap();
+    assert_eq!(
+        result.did_resolution_metadata.error.as_deref(),
+        Some("notFound")
+    );
+    block_on(resolver.resolve(&format!("did:indy:other:{}", &*did))).unwrap_err();
+}
+
+#[test]
+fn emulator_dereference_did_url() {
+    let (_emulator, pool) = _start();
+    let (trustee, trustee_key) = _trustee();
+    let builder = pool.get_request_builder();
+    let resolver = PoolResolver::new(pool.clone()).namespace(Some("test".to_string()));
+
+    let mut attr_names = HashSet::new();
+    attr_names.insert("name".to_string());
+    let schema = SchemaV1 {
+        id: SchemaId(format!("{}:2:npdb:4.3.4", trustee.0)),
+        name: "npdb".to_string(),
+        version: "4.3.4".to_string(),
+        attr_names: AttributeNames(attr_names),
+        seq_no: None,
+    };
+    let mut request = builder
+        .build_schema_request(&trustee, Schema::SchemaV1(schema))
+        .unwrap();
+    _sign(&mut request, &trustee_key);
+    let seq_no = _reply(&pool, &request)["result"]["txnMetadata"]["seqNo"].as_u64();
+
+    let result = block_on(resolver.dereference(&format!(
+        "did:indy:test:{}/anoncreds/v0/SCHEMA/npdb/4.3.4",
+        trustee.0
+    )))
+    .unwrap();
+    assert_eq!(
+        result.content_stream.unwrap()["attr_names"],
+        json!(["name"])
+    );
+    assert_eq!(result.content_metadata.object_type, "SCHEMA");
+    assert_eq!(result.content_metadata.seq_no, seq_no);
+    assert!(result.content_metadata.txn_time.is_some());
+    assert!(result.content_metadata.state_proof);
+
+    let result = block_on(resolver.dereference(&format!(
+        "did:indy:test:{}/anoncreds/v0/SCHEMA/npdb/1.0",
+        trustee.0
+    )))
+    .unwrap();
+    assert_eq!(result.content_stream, None);
+    assert_eq!(
+        result.dereferencing_metadata.error.as_deref(),
+        Some("notFound")
+    );
+
+    // the verification method of a DID document
+    let result =
+        block_on(resolver.dereference(&format!("did:indy:test:{}#verkey", trustee.0))).unwrap();
+    assert_eq!(
+        result.content_stream.unwrap()["id"],
+        format!("did:indy:test:{}#verkey", trustee.0)
+    );
+    assert_eq!(result.content_metadata.object_type, "NYM");
+}
+
+#[test]
+fn emulator_registry_routes_requests() {
+    let (_sovrin, sovrin_pool) = _start();
+    let (_idunion, idunion_pool) = _start();
+    let (trustee, trustee_key) = _trustee();
+    let mut registry = PoolRegistry::new();
+    registry
+        .insert("sovrin".to_string(), sovrin_pool.clone())
+        .unwrap();
+    registry
+        .insert("idunion".to_string(), idunion_pool)
+        .unwrap();
+
+    let (did, _, verkey) = generate_did(None).unwrap();
+    let did = DidValue((*did).to_owned());
+    let mut request = sovrin_pool
+        .get_request_builder()
+        .build_nym_request(
+            &trustee,
+            &did,
+            Some(verkey.as_base58().unwrap().to_string()),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    _sign(&mut request, &trustee_key);
+    _reply(&sovrin_pool, &request);
+
+    let get_nym = |pool: &LocalPool| {
+        pool.get_request_builder()
+            .build_get_nym_request(None, &did, None, None)
+    };
+
+    // an unqualified DID is found on whichever ledger registered it
+    let (namespace, result) = block_on(registry.perform_request(&did.0, get_nym)).unwrap();
+    assert_eq!(namespace, "sovrin");
+    assert!(matches!(result, RequestResult::Reply(_)));
+
+    // a did:indy DID is only looked up in its own namespace
+    let (namespace, result) =
+        block_on(registry.perform_request(&format!("did:indy:idunion:{}", did.0), get_nym))
+            .unwrap();
+    assert_eq!(namespace, "idunion");
+    match result {
+        RequestResult::Reply(reply) => {
+            let reply: serde_json::Value = serde_json::from_str(&reply).unwrap();
+            assert!(reply["result"]["data"].is_null());
+        }
+        RequestResult::Failed(err) => panic!("Request failed: {}", err),
+    }
+
+    block_on(registry.perform_request(&format!("did:indy:bcovrin:{}", did.0), get_nym))
+        .unwrap_err();
+}
+
+#[test]
+fn emulator_parse_get_responses() {
+    let (_emulator, pool) = _start();
+    let (trustee, trustee_key) = _trustee();
+    let builder = pool.get_request_builder();
+
+    let mut attr_names = HashSet::new();
+    attr_names.insert("name".to_string());
+    let schema_id = SchemaId(format!("{}:2:gvt:1.0", trustee.0));
+    let schema = SchemaV1 {
+        id: schema_id.clone(),
+        name: "gvt".to_string(),
+        version: "1.0".to_string(),
+        attr_names: AttributeNames(attr_names),
+        seq_no: None,
+    };
+    let mut request = builder
+        .build_schema_request(&trustee, Schema::SchemaV1(schema))
+        .unwrap();
+    _sign(&mut request, &trustee_key);
+    _reply(&pool, &request);
+
+    let request = builder.build_get_schema_request(None, &schema_id).unwrap();
+    let reply = _reply(&pool, &request).to_string();
+    let parsed = parse_get_schema_response(&reply).unwrap();
+    let schema = parsed.data.unwrap();
+    assert_eq!(schema.id, schema_id);
+    assert!(schema.attr_names.0.contains("name"));
+    assert!(parsed.metadata.seq_no.is_some());
+    assert!(parsed.metadata.last_txn_time.is_some());
+
+    let request = builder
+        .build_get_nym_request(None, &trustee, None, None)
+        .unwrap();
+    let reply = _reply(&pool, &request).to_string();
+    let nym = parse_get_nym_response(&reply).unwrap().data.unwrap();
+    assert_eq!(nym.dest, trustee);
+    assert!(nym.verkey.is_some());
+}
@@ -9,6 +9,7 @@ pub struct Config {
     pub port: Option<u16>,
     pub init_refresh: bool,
     pub interval_refresh: u32,
+    pub cache_dir: Option<String>,
 }
 
 pub fn load_config() -> Result<Config, String> {
@@ -53,6 +54,13 @@ pub fn load_config() -> Result<Config, String> {
                 .takes_value(true)
                 .value_name("INTERVAL")
                 .help("Set the interval in minutes between validator node refresh attempts (0 to disable refresh, default 120)"),
+        )
+        .arg(
+            Arg::with_name("cache-dir")
+                .long("cache-dir")
+                .takes_value(true)
+                .value_name("CACHE_DIR")
+                .help("Set the directory used to cache the refreshed validator pool transactions"),
         );
 
     #[cfg(unix)]
@@ -99,6 +107,7 @@ pub fn load_config() -> Result<Config, String> {
         .map(|ival| ival.parse::<u32>().map_err(|_| "Invalid refresh interval"))
         .transpose()?
         .unwrap_or(120);
+    let cache_dir = matches.value_of("cache-dir").map(str::to_owned);
 
     Ok(Config {
         genesis,
@@ -108,5 +117,6 @@ pub fn load_config() -> Result<Config, String> {
         port,
         init_refresh,
         interval_refresh,
+        cache_dir,
     })
 }
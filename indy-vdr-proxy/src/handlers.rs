@@ -271,11 +271,16 @@ async fn get_attrib<T: Pool>(pool: &T, dest: &str, raw: &str) -> VdrResult<Respo
     Ok(result.into())
 }
 
-async fn get_nym<T: Pool>(pool: &T, nym: &str) -> VdrResult<ResponseType> {
+async fn get_nym<T: Pool>(
+    pool: &T,
+    nym: &str,
+    seq_no: Option<i32>,
+    timestamp: Option<u64>,
+) -> VdrResult<ResponseType> {
     let nym = DidValue::from_str(nym)?;
     let request = pool
         .get_request_builder()
-        .build_get_nym_request(None, &nym)?;
+        .build_get_nym_request(None, &nym, seq_no, timestamp)?;
     let result = perform_ledger_request(pool, &request).await?;
     Ok(result.into())
 }
@@ -379,6 +384,18 @@ async fn submit_request<T: Pool>(pool: &T, message: Vec<u8>) -> VdrResult<Respon
     Ok(result.into())
 }
 
+/// Find a query parameter by name, producing an empty value for a flag such as `?html`
+fn query_param<'a>(query: Option<&'a str>, name: &str) -> Option<&'a str> {
+    query?.split('&').find_map(|param| {
+        let mut parts = param.splitn(2, '=');
+        if parts.next() == Some(name) {
+            Some(parts.next().unwrap_or(""))
+        } else {
+            None
+        }
+    })
+}
+
 pub async fn handle_request<T: Pool>(
     req: Request<Body>,
     state: Rc<RefCell<AppState>>,
@@ -395,9 +412,9 @@ pub async fn handle_request<T: Pool>(
                 .filter(|p| !p.is_empty())
         });
     let query = req.uri().query();
-    let format = if query == Some("html") {
+    let format = if query_param(query, "html").is_some() {
         ResponseFormat::Html
-    } else if query == Some("raw") {
+    } else if query_param(query, "raw").is_some() {
         ResponseFormat::Raw
     } else {
         if let Some(Ok(accept)) = req.headers().get("accept").map(|h| h.to_str()) {
@@ -480,7 +497,12 @@ pub async fn handle_request<T: Pool>(
         }
         (&Method::GET, "nym") => {
             if let Some(nym) = parts.next() {
-                get_nym(pool, &*nym).await
+                let seq_no = query_param(query, "seqNo").map(|val| val.parse::<i32>());
+                let timestamp = query_param(query, "timestamp").map(|val| val.parse::<u64>());
+                match (seq_no.transpose(), timestamp.transpose()) {
+                    (Ok(seq_no), Ok(timestamp)) => get_nym(pool, &*nym, seq_no, timestamp).await,
+                    _ => http_status(StatusCode::BAD_REQUEST),
+                }
             } else {
                 http_status(StatusCode::NOT_FOUND)
             }
@@ -31,7 +31,9 @@ use tokio::select;
 use tokio::signal::unix::SignalKind;
 
 use indy_vdr::common::error::prelude::*;
-use indy_vdr::pool::{helpers::perform_refresh, LocalPool, PoolBuilder, PoolTransactions};
+use indy_vdr::pool::{
+    helpers::perform_refresh, LocalPool, Pool, PoolBuilder, PoolTransactions, PoolTransactionsStore,
+};
 
 fn main() {
     let config = app::load_config().unwrap_or_else(|err| {
@@ -57,6 +59,8 @@ pub struct AppState {
     pool: Option<LocalPool>,
     last_refresh: Option<SystemTime>,
     transactions: PoolTransactions,
+    genesis: PoolTransactions,
+    store: Option<PoolTransactionsStore>,
 }
 
 #[cfg(feature = "fetch")]
@@ -95,16 +99,23 @@ async fn fetch_transactions(_genesis: String) -> VdrResult<PoolTransactions> {
     ))
 }
 
-async fn init_app_state(genesis: String) -> VdrResult<AppState> {
-    let transactions = if genesis.starts_with("http:") || genesis.starts_with("https:") {
+async fn init_app_state(genesis: String, cache_dir: Option<String>) -> VdrResult<AppState> {
+    let genesis = if genesis.starts_with("http:") || genesis.starts_with("https:") {
         fetch_transactions(genesis).await?
     } else {
         PoolTransactions::from_json_file(genesis.as_str())?
     };
+    let store = cache_dir.map(PoolTransactionsStore::new);
+    let transactions = match &store {
+        Some(store) => store.load_or_genesis(genesis.clone())?,
+        None => genesis.clone(),
+    };
     let state = AppState {
         pool: None,
         last_refresh: None,
         transactions,
+        genesis,
+        store,
     };
     Ok(state)
 }
@@ -196,12 +207,18 @@ async fn refresh_pool(
     state.borrow_mut().last_refresh.replace(SystemTime::now());
 
     if let Some(txns) = txns {
-        let builder = {
-            let pool_txns = &mut state.borrow_mut().transactions;
-            pool_txns.extend_from_json(&txns)?;
-            PoolBuilder::default().transactions(pool_txns.clone())?
-        };
-        Ok(Some(builder.into_local()?))
+        // the updated pool shares the networker and open connections of the current instance
+        let mut upd_pool = pool.clone();
+        upd_pool.apply_transactions(&txns)?;
+        let transactions = PoolTransactions::from(upd_pool.get_merkle_tree());
+        let mut state = state.borrow_mut();
+        if let Some(store) = &state.store {
+            if let Err(err) = store.store(&state.genesis, &transactions) {
+                log::warn!("Error storing refreshed pool transactions: {}", err);
+            }
+        }
+        state.transactions = transactions;
+        Ok(Some(upd_pool))
     } else {
         Ok(None)
     }
@@ -209,7 +226,7 @@ async fn refresh_pool(
 
 async fn init_server(config: app::Config) -> Result<(), String> {
     let state = Rc::new(RefCell::new(
-        init_app_state(config.genesis.clone())
+        init_app_state(config.genesis.clone(), config.cache_dir.clone())
             .await
             .map_err(|err| format!("Error loading config: {}", err))?,
     ));
@@ -0,0 +1,92 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use serde_json::{json, Value as SJsonValue};
+use ursa::bls::{Bls, Generator, MultiSignature, SignKey, VerKey};
+
+use indy_vdr::pool::{VerifierInfo, VerifierKey, Verifiers};
+use indy_vdr::state_proof::{verify_multi_signature, SignatureCache};
+
+// The generator used by the library when verifying multi-signatures
+const GENERATOR: &str = "3LHpUjiyFC2q2hD7MnwwNmVXiuaFbQx2XkAFJWzswCjgN1utjsCeLzHsKk1nJvFEaS4fcrUmVAkdhtPCYbrVyATZcmzwJReTcJqwqBCPTmTQ9uWPwz6rEncKb2pYYYFcdHa8N17HzVyTqKfgPi4X9pMetfT3A5xCHq54R2pDNYWVLDX";
+
+const ROOT_HASH: &str = "7Wdj3rrMCZ1R1M78H4xK5jxikmdUUGW2kbfJQ1HoEpK";
+
+fn signed_state(node_count: usize) -> (Verifiers, SJsonValue) {
+    let gen = Generator::from_bytes(&indy_utils::base58::decode(GENERATOR).unwrap()).unwrap();
+    let sign_keys = (0..node_count)
+        .map(|idx| SignKey::new(Some(&[idx as u8 + 1; 32][..])).unwrap())
+        .collect::<Vec<_>>();
+    let aliases = (0..node_count)
+        .map(|idx| format!("Node{}", idx + 1))
+        .collect::<Vec<_>>();
+    let verifiers = aliases
+        .iter()
+        .zip(sign_keys.iter())
+        .map(|(alias, sign_key)| {
+            let ver_key = VerKey::new(&gen, sign_key).unwrap();
+            (
+                alias.clone(),
+                VerifierInfo {
+                    client_addr: String::new(),
+                    node_addr: String::new(),
+                    public_key: String::new(),
+                    enc_key: vec![],
+                    bls_key: Some(VerifierKey::from_bytes(ver_key.as_bytes()).unwrap()),
+                },
+            )
+        })
+        .collect();
+
+    let value = json!({
+        "ledger_id": 1,
+        "pool_state_root_hash": "",
+        "state_root_hash": ROOT_HASH,
+        "timestamp": 1000,
+        "txn_root_hash": "",
+    });
+    let message = rmp_serde::to_vec_named(&value).unwrap();
+    let signatures = sign_keys
+        .iter()
+        .map(|sign_key| Bls::sign(&message, sign_key).unwrap())
+        .collect::<Vec<_>>();
+    let multi_sig = MultiSignature::new(&signatures.iter().collect::<Vec<_>>()).unwrap();
+    let multi_signature = json!({
+        "participants": aliases,
+        "signature": indy_utils::base58::encode(multi_sig.as_bytes()),
+        "value": value,
+    });
+    (verifiers, multi_signature)
+}
+
+fn bench_verify_multi_signature(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_multi_signature");
+    for node_count in [4, 25].iter() {
+        let (verifiers, multi_signature) = signed_state(*node_count);
+        group.bench_with_input(
+            BenchmarkId::new("uncached", node_count),
+            &multi_signature,
+            |b, multi_signature| {
+                b.iter(|| {
+                    verify_multi_signature(multi_signature, ROOT_HASH, &verifiers, None)
+                        .unwrap()
+                        .unwrap()
+                })
+            },
+        );
+        let cache = SignatureCache::new(256);
+        group.bench_with_input(
+            BenchmarkId::new("cached", node_count),
+            &multi_signature,
+            |b, multi_signature| {
+                b.iter(|| {
+                    verify_multi_signature(multi_signature, ROOT_HASH, &verifiers, Some(&cache))
+                        .unwrap()
+                        .unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_verify_multi_signature);
+criterion_main!(benches);
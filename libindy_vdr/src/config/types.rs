@@ -30,6 +30,22 @@ pub struct PoolConfig {
     /// The socks proxy host name and port for ZMQ (example: proxy1.intranet.company.com:1080)
     #[serde(default = "PoolConfig::default_socks_proxy")]
     pub socks_proxy: Option<String>,
+    /// The number of consecutive failures before a node is temporarily blacklisted (0 to disable)
+    #[serde(default = "PoolConfig::default_node_blacklist_threshold")]
+    pub node_blacklist_threshold: usize,
+    /// The initial time in seconds before a blacklisted node is probed again
+    #[serde(default = "PoolConfig::default_node_blacklist_timeout")]
+    pub node_blacklist_timeout: i64,
+    /// The interval in seconds between automatic validator pool refreshes by a `PoolRunner` (0 to disable)
+    #[serde(default = "PoolConfig::default_refresh_interval")]
+    pub refresh_interval: u64,
+    /// Whether a `PoolRunner` should refresh the validator pool after more nodes time out on a
+    /// request than the pool can tolerate as faulty
+    #[serde(default = "PoolConfig::default_refresh_on_timeout")]
+    pub refresh_on_timeout: bool,
+    /// The number of verified BLS multi-signatures to cache for state proof replies (0 to disable)
+    #[serde(default = "PoolConfig::default_signature_cache_size")]
+    pub signature_cache_size: usize,
 }
 
 impl Validatable for PoolConfig {
@@ -52,6 +68,9 @@ impl Validatable for PoolConfig {
         if self.request_read_nodes == 0 {
             return Err(invalid!("`request_read_nodes` must be greater than 0"));
         }
+        if self.node_blacklist_timeout <= 0 {
+            return Err(invalid!("`node_blacklist_timeout` must be greater than 0"));
+        }
         Ok(())
     }
 }
@@ -96,6 +115,31 @@ impl PoolConfig {
     pub fn default_socks_proxy() -> Option<String> {
         None
     }
+
+    /// The default number of consecutive failures before blacklisting a node
+    pub fn default_node_blacklist_threshold() -> usize {
+        constants::DEFAULT_NODE_BLACKLIST_THRESHOLD
+    }
+
+    /// The default initial time before probing a blacklisted node
+    pub fn default_node_blacklist_timeout() -> i64 {
+        constants::DEFAULT_NODE_BLACKLIST_TIMEOUT
+    }
+
+    /// The default interval between automatic pool refreshes (disabled)
+    pub fn default_refresh_interval() -> u64 {
+        constants::DEFAULT_REFRESH_INTERVAL
+    }
+
+    /// The default for refreshing the pool after a request timeout (disabled)
+    pub fn default_refresh_on_timeout() -> bool {
+        constants::DEFAULT_REFRESH_ON_TIMEOUT
+    }
+
+    /// The default number of verified BLS multi-signatures to cache
+    pub fn default_signature_cache_size() -> usize {
+        constants::DEFAULT_SIGNATURE_CACHE_SIZE
+    }
 }
 
 impl Default for PoolConfig {
@@ -109,6 +153,11 @@ impl Default for PoolConfig {
             conn_active_timeout: Self::default_conn_active_timeout(),
             request_read_nodes: Self::default_request_read_nodes(),
             socks_proxy: Self::default_socks_proxy(),
+            node_blacklist_threshold: Self::default_node_blacklist_threshold(),
+            node_blacklist_timeout: Self::default_node_blacklist_timeout(),
+            refresh_interval: Self::default_refresh_interval(),
+            refresh_on_timeout: Self::default_refresh_on_timeout(),
+            signature_cache_size: Self::default_signature_cache_size(),
         }
     }
 }
@@ -1,12 +1,12 @@
 use crate::common::error::prelude::*;
 use crate::pool::{
-    PoolBuilder, PoolRunner, PoolTransactions, RequestMethod, RequestResult, TimingResult,
+    PoolBuilder, PoolRunner, PoolTransactions, PoolTransactionsStore, RequestMethod, RequestResult,
+    TimingResult,
 };
 
 use std::collections::{BTreeMap, HashMap};
 use std::os::raw::c_char;
 use std::sync::RwLock;
-use std::thread;
 
 use ffi_support::{rust_string_to_c, FfiStr};
 use once_cell::sync::Lazy;
@@ -28,6 +28,8 @@ struct PoolCreateParams {
     pub transactions_path: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub node_weights: Option<HashMap<String, f32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_path: Option<String>,
 }
 
 #[no_mangle]
@@ -50,7 +52,13 @@ pub extern "C" fn indy_vdr_pool_create(params: FfiStr, handle_p: *mut usize) ->
         };
         let builder = {
             let gcfg = read_lock!(POOL_CONFIG)?;
-            PoolBuilder::from(gcfg.clone()).transactions(txns)?.node_weights(params.node_weights)
+            PoolBuilder::from(gcfg.clone()).node_weights(params.node_weights)
+        };
+        // refreshed transactions are stored by the pool runner when a cache is provided
+        let builder = if let Some(path) = params.cache_path {
+            builder.transactions_store(PoolTransactionsStore::new(path), txns)?
+        } else {
+            builder.transactions(txns)?
         };
         let pool = builder.into_runner()?;
         let handle = PoolHandle::next();
@@ -63,34 +71,6 @@ pub extern "C" fn indy_vdr_pool_create(params: FfiStr, handle_p: *mut usize) ->
     }
 }
 
-fn handle_pool_refresh(
-    pool_handle: PoolHandle,
-    old_txns: Vec<String>,
-    new_txns: Vec<String>,
-) -> ErrorCode {
-    catch_err! {
-        debug!("Adding {} new pool transactions", new_txns.len());
-        let mut txns = PoolTransactions::from_json_transactions(old_txns)?;
-        txns.extend_from_json(&new_txns)?;
-        let builder = {
-            let gcfg = read_lock!(POOL_CONFIG)?;
-            PoolBuilder::from(gcfg.clone())
-        };
-        let pool = builder.transactions(txns)?.into_runner()?;
-        let mut pools = write_lock!(POOLS)?;
-        if !pools.contains_key(&pool_handle) {
-            let error: VdrError = (VdrErrorKind::Unexpected, "Pool was freed before refresh completed").into();
-            let code = ErrorCode::from(error.kind());
-            set_last_error(Some(error));
-            Ok(code)
-        } else {
-            pools.insert(pool_handle, pool);
-            // previous pool instance will now be dropped
-            Ok(ErrorCode::Success)
-        }
-    }
-}
-
 #[no_mangle]
 pub extern "C" fn indy_vdr_pool_refresh(
     pool_handle: usize,
@@ -106,19 +86,12 @@ pub extern "C" fn indy_vdr_pool_refresh(
         pool.refresh(Box::new(
             move |result| {
                 let errcode = match result {
-                    Ok((old_txns, new_txns, _timing)) => {
+                    Ok((_old_txns, new_txns, _timing)) => {
+                        // new transactions have already been applied by the pool runner
                         if let Some(new_txns) = new_txns {
-                            // We must spawn a new thread here because this callback
-                            // is being run in the PoolRunner's thread, and if we drop
-                            // the instance now it will create a deadlock
-                            thread::spawn(move || {
-                                let result = handle_pool_refresh(PoolHandle(pool_handle), old_txns, new_txns);
-                                cb(cb_id, result)
-                            });
-                            return
-                        } else {
-                            ErrorCode::Success
+                            debug!("Applied {} new pool transactions", new_txns.len());
                         }
+                        ErrorCode::Success
                     },
                     Err(err) => {
                         let code = ErrorCode::from(err.kind());
@@ -132,6 +105,29 @@ pub extern "C" fn indy_vdr_pool_refresh(
     }
 }
 
+#[no_mangle]
+pub extern "C" fn indy_vdr_pool_add_refresh_listener(
+    pool_handle: usize,
+    cb: Option<extern "C" fn(cb_id: usize, changes: *const c_char)>,
+    cb_id: usize,
+) -> ErrorCode {
+    catch_err! {
+        trace!("Add pool refresh listener: {}", pool_handle);
+        let cb = cb.ok_or_else(|| input_err("No callback provided"))?;
+        let pools = read_lock!(POOLS)?;
+        let pool = pools.get(&PoolHandle(pool_handle))
+            .ok_or_else(|| input_err("Unknown pool handle"))?;
+        pool.add_refresh_listener(Box::new(
+            move |changes| {
+                match serde_json::to_string(changes) {
+                    Ok(changes) => cb(cb_id, rust_string_to_c(changes)),
+                    Err(err) => warn!("Error serializing pool changes: {}", err)
+                }
+            }))?;
+        Ok(ErrorCode::Success)
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn indy_vdr_pool_get_status(
     pool_handle: usize,
@@ -1,6 +1,6 @@
 use crate::common::error::prelude::*;
 use crate::ledger::{RequestBuilder, TxnAuthrAgrmtAcceptanceData};
-use crate::pool::PreparedRequest;
+use crate::pool::{ConsensusPolicy, PreparedRequest};
 use crate::utils::did::DidValue;
 use crate::utils::Qualifiable;
 
@@ -114,6 +114,26 @@ pub extern "C" fn indy_vdr_request_get_signature_input(
     }
 }
 
+#[no_mangle]
+pub extern "C" fn indy_vdr_request_set_consensus_policy(
+    request_handle: usize,
+    policy: FfiStr, // optional
+) -> ErrorCode {
+    catch_err! {
+        trace!("Set request consensus policy: {}", request_handle);
+        let policy = policy
+            .as_opt_str()
+            .map(serde_json::from_str::<ConsensusPolicy>)
+            .transpose()
+            .with_input_err("Invalid consensus policy format")?;
+        let mut reqs = write_lock!(REQUESTS)?;
+        let req = reqs.get_mut(&RequestHandle(request_handle))
+            .ok_or_else(|| input_err("Unknown request handle"))?;
+        req.set_consensus_policy(policy)?;
+        Ok(ErrorCode::Success)
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn indy_vdr_request_set_endorser(
     request_handle: usize,
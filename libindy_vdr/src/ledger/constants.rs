@@ -101,6 +101,9 @@ pub const RS_PRES_DEF_TYPE_VALUE: &str = "pdf";
 
 pub const ROLES: [&str; 4] = [TRUSTEE, STEWARD, ENDORSER, NETWORK_MONITOR];
 
+// DID versions of a NYM: no self-certification check, did:sov and did:indy
+pub const NYM_VERSIONS: [i32; 3] = [0, 1, 2];
+
 pub fn txn_name_to_code(txn: &str) -> Option<&str> {
     if REQUESTS.contains(&txn) {
         return Some(txn);
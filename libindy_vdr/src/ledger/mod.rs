@@ -22,5 +22,16 @@ pub mod requests;
 /// Helpers for constructing ledger requests
 mod request_builder;
 
+/// Typed parsers for the replies to ledger read requests
+pub mod responses;
+
 pub use request_builder::RequestBuilder;
 pub(crate) use requests::author_agreement::TxnAuthrAgrmtAcceptanceData;
+pub use responses::{
+    parse_get_acceptance_mechanisms_response, parse_get_attrib_response,
+    parse_get_auth_rule_response, parse_get_cred_def_response, parse_get_nym_response,
+    parse_get_revoc_reg_def_response, parse_get_revoc_reg_delta_response,
+    parse_get_revoc_reg_response, parse_get_schema_response,
+    parse_get_txn_author_agreement_response, parse_get_txn_response, LedgerResponse,
+    ResponseMetadata,
+};
@@ -45,7 +45,7 @@ use super::requests::txn::GetTxnOperation;
 use super::requests::validator_info::GetValidatorInfoOperation;
 use super::requests::{Request, RequestType};
 
-use super::constants::txn_name_to_code;
+use super::constants::{txn_name_to_code, NYM_VERSIONS};
 
 fn datetime_to_date_timestamp(time: u64) -> u64 {
     const SEC_IN_DAY: u64 = 86400;
@@ -122,6 +122,9 @@ impl RequestBuilder {
     }
 
     /// Build a `NYM` transaction request
+    ///
+    /// The DID document content and DID version are supported by Indy Node 1.13 and later.
+    #[allow(clippy::too_many_arguments)]
     pub fn build_nym_request(
         &self,
         identifier: &DidValue,
@@ -129,20 +132,51 @@ impl RequestBuilder {
         verkey: Option<String>,
         alias: Option<String>,
         role: Option<String>,
+        diddoc_content: Option<&SJsonValue>,
+        version: Option<i32>,
     ) -> VdrResult<PreparedRequest> {
         let role = role_to_code(role)?;
-        let operation = NymOperation::new(dest.to_short(), verkey, alias, role);
+        let diddoc_content = match diddoc_content {
+            Some(content) if content.is_object() => Some(content.to_string()),
+            Some(_) => return Err(input_err("DID document content must be a JSON object")),
+            None => None,
+        };
+        if let Some(version) = version {
+            if !NYM_VERSIONS.contains(&version) {
+                return Err(input_err(format!("Invalid DID version: {}", version)));
+            }
+        }
+        let operation = NymOperation::new(
+            dest.to_short(),
+            verkey,
+            alias,
+            role,
+            diddoc_content,
+            version,
+        );
         self.build(operation, Some(identifier))
     }
 
     /// Build a `GET_NYM` transaction request
+    ///
+    /// A historical version of the DID may be requested by sequence number or timestamp.
     pub fn build_get_nym_request(
         &self,
         identifier: Option<&DidValue>,
         dest: &DidValue,
+        seq_no: Option<i32>,
+        timestamp: Option<u64>,
     ) -> VdrResult<PreparedRequest> {
+        if seq_no.is_some() && timestamp.is_some() {
+            return Err(input_err(
+                "Only one of sequence number and timestamp may be provided",
+            ));
+        }
+        if seq_no.map_or(false, |seq_no| seq_no <= 0) {
+            return Err(input_err("Sequence number must be greater than 0"));
+        }
         let dest = dest.to_short();
-        let operation = GetNymOperation::new(dest.clone());
+        let operation = GetNymOperation::new(dest.clone(), seq_no, timestamp);
         self.build(operation, identifier)
     }
 
@@ -699,7 +733,7 @@ mod tests {
             request_builder: RequestBuilder,
         ) {
             let request = request_builder
-                .build_nym_request(&_identifier(), &_dest(), None, None, None)
+                .build_nym_request(&_identifier(), &_dest(), None, None, None, None, None)
                 .unwrap();
 
             assert_eq!(request.txn_type, constants::NYM);
@@ -711,7 +745,7 @@ mod tests {
             request_builder: RequestBuilder,
         ) {
             let request = request_builder
-                .build_get_nym_request(None, &_dest())
+                .build_get_nym_request(None, &_dest(), None, None)
                 .unwrap();
 
             assert_eq!(request.txn_type, constants::GET_NYM);
@@ -727,6 +761,42 @@ mod tests {
             );
         }
 
+        #[rstest]
+        fn test_prepared_request_from_request_json_for_historical_get_nym(
+            request_builder: RequestBuilder,
+        ) {
+            let request = request_builder
+                .build_get_nym_request(None, &_dest(), None, Some(123456789))
+                .unwrap();
+            assert!(matches!(
+                request.method,
+                RequestMethod::BuiltinStateProof {
+                    sp_timestamps: (None, Some(123456789)),
+                    ..
+                }
+            ));
+
+            let request = request_builder
+                .build_get_nym_request(None, &_dest(), Some(10), None)
+                .unwrap();
+            assert!(matches!(
+                request.method,
+                RequestMethod::BuiltinStateProof {
+                    sp_timestamps: (None, Some(0)),
+                    ..
+                }
+            ));
+
+            let request = PreparedRequest::from_request_json(request.req_json.to_string()).unwrap();
+            assert!(matches!(
+                request.method,
+                RequestMethod::BuiltinStateProof {
+                    sp_timestamps: (None, Some(0)),
+                    ..
+                }
+            ));
+        }
+
         #[rstest]
         fn test_prepared_request_from_request_json_for_get_request_with_single_timestamp(
             request_builder: RequestBuilder,
@@ -921,7 +991,7 @@ mod tests {
     )]
     fn test_prepare_request_for_different_protocol_versions(protocol_version: ProtocolVersion) {
         let request = RequestBuilder::new(protocol_version.clone())
-            .build_get_nym_request(None, &_dest())
+            .build_get_nym_request(None, &_dest(), None, None)
             .unwrap();
 
         assert_eq!(request.protocol_version, protocol_version.clone());
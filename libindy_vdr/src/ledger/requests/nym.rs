@@ -17,6 +17,10 @@ pub struct NymOperation {
     pub alias: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub role: Option<::serde_json::Value>,
+    #[serde(rename = "diddocContent", skip_serializing_if = "Option::is_none")]
+    pub diddoc_content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<i32>,
 }
 
 impl NymOperation {
@@ -25,6 +29,8 @@ impl NymOperation {
         verkey: Option<String>,
         alias: Option<String>,
         role: Option<::serde_json::Value>,
+        diddoc_content: Option<String>,
+        version: Option<i32>,
     ) -> NymOperation {
         NymOperation {
             _type: Self::get_txn_type().to_string(),
@@ -32,6 +38,8 @@ impl NymOperation {
             verkey,
             alias,
             role,
+            diddoc_content,
+            version,
         }
     }
 }
@@ -43,17 +51,28 @@ impl RequestType for NymOperation {
 }
 
 #[derive(Serialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct GetNymOperation {
     #[serde(rename = "type")]
     pub _type: String,
     pub dest: ShortDidValue,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seq_no: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
 }
 
 impl GetNymOperation {
-    pub fn new(dest: ShortDidValue) -> GetNymOperation {
+    pub fn new(
+        dest: ShortDidValue,
+        seq_no: Option<i32>,
+        timestamp: Option<u64>,
+    ) -> GetNymOperation {
         GetNymOperation {
             _type: Self::get_txn_type().to_string(),
             dest,
+            seq_no,
+            timestamp,
         }
     }
 }
@@ -67,6 +86,15 @@ impl RequestType for GetNymOperation {
         let hash = SHA256::digest(self.dest.as_bytes());
         Ok(Some(hash))
     }
+
+    fn get_sp_timestamps(&self) -> VdrResult<(Option<u64>, Option<u64>)> {
+        // a lookup by sequence number is not subject to the freshness check
+        Ok(match (self.seq_no, self.timestamp) {
+            (_, Some(timestamp)) => (None, Some(timestamp)),
+            (Some(_), None) => (None, Some(0)),
+            (None, None) => (None, None),
+        })
+    }
 }
 
 pub fn role_to_code(role: Option<String>) -> VdrResult<Option<serde_json::Value>> {
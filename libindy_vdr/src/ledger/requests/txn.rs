@@ -28,7 +28,8 @@ impl RequestType for GetTxnOperation {
     }
 
     fn get_sp_key(&self, _protocol_version: ProtocolVersion) -> VdrResult<Option<Vec<u8>>> {
-        Ok(Some(self.data.to_string().into_bytes()))
+        let key = format!("{}:{}", self.ledger_id, self.data);
+        Ok(Some(key.into_bytes()))
     }
 
     fn get_sp_timestamps(&self) -> VdrResult<(Option<u64>, Option<u64>)> {
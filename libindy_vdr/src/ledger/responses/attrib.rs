@@ -0,0 +1,93 @@
+use serde_json::Value as SJsonValue;
+
+use super::{parse_reply_result, LedgerResponse, ResponseMetadata};
+use crate::common::error::prelude::*;
+use crate::ledger::constants::GET_ATTR;
+use crate::utils::did::DidValue;
+
+/// The value of an attribute stored on the ledger
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AttribValue {
+    /// A raw attribute, as a JSON object keyed by the attribute name
+    Raw(SJsonValue),
+    /// The hash of an attribute stored outside of the ledger
+    Hash(String),
+    /// An encrypted attribute
+    Enc(String),
+}
+
+/// The ledger data of an attribute
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AttribData {
+    /// The DID the attribute belongs to
+    pub dest: DidValue,
+    /// The attribute value
+    pub value: AttribValue,
+}
+
+/// Parse the reply to a `GET_ATTRIB` request
+pub fn parse_get_attrib_response(response: &str) -> VdrResult<LedgerResponse<AttribData>> {
+    let result = parse_reply_result(response, GET_ATTR)?;
+    let metadata = ResponseMetadata::from_result(&result);
+    let data = match result["data"].as_str() {
+        Some(data) => data,
+        None => return Ok(LedgerResponse::not_found(metadata)),
+    };
+    let dest = result["dest"]
+        .as_str()
+        .ok_or_else(|| input_err("Missing dest in GET_ATTRIB response"))?;
+    let value = if !result["raw"].is_null() {
+        AttribValue::Raw(serde_json::from_str(data).with_input_err("Invalid raw attribute")?)
+    } else if !result["hash"].is_null() {
+        AttribValue::Hash(data.to_string())
+    } else if !result["enc"].is_null() {
+        AttribValue::Enc(data.to_string())
+    } else {
+        return Err(input_err("Missing attribute type in GET_ATTRIB response"));
+    };
+    let attrib = AttribData {
+        dest: DidValue(dest.to_string()),
+        value,
+    };
+    Ok(LedgerResponse::new(Some(attrib), metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_get_attrib_response() {
+        let response = json!({
+            "op": "REPLY",
+            "result": {
+                "type": GET_ATTR,
+                "dest": "V4SGRU86Z58d6TV7PBUe6f",
+                "raw": "endpoint",
+                "data": "{\"endpoint\":{\"ha\":\"127.0.0.1:5555\"}}",
+                "seqNo": 15,
+                "txnTime": 1_600_000_000,
+            },
+        })
+        .to_string();
+        let parsed = parse_get_attrib_response(&response).unwrap();
+        assert_eq!(
+            parsed.data.unwrap().value,
+            AttribValue::Raw(json!({"endpoint": {"ha": "127.0.0.1:5555"}}))
+        );
+        assert_eq!(parsed.metadata.seq_no, Some(15));
+
+        let response = json!({
+            "op": "REPLY",
+            "result": {
+                "type": GET_ATTR,
+                "dest": "V4SGRU86Z58d6TV7PBUe6f",
+                "hash": "abcd",
+                "data": null,
+            },
+        })
+        .to_string();
+        assert_eq!(parse_get_attrib_response(&response).unwrap().data, None);
+    }
+}
@@ -0,0 +1,56 @@
+use super::{parse_reply_result, parse_result_data, LedgerResponse, ResponseMetadata};
+use crate::common::error::prelude::*;
+use crate::ledger::constants::GET_AUTH_RULE;
+use crate::ledger::requests::auth_rule::AuthRule;
+
+/// Parse the reply to a `GET_AUTH_RULE` request
+pub fn parse_get_auth_rule_response(response: &str) -> VdrResult<LedgerResponse<Vec<AuthRule>>> {
+    let result = parse_reply_result(response, GET_AUTH_RULE)?;
+    let metadata = ResponseMetadata::from_result(&result);
+    let rules = match parse_result_data(&result)? {
+        Some(data) => serde_json::from_value(data)
+            .with_input_err("Invalid auth rules in GET_AUTH_RULE response")?,
+        None => return Ok(LedgerResponse::not_found(metadata)),
+    };
+    Ok(LedgerResponse::new(Some(rules), metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::requests::auth_rule::Constraint;
+
+    #[test]
+    fn test_parse_get_auth_rule_response() {
+        let response = json!({
+            "op": "REPLY",
+            "result": {
+                "type": GET_AUTH_RULE,
+                "data": [{
+                    "auth_type": "1",
+                    "auth_action": "ADD",
+                    "field": "role",
+                    "new_value": "101",
+                    "constraint": {
+                        "constraint_id": "ROLE",
+                        "role": "0",
+                        "sig_count": 1,
+                        "need_to_be_owner": false,
+                        "metadata": {},
+                    },
+                }],
+            },
+        })
+        .to_string();
+        let rules = parse_get_auth_rule_response(&response)
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].auth_action, "ADD");
+        match &rules[0].constraint {
+            Constraint::RoleConstraint(constraint) => assert_eq!(constraint.sig_count, 1),
+            constraint => panic!("Unexpected constraint: {:?}", constraint),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use super::{parse_reply_result, parse_result_data, LedgerResponse, ResponseMetadata};
+use crate::common::error::prelude::*;
+use crate::ledger::constants::{GET_TXN_AUTHR_AGRMT, GET_TXN_AUTHR_AGRMT_AML};
+use crate::ledger::requests::author_agreement::AcceptanceMechanisms;
+
+/// A transaction author agreement registered on the ledger
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxnAuthorAgreement {
+    /// The text of the agreement
+    pub text: String,
+    /// The version of the agreement
+    pub version: String,
+    /// The digest of the agreement, used when accepting it
+    #[serde(default)]
+    pub digest: Option<String>,
+    /// The time the agreement was ratified, in seconds since the epoch
+    #[serde(default)]
+    pub ratification_ts: Option<u64>,
+    /// The time the agreement was retired, in seconds since the epoch
+    #[serde(default)]
+    pub retirement_ts: Option<u64>,
+}
+
+/// A list of acceptance mechanisms for transaction author agreements registered on the ledger
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptanceMechanismList {
+    /// The acceptance mechanisms and their descriptions
+    pub aml: AcceptanceMechanisms,
+    /// The version of the list
+    pub version: String,
+    /// The context information of the list
+    #[serde(default)]
+    pub aml_context: Option<String>,
+}
+
+/// Parse the reply to a `GET_TXN_AUTHR_AGRMT` request
+pub fn parse_get_txn_author_agreement_response(
+    response: &str,
+) -> VdrResult<LedgerResponse<TxnAuthorAgreement>> {
+    let result = parse_reply_result(response, GET_TXN_AUTHR_AGRMT)?;
+    let metadata = ResponseMetadata::from_result(&result);
+    let taa = match parse_result_data(&result)? {
+        Some(data) => serde_json::from_value(data)
+            .with_input_err("Invalid agreement in GET_TXN_AUTHR_AGRMT response")?,
+        None => return Ok(LedgerResponse::not_found(metadata)),
+    };
+    Ok(LedgerResponse::new(Some(taa), metadata))
+}
+
+/// Parse the reply to a `GET_TXN_AUTHR_AGRMT_AML` request
+pub fn parse_get_acceptance_mechanisms_response(
+    response: &str,
+) -> VdrResult<LedgerResponse<AcceptanceMechanismList>> {
+    let result = parse_reply_result(response, GET_TXN_AUTHR_AGRMT_AML)?;
+    let metadata = ResponseMetadata::from_result(&result);
+    let aml = match parse_result_data(&result)? {
+        Some(data) => serde_json::from_value(data)
+            .with_input_err("Invalid acceptance mechanisms in GET_TXN_AUTHR_AGRMT_AML response")?,
+        None => return Ok(LedgerResponse::not_found(metadata)),
+    };
+    Ok(LedgerResponse::new(Some(aml), metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_get_txn_author_agreement_response() {
+        let response = json!({
+            "op": "REPLY",
+            "result": {
+                "type": GET_TXN_AUTHR_AGRMT,
+                "data": {
+                    "text": "some agreement text",
+                    "version": "1.0",
+                    "digest": "abcd",
+                    "ratification_ts": 1_600_000_000,
+                },
+                "seqNo": 3,
+                "txnTime": 1_600_000_000,
+            },
+        })
+        .to_string();
+        let parsed = parse_get_txn_author_agreement_response(&response).unwrap();
+        let taa = parsed.data.unwrap();
+        assert_eq!(taa.version, "1.0");
+        assert_eq!(taa.ratification_ts, Some(1_600_000_000));
+        assert_eq!(taa.retirement_ts, None);
+    }
+
+    #[test]
+    fn test_parse_get_acceptance_mechanisms_response() {
+        let response = json!({
+            "op": "REPLY",
+            "result": {
+                "type": GET_TXN_AUTHR_AGRMT_AML,
+                "data": {
+                    "aml": {"for_session": "Accepted for the session"},
+                    "version": "1.0",
+                    "amlContext": "http://aml-context-descr",
+                },
+            },
+        })
+        .to_string();
+        let aml = parse_get_acceptance_mechanisms_response(&response)
+            .unwrap()
+            .data
+            .unwrap();
+        assert!(aml.aml.0.contains_key("for_session"));
+        assert_eq!(aml.aml_context.as_deref(), Some("http://aml-context-descr"));
+    }
+}
@@ -0,0 +1,108 @@
+use super::{parse_reply_result, parse_result_data, LedgerResponse, ResponseMetadata};
+use crate::common::error::prelude::*;
+use crate::ledger::constants::GET_CRED_DEF;
+use crate::ledger::requests::cred_def::{CredentialDefinitionV1, CL_SIGNATURE_TYPE};
+
+/// Parse the reply to a `GET_CRED_DEF` request
+///
+/// The credential definition identifier is reconstructed from the `origin`, `ref` and `tag`
+/// fields of the reply, and the schema identifier is the sequence number of the schema.
+pub fn parse_get_cred_def_response(
+    response: &str,
+) -> VdrResult<LedgerResponse<CredentialDefinitionV1>> {
+    let result = parse_reply_result(response, GET_CRED_DEF)?;
+    let metadata = ResponseMetadata::from_result(&result);
+    let data = match parse_result_data(&result)? {
+        Some(data) => data,
+        None => return Ok(LedgerResponse::not_found(metadata)),
+    };
+    let (origin, schema_ref) = match (result["origin"].as_str(), result["ref"].as_u64()) {
+        (Some(origin), Some(schema_ref)) => (origin, schema_ref),
+        _ => return Err(input_err("Missing origin or ref in GET_CRED_DEF response")),
+    };
+    let signature_type = result["signature_type"]
+        .as_str()
+        .unwrap_or(CL_SIGNATURE_TYPE);
+    let tag = result["tag"].as_str().unwrap_or_default();
+    let id = if tag.is_empty() {
+        format!("{}:3:{}:{}", origin, signature_type, schema_ref)
+    } else {
+        format!("{}:3:{}:{}:{}", origin, signature_type, schema_ref, tag)
+    };
+    let cred_def = serde_json::from_value(json!({
+        "id": id,
+        "schemaId": schema_ref.to_string(),
+        "type": signature_type,
+        "tag": tag,
+        "value": data,
+    }))
+    .with_input_err("Invalid credential definition in GET_CRED_DEF response")?;
+    Ok(LedgerResponse::new(Some(cred_def), metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_get_cred_def_response() {
+        let primary = json!({
+            "n": "1",
+            "s": "2",
+            "r": {"master_secret": "3", "name": "4"},
+            "rctxt": "5",
+            "z": "6",
+        });
+        let response = json!({
+            "op": "REPLY",
+            "result": {
+                "type": GET_CRED_DEF,
+                "origin": "V4SGRU86Z58d6TV7PBUe6f",
+                "ref": 20,
+                "signature_type": "CL",
+                "tag": "tag",
+                "data": {"primary": primary},
+                "seqNo": 21,
+                "txnTime": 1_600_000_000,
+            },
+        })
+        .to_string();
+        let parsed = parse_get_cred_def_response(&response).unwrap();
+        let cred_def = parsed.data.unwrap();
+        assert_eq!(cred_def.id.0, "V4SGRU86Z58d6TV7PBUe6f:3:CL:20:tag");
+        assert_eq!(cred_def.schema_id.0, "20");
+        assert_eq!(cred_def.tag, "tag");
+        assert_eq!(
+            serde_json::to_value(&cred_def.value).unwrap()["primary"],
+            primary
+        );
+        assert_eq!(parsed.metadata.seq_no, Some(21));
+    }
+
+    #[test]
+    fn test_parse_get_cred_def_not_found() {
+        let response = json!({
+            "op": "REPLY",
+            "result": {
+                "type": GET_CRED_DEF,
+                "origin": "V4SGRU86Z58d6TV7PBUe6f",
+                "ref": 20,
+                "signature_type": "CL",
+                "tag": "tag",
+                "data": null,
+            },
+        })
+        .to_string();
+        assert!(parse_get_cred_def_response(&response)
+            .unwrap()
+            .data
+            .is_none());
+
+        let response = json!({
+            "op": "REPLY",
+            "result": {"type": GET_CRED_DEF, "data": {"primary": {}}},
+        })
+        .to_string();
+        assert!(parse_get_cred_def_response(&response).is_err());
+    }
+}
@@ -0,0 +1,146 @@
+/// GET_ATTRIB response parsing
+pub mod attrib;
+/// GET_AUTH_RULE response parsing
+pub mod auth_rule;
+/// GET_TXN_AUTHR_AGRMT and GET_TXN_AUTHR_AGRMT_AML response parsing
+pub mod author_agreement;
+/// GET_CRED_DEF response parsing
+pub mod cred_def;
+/// GET_NYM response parsing
+pub mod nym;
+/// GET_REVOC_REG and GET_REVOC_REG_DELTA response parsing
+pub mod rev_reg;
+/// GET_REVOC_REG_DEF response parsing
+pub mod rev_reg_def;
+/// GET_SCHEMA response parsing
+pub mod schema;
+/// GET_TXN response parsing
+pub mod txn;
+
+use serde_json::Value as SJsonValue;
+
+use crate::common::error::prelude::*;
+
+pub use self::attrib::parse_get_attrib_response;
+pub use self::auth_rule::parse_get_auth_rule_response;
+pub use self::author_agreement::{
+    parse_get_acceptance_mechanisms_response, parse_get_txn_author_agreement_response,
+};
+pub use self::cred_def::parse_get_cred_def_response;
+pub use self::nym::parse_get_nym_response;
+pub use self::rev_reg::{parse_get_revoc_reg_delta_response, parse_get_revoc_reg_response};
+pub use self::rev_reg_def::parse_get_revoc_reg_def_response;
+pub use self::schema::parse_get_schema_response;
+pub use self::txn::parse_get_txn_response;
+
+/// A ledger object returned by a read request, with its ledger metadata
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LedgerResponse<T> {
+    /// The ledger object, or `None` if it was not found
+    pub data: Option<T>,
+    /// The ledger metadata of the object and the reply
+    pub metadata: ResponseMetadata,
+}
+
+impl<T> LedgerResponse<T> {
+    fn new(data: Option<T>, metadata: ResponseMetadata) -> Self {
+        Self { data, metadata }
+    }
+
+    fn not_found(metadata: ResponseMetadata) -> Self {
+        Self {
+            data: None,
+            metadata,
+        }
+    }
+}
+
+/// Ledger metadata attached to a parsed read response
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseMetadata {
+    /// The sequence number of the transaction defining the object
+    pub seq_no: Option<u64>,
+    /// The time of the transaction defining the object in seconds since the epoch
+    pub txn_time: Option<u64>,
+    /// The time of the ledger state the reply was read from, when a state proof is included
+    pub last_txn_time: Option<u64>,
+}
+
+impl ResponseMetadata {
+    fn from_result(result: &SJsonValue) -> Self {
+        Self {
+            seq_no: result["seqNo"].as_u64(),
+            txn_time: result["txnTime"].as_u64(),
+            last_txn_time: result["state_proof"]["multi_signature"]["value"]["timestamp"].as_u64(),
+        }
+    }
+}
+
+/// Extract the result of a ledger reply, checking the operation and transaction type
+fn parse_reply_result(response: &str, txn_type: &str) -> VdrResult<SJsonValue> {
+    let mut reply: SJsonValue =
+        serde_json::from_str(response).with_input_err("Invalid ledger response")?;
+    match reply["op"].as_str() {
+        Some("REPLY") => (),
+        Some("REQNACK") | Some("REJECT") => {
+            return Err(VdrErrorKind::PoolRequestFailed(response.to_string()).into())
+        }
+        _ => return Err(input_err("Invalid ledger response: expected a REPLY")),
+    }
+    let result = reply["result"].take();
+    match result["type"].as_str() {
+        Some(result_type) if result_type == txn_type => Ok(result),
+        result_type => Err(input_err(format!(
+            "Unexpected response type: expected {}, found {:?}",
+            txn_type, result_type
+        ))),
+    }
+}
+
+/// Get the `data` of a reply result, decoding it when it is a JSON-encoded string
+fn parse_result_data(result: &SJsonValue) -> VdrResult<Option<SJsonValue>> {
+    match &result["data"] {
+        SJsonValue::Null => Ok(None),
+        SJsonValue::String(data) => serde_json::from_str(data)
+            .map(Some)
+            .with_input_err("Invalid response data"),
+        data => Ok(Some(data.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::constants::{GET_NYM, GET_SCHEMA};
+
+    #[test]
+    fn test_parse_reply_result() {
+        let response = json!({
+            "op": "REPLY",
+            "result": {
+                "type": GET_NYM,
+                "seqNo": 10,
+                "txnTime": 1_600_000_000,
+                "state_proof": {"multi_signature": {"value": {"timestamp": 1_600_000_100}}},
+            },
+        })
+        .to_string();
+        let result = parse_reply_result(&response, GET_NYM).unwrap();
+        assert_eq!(
+            ResponseMetadata::from_result(&result),
+            ResponseMetadata {
+                seq_no: Some(10),
+                txn_time: Some(1_600_000_000),
+                last_txn_time: Some(1_600_000_100),
+            }
+        );
+
+        assert!(parse_reply_result(&response, GET_SCHEMA).is_err());
+        let response = json!({"op": "REQNACK", "reason": "invalid"}).to_string();
+        match parse_reply_result(&response, GET_NYM).unwrap_err().kind() {
+            VdrErrorKind::PoolRequestFailed(_) => (),
+            kind => panic!("Unexpected error kind: {:?}", kind),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use serde_json::Value as SJsonValue;
+
+use super::{parse_reply_result, parse_result_data, LedgerResponse, ResponseMetadata};
+use crate::common::error::prelude::*;
+use crate::ledger::constants::GET_NYM;
+use crate::utils::did::DidValue;
+
+/// The ledger data of a NYM
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NymData {
+    /// The DID
+    pub dest: DidValue,
+    /// The DID which created the NYM
+    pub identifier: Option<DidValue>,
+    /// The role of the DID, as a role code
+    pub role: Option<String>,
+    /// The verkey of the DID, or `None` if it has been deactivated
+    pub verkey: Option<String>,
+    /// Additional DID document content for `did:indy` DIDs
+    pub diddoc_content: Option<SJsonValue>,
+    /// The version of the DID, describing how it was derived from its verkey
+    pub version: Option<i32>,
+}
+
+/// Parse the reply to a `GET_NYM` request
+///
+/// The NYM data is returned by the ledger as a JSON-encoded string, which is decoded here.
+pub fn parse_get_nym_response(response: &str) -> VdrResult<LedgerResponse<NymData>> {
+    let result = parse_reply_result(response, GET_NYM)?;
+    let mut metadata = ResponseMetadata::from_result(&result);
+    let data = match parse_result_data(&result)? {
+        Some(data) => data,
+        None => return Ok(LedgerResponse::not_found(metadata)),
+    };
+    metadata.seq_no = metadata.seq_no.or_else(|| data["seqNo"].as_u64());
+    metadata.txn_time = metadata.txn_time.or_else(|| data["txnTime"].as_u64());
+
+    let dest = data["dest"]
+        .as_str()
+        .or_else(|| result["dest"].as_str())
+        .ok_or_else(|| input_err("Missing dest in GET_NYM response"))?;
+    let diddoc_content = match &data["diddocContent"] {
+        SJsonValue::Null => None,
+        SJsonValue::String(content) => Some(
+            serde_json::from_str(content).with_input_err("Invalid diddocContent in response")?,
+        ),
+        content => Some(content.clone()),
+    };
+    let nym = NymData {
+        dest: DidValue(dest.to_string()),
+        identifier: data["identifier"]
+            .as_str()
+            .map(|did| DidValue(did.to_string())),
+        role: data["role"].as_str().map(str::to_string),
+        verkey: data["verkey"].as_str().map(str::to_string),
+        diddoc_content,
+        version: data["version"].as_i64().map(|version| version as i32),
+    };
+    Ok(LedgerResponse::new(Some(nym), metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_get_nym_response() {
+        let data = json!({
+            "dest": "V4SGRU86Z58d6TV7PBUe6f",
+            "identifier": "Th7MpTaRZVRYnPiabds81Y",
+            "role": "0",
+            "verkey": "~CoRER63DVYnWZtK8uAzNbx",
+            "diddocContent": "{\"service\":[]}",
+            "seqNo": 12,
+            "txnTime": 1_600_000_000,
+        });
+        let response = json!({
+            "op": "REPLY",
+            "result": {"type": GET_NYM, "dest": "V4SGRU86Z58d6TV7PBUe6f", "data": data.to_string()},
+        })
+        .to_string();
+        let parsed = parse_get_nym_response(&response).unwrap();
+        let nym = parsed.data.unwrap();
+        assert_eq!(nym.dest, DidValue("V4SGRU86Z58d6TV7PBUe6f".to_string()));
+        assert_eq!(nym.role.as_deref(), Some("0"));
+        assert_eq!(nym.verkey.as_deref(), Some("~CoRER63DVYnWZtK8uAzNbx"));
+        assert_eq!(nym.diddoc_content, Some(json!({"service": []})));
+        assert_eq!(nym.version, None);
+        assert_eq!(parsed.metadata.seq_no, Some(12));
+        assert_eq!(parsed.metadata.txn_time, Some(1_600_000_000));
+
+        let response = json!({
+            "op": "REPLY",
+            "result": {"type": GET_NYM, "dest": "V4SGRU86Z58d6TV7PBUe6f", "data": null},
+        })
+        .to_string();
+        assert_eq!(parse_get_nym_response(&response).unwrap().data, None);
+    }
+}
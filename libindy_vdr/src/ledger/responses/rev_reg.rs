@@ -0,0 +1,91 @@
+use serde_json::Value as SJsonValue;
+
+use super::{parse_reply_result, parse_result_data, LedgerResponse, ResponseMetadata};
+use crate::common::error::prelude::*;
+use crate::ledger::constants::{GET_REVOC_REG, GET_REVOC_REG_DELTA};
+use crate::ledger::requests::rev_reg::{RevocationRegistryDeltaV1, RevocationRegistryV1};
+
+/// Parse the reply to a `GET_REVOC_REG` request
+///
+/// The transaction time in the metadata is the time of the revocation registry entry
+/// in effect at the requested timestamp.
+pub fn parse_get_revoc_reg_response(
+    response: &str,
+) -> VdrResult<LedgerResponse<RevocationRegistryV1>> {
+    let result = parse_reply_result(response, GET_REVOC_REG)?;
+    let metadata = ResponseMetadata::from_result(&result);
+    let data = match parse_result_data(&result)? {
+        Some(data) => data,
+        None => return Ok(LedgerResponse::not_found(metadata)),
+    };
+    let rev_reg = serde_json::from_value(json!({ "value": data["value"] }))
+        .with_input_err("Invalid revocation registry in GET_REVOC_REG response")?;
+    Ok(LedgerResponse::new(Some(rev_reg), metadata))
+}
+
+/// Parse the reply to a `GET_REVOC_REG_DELTA` request
+///
+/// The metadata describes the revocation registry entry at the end of the interval.
+pub fn parse_get_revoc_reg_delta_response(
+    response: &str,
+) -> VdrResult<LedgerResponse<RevocationRegistryDeltaV1>> {
+    let result = parse_reply_result(response, GET_REVOC_REG_DELTA)?;
+    let mut metadata = ResponseMetadata::from_result(&result);
+    let value = match parse_result_data(&result)? {
+        Some(data) if !data["value"]["accum_to"].is_null() => data["value"].clone(),
+        _ => return Ok(LedgerResponse::not_found(metadata)),
+    };
+    let accum_to = &value["accum_to"];
+    metadata.seq_no = accum_to["seqNo"].as_u64();
+    metadata.txn_time = accum_to["txnTime"].as_u64();
+
+    let mut delta = json!({
+        "accum": accum_to["value"]["accum"],
+        "issued": _revocation_indices(&value["issued"]),
+        "revoked": _revocation_indices(&value["revoked"]),
+    });
+    let prev_accum = &value["accum_from"]["value"]["accum"];
+    if !prev_accum.is_null() {
+        delta["prevAccum"] = prev_accum.clone();
+    }
+    let delta = serde_json::from_value(json!({ "value": delta }))
+        .with_input_err("Invalid revocation registry delta in GET_REVOC_REG_DELTA response")?;
+    Ok(LedgerResponse::new(Some(delta), metadata))
+}
+
+fn _revocation_indices(value: &SJsonValue) -> SJsonValue {
+    if value.is_array() {
+        value.clone()
+    } else {
+        json!([])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_get_revoc_reg_delta_not_found() {
+        let response = json!({
+            "op": "REPLY",
+            "result": {
+                "type": GET_REVOC_REG_DELTA,
+                "data": {"revocDefType": "CL_ACCUM", "value": {"accum_to": null}},
+            },
+        })
+        .to_string();
+        let parsed = parse_get_revoc_reg_delta_response(&response).unwrap();
+        assert!(parsed.data.is_none());
+
+        let response = json!({
+            "op": "REPLY",
+            "result": {"type": GET_REVOC_REG, "data": null, "txnTime": null},
+        })
+        .to_string();
+        assert!(parse_get_revoc_reg_response(&response)
+            .unwrap()
+            .data
+            .is_none());
+    }
+}
@@ -0,0 +1,41 @@
+use super::{parse_reply_result, parse_result_data, LedgerResponse, ResponseMetadata};
+use crate::common::error::prelude::*;
+use crate::ledger::constants::GET_REVOC_REG_DEF;
+use crate::ledger::requests::rev_reg_def::RevocationRegistryDefinitionV1;
+
+/// Parse the reply to a `GET_REVOC_REG_DEF` request
+pub fn parse_get_revoc_reg_def_response(
+    response: &str,
+) -> VdrResult<LedgerResponse<RevocationRegistryDefinitionV1>> {
+    let result = parse_reply_result(response, GET_REVOC_REG_DEF)?;
+    let metadata = ResponseMetadata::from_result(&result);
+    let rev_reg_def = match parse_result_data(&result)? {
+        Some(data) => serde_json::from_value(data).with_input_err(
+            "Invalid revocation registry definition in GET_REVOC_REG_DEF response",
+        )?,
+        None => return Ok(LedgerResponse::not_found(metadata)),
+    };
+    Ok(LedgerResponse::new(Some(rev_reg_def), metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_get_revoc_reg_def_not_found() {
+        let response = json!({
+            "op": "REPLY",
+            "result": {
+                "type": GET_REVOC_REG_DEF,
+                "id": "V4SGRU86Z58d6TV7PBUe6f:4:V4SGRU86Z58d6TV7PBUe6f:3:CL:20:tag:CL_ACCUM:tag",
+                "data": null,
+            },
+        })
+        .to_string();
+        assert!(parse_get_revoc_reg_def_response(&response)
+            .unwrap()
+            .data
+            .is_none());
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::HashSet;
+
+use super::{parse_reply_result, parse_result_data, LedgerResponse, ResponseMetadata};
+use crate::common::error::prelude::*;
+use crate::ledger::constants::GET_SCHEMA;
+use crate::ledger::identifiers::SchemaId;
+use crate::ledger::requests::schema::{AttributeNames, SchemaV1};
+
+/// Parse the reply to a `GET_SCHEMA` request
+///
+/// The ledger echoes the requested name and version when the schema is not found, so a
+/// schema is only returned when its attribute names are present.
+pub fn parse_get_schema_response(response: &str) -> VdrResult<LedgerResponse<SchemaV1>> {
+    let result = parse_reply_result(response, GET_SCHEMA)?;
+    let metadata = ResponseMetadata::from_result(&result);
+    let data = match parse_result_data(&result)? {
+        Some(data) if !data["attr_names"].is_null() => data,
+        _ => return Ok(LedgerResponse::not_found(metadata)),
+    };
+    let (dest, name, version) = match (
+        result["dest"].as_str(),
+        data["name"].as_str(),
+        data["version"].as_str(),
+    ) {
+        (Some(dest), Some(name), Some(version)) => (dest, name, version),
+        _ => {
+            return Err(input_err(
+                "Missing dest, name or version in GET_SCHEMA response",
+            ))
+        }
+    };
+    let attr_names: HashSet<String> = serde_json::from_value(data["attr_names"].clone())
+        .with_input_err("Invalid attr_names in GET_SCHEMA response")?;
+    let schema = SchemaV1 {
+        id: SchemaId(format!("{}:2:{}:{}", dest, name, version)),
+        name: name.to_string(),
+        version: version.to_string(),
+        attr_names: AttributeNames(attr_names),
+        seq_no: metadata.seq_no.map(|seq_no| seq_no as u32),
+    };
+    Ok(LedgerResponse::new(Some(schema), metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_get_schema_response() {
+        let response = json!({
+            "op": "REPLY",
+            "result": {
+                "type": GET_SCHEMA,
+                "dest": "V4SGRU86Z58d6TV7PBUe6f",
+                "data": {"name": "gvt", "version": "1.0", "attr_names": ["name", "age"]},
+                "seqNo": 20,
+                "txnTime": 1_600_000_000,
+            },
+        })
+        .to_string();
+        let parsed = parse_get_schema_response(&response).unwrap();
+        let schema = parsed.data.unwrap();
+        assert_eq!(
+            schema.id,
+            SchemaId("V4SGRU86Z58d6TV7PBUe6f:2:gvt:1.0".to_string())
+        );
+        assert_eq!(schema.attr_names.0.len(), 2);
+        assert_eq!(schema.seq_no, Some(20));
+
+        let response = json!({
+            "op": "REPLY",
+            "result": {
+                "type": GET_SCHEMA,
+                "dest": "V4SGRU86Z58d6TV7PBUe6f",
+                "data": {"name": "gvt", "version": "1.0"},
+                "seqNo": null,
+            },
+        })
+        .to_string();
+        assert!(parse_get_schema_response(&response).unwrap().data.is_none());
+    }
+}
@@ -0,0 +1,84 @@
+use serde_json::Value as SJsonValue;
+
+use super::{parse_reply_result, parse_result_data, LedgerResponse, ResponseMetadata};
+use crate::common::error::prelude::*;
+use crate::ledger::constants::GET_TXN;
+
+/// A transaction read from one of the ledgers
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerTransaction {
+    /// The transaction type identifier
+    pub txn_type: String,
+    /// The transaction data, specific to the transaction type
+    pub data: SJsonValue,
+    /// The transaction metadata, such as the DID of the author
+    pub txn_metadata: SJsonValue,
+    /// The transaction identifier, when one was assigned
+    pub txn_id: Option<String>,
+}
+
+/// Parse the reply to a `GET_TXN` request
+///
+/// The sequence number and time in the metadata are those of the returned transaction.
+pub fn parse_get_txn_response(response: &str) -> VdrResult<LedgerResponse<LedgerTransaction>> {
+    let result = parse_reply_result(response, GET_TXN)?;
+    let mut metadata = ResponseMetadata::from_result(&result);
+    let data = match parse_result_data(&result)? {
+        Some(data) => data,
+        None => return Ok(LedgerResponse::not_found(metadata)),
+    };
+    let txn = &data["txn"];
+    let txn_type = txn["type"]
+        .as_str()
+        .ok_or_else(|| input_err("Missing transaction type in GET_TXN response"))?;
+    metadata.seq_no = data["txnMetadata"]["seqNo"].as_u64();
+    metadata.txn_time = data["txnMetadata"]["txnTime"].as_u64();
+    let txn = LedgerTransaction {
+        txn_type: txn_type.to_string(),
+        data: txn["data"].clone(),
+        txn_metadata: txn["metadata"].clone(),
+        txn_id: data["txnMetadata"]["txnId"].as_str().map(str::to_string),
+    };
+    Ok(LedgerResponse::new(Some(txn), metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_get_txn_response() {
+        let response = json!({
+            "op": "REPLY",
+            "result": {
+                "type": GET_TXN,
+                "seqNo": 1,
+                "data": {
+                    "txn": {
+                        "type": "1",
+                        "data": {"dest": "V4SGRU86Z58d6TV7PBUe6f", "role": "0"},
+                        "metadata": {},
+                    },
+                    "txnMetadata": {"seqNo": 1, "txnTime": 1_600_000_000},
+                    "ver": "1",
+                },
+            },
+        })
+        .to_string();
+        let parsed = parse_get_txn_response(&response).unwrap();
+        let txn = parsed.data.unwrap();
+        assert_eq!(txn.txn_type, "1");
+        assert_eq!(txn.data["dest"], "V4SGRU86Z58d6TV7PBUe6f");
+        assert_eq!(txn.txn_id, None);
+        assert_eq!(parsed.metadata.seq_no, Some(1));
+        assert_eq!(parsed.metadata.txn_time, Some(1_600_000_000));
+
+        let response = json!({
+            "op": "REPLY",
+            "result": {"type": GET_TXN, "seqNo": 100, "data": null},
+        })
+        .to_string();
+        assert!(parse_get_txn_response(&response).unwrap().data.is_none());
+    }
+}
@@ -65,6 +65,8 @@ mod ffi;
 pub mod ledger;
 /// Handling of verifier pool instances and communication
 pub mod pool;
+/// DID resolution for `did:indy` and `did:sov` DIDs
+pub mod resolver;
 /// State proof verification for ledger read transactions
 pub mod state_proof;
 
@@ -1,13 +1,21 @@
 use super::genesis::PoolTransactions;
+#[cfg(all(feature = "tokio_networker", unix))]
+use super::networker::TokioNetworkerFactory;
 use super::networker::{MakeLocal, MakeShared, ZMQNetworkerFactory};
+#[cfg(all(feature = "tokio_networker", unix))]
+use super::pool::TokioPool;
 use super::pool::{LocalPool, SharedPool};
 use super::runner::PoolRunner;
+use super::selector::{NodeSelector, WeightedNodeSelector};
+use super::store::PoolTransactionsStore;
 
 use crate::common::error::prelude::*;
 use crate::common::merkle_tree::MerkleTree;
 use crate::config::PoolConfig;
+use crate::state_proof::{BoxedSPParser, StateProofParsers};
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// A utility class for building a new pool instance or runner.
 #[derive(Clone)]
@@ -15,6 +23,9 @@ pub struct PoolBuilder {
     pub config: PoolConfig,
     merkle_tree: Option<MerkleTree>,
     node_weights: Option<HashMap<String, f32>>,
+    node_selector: Option<Arc<dyn NodeSelector>>,
+    store: Option<(PoolTransactionsStore, PoolTransactions)>,
+    state_proof_parsers: Option<StateProofParsers>,
 }
 
 impl PoolBuilder {
@@ -28,6 +39,9 @@ impl PoolBuilder {
             config,
             merkle_tree,
             node_weights,
+            node_selector: None,
+            store: None,
+            state_proof_parsers: None,
         }
     }
 
@@ -43,6 +57,28 @@ impl PoolBuilder {
         self
     }
 
+    /// Set the strategy used to select the nodes contacted by each request.
+    /// When set, this takes precedence over the node weights.
+    pub fn node_selector(mut self, node_selector: Option<Arc<dyn NodeSelector>>) -> Self {
+        self.node_selector = node_selector;
+        self
+    }
+
+    /// Register a custom state proof parser for a transaction type, such as a plugin ledger read.
+    ///
+    /// Replies to requests of this type submitted to the pool are verified using the parser,
+    /// unless a built-in state proof is supported or the request has its own parser.
+    pub fn state_proof_parser<T: Into<String>>(
+        mut self,
+        txn_type: T,
+        parser: BoxedSPParser,
+    ) -> Self {
+        self.state_proof_parsers
+            .get_or_insert_with(StateProofParsers::default)
+            .register(txn_type, parser);
+        self
+    }
+
     /// Replace the builder's pool transactions.
     pub fn transactions(mut self, transactions: PoolTransactions) -> VdrResult<Self> {
         let merkle_tree = transactions.into_merkle_tree()?;
@@ -50,6 +86,22 @@ impl PoolBuilder {
         Ok(self)
     }
 
+    /// Replace the builder's pool transactions with those cached for a set of genesis
+    /// transactions, falling back to the genesis transactions when none are cached.
+    ///
+    /// A `PoolRunner` created from the builder also stores the pool transactions
+    /// after each refresh which applies new transactions.
+    pub fn transactions_store(
+        mut self,
+        store: PoolTransactionsStore,
+        genesis: PoolTransactions,
+    ) -> VdrResult<Self> {
+        let transactions = store.load_or_genesis(genesis.clone())?;
+        self = self.transactions(transactions)?;
+        self.store.replace((store, genesis));
+        Ok(self)
+    }
+
     /// Create a `LocalPool` instance from the builder, for use in a single thread.
     pub fn into_local(self) -> VdrResult<LocalPool> {
         if self.merkle_tree.is_none() {
@@ -58,11 +110,13 @@ impl PoolBuilder {
                 "No pool transactions provided",
             ));
         }
+        let node_selector = self.get_node_selector();
         LocalPool::build(
             self.config,
             self.merkle_tree.unwrap(),
             MakeLocal(ZMQNetworkerFactory {}),
-            self.node_weights,
+            node_selector,
+            self.state_proof_parsers,
         )
     }
 
@@ -74,11 +128,34 @@ impl PoolBuilder {
                 "No pool transactions provided",
             ));
         }
+        let node_selector = self.get_node_selector();
         SharedPool::build(
             self.config,
             self.merkle_tree.unwrap(),
             MakeShared(ZMQNetworkerFactory {}),
-            self.node_weights,
+            node_selector,
+            self.state_proof_parsers,
+        )
+    }
+
+    /// Create a `TokioPool` instance from the builder, for use within a Tokio runtime.
+    ///
+    /// Must be called from within the runtime context.
+    #[cfg(all(feature = "tokio_networker", unix))]
+    pub fn into_tokio(self) -> VdrResult<TokioPool> {
+        if self.merkle_tree.is_none() {
+            return Err(err_msg(
+                VdrErrorKind::Config,
+                "No pool transactions provided",
+            ));
+        }
+        let node_selector = self.get_node_selector();
+        TokioPool::build(
+            self.config,
+            self.merkle_tree.unwrap(),
+            TokioNetworkerFactory::new(),
+            node_selector,
+            self.state_proof_parsers,
         )
     }
 
@@ -91,13 +168,24 @@ impl PoolBuilder {
                 "No pool transactions provided",
             ));
         }
+        let node_selector = self.get_node_selector();
         Ok(PoolRunner::new(
             self.config,
             self.merkle_tree.unwrap(),
             MakeLocal(ZMQNetworkerFactory {}),
-            self.node_weights,
+            node_selector,
+            self.store,
+            self.state_proof_parsers,
         ))
     }
+
+    fn get_node_selector(&self) -> Option<Arc<dyn NodeSelector>> {
+        self.node_selector.clone().or_else(|| {
+            self.node_weights.clone().map(|weights| {
+                Arc::new(WeightedNodeSelector::new(Some(weights))) as Arc<dyn NodeSelector>
+            })
+        })
+    }
 }
 
 impl Default for PoolBuilder {
@@ -144,6 +144,11 @@ impl PoolTransactions {
     pub fn len(&self) -> usize {
         self.inner.len()
     }
+
+    /// Derive the verifier nodes defined by the pool transactions.
+    pub fn verifiers(&self, protocol_version: ProtocolVersion) -> VdrResult<Verifiers> {
+        build_verifiers(build_node_transaction_map(self.iter(), protocol_version)?)
+    }
 }
 
 impl std::fmt::Debug for PoolTransactions {
@@ -411,6 +416,14 @@ mod tests {
             )
         }
 
+        #[test]
+        fn test_pool_transactions_verifiers_works() {
+            let transactions = PoolTransactions::from_json_transactions(&_transactions()).unwrap();
+            let verifiers = transactions.verifiers(ProtocolVersion::Node1_4).unwrap();
+            assert_eq!(verifiers.len(), 4);
+            assert!(verifiers.values().all(|info| info.bls_key.is_some()));
+        }
+
         #[test]
         fn test_pool_transactions_from_file_for_unknown_file() {
             let file = {
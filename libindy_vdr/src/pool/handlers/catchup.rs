@@ -29,7 +29,8 @@ pub async fn handle_catchup_request<R: PoolRequest>(
                             cr.consProof.clone(),
                         ) {
                             Ok(txns) => {
-                                return Ok((RequestResult::Reply(txns), request.get_timing()))
+                                request.report_success(node_alias)?;
+                                return Ok((RequestResult::Reply(txns), request.get_timing()));
                             }
                             Err(_) => {
                                 request.clean_timeout(node_alias)?;
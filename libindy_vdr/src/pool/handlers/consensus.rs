@@ -8,10 +8,17 @@ use ursa::bls::Generator;
 
 use crate::common::error::prelude::*;
 use crate::config::constants::DEFAULT_GENERATOR;
-use crate::state_proof::{check_state_proof, result_without_state_proof, BoxedSPParser};
+use crate::pool::networker::NodeFailure;
+use crate::pool::ConsensusPolicy;
+use crate::state_proof::{
+    check_state_proof, result_without_state_proof, BoxedSPParser, InclusionReceipt,
+    StateProofContext, StateProofResult,
+};
 use crate::utils::{base58, base64};
 
-use super::types::Message;
+use super::types::{
+    ConsensusRejectGroup, ConsensusReplyGroup, ConsensusReport, Message, SingleReply,
+};
 use super::{
     min_consensus, ConsensusState, HashableValue, PoolRequest, ReplyState, RequestEvent,
     RequestResult, TimingResult,
@@ -23,15 +30,47 @@ pub async fn handle_consensus_request<R: PoolRequest>(
     state_proof_timestamps: (Option<u64>, Option<u64>),
     as_read_request: bool,
     custom_state_proof_parser: Option<&BoxedSPParser>,
+    policy: Option<&ConsensusPolicy>,
+    report: Option<&mut ConsensusReport>,
+) -> VdrResult<(RequestResult<String>, Option<TimingResult>)> {
+    let mut replies = ReplyState::new();
+    let mut consensus = ConsensusState::new();
+    let mut fail_consensus = ConsensusState::new();
+    let result = _handle_consensus_request(
+        request,
+        state_proof_key,
+        state_proof_timestamps,
+        as_read_request,
+        custom_state_proof_parser,
+        policy,
+        &mut replies,
+        &mut consensus,
+        &mut fail_consensus,
+    )
+    .await;
+    if let Some(report) = report {
+        *report = build_report(&replies, &consensus, &fail_consensus);
+    }
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn _handle_consensus_request<R: PoolRequest>(
+    request: &mut R,
+    state_proof_key: Option<Vec<u8>>,
+    state_proof_timestamps: (Option<u64>, Option<u64>),
+    as_read_request: bool,
+    custom_state_proof_parser: Option<&BoxedSPParser>,
+    policy: Option<&ConsensusPolicy>,
+    replies: &mut ReplyState<bool>,
+    consensus: &mut ConsensusState<HashableValue, NodeResponse>,
+    fail_consensus: &mut ConsensusState<String, String>,
 ) -> VdrResult<(RequestResult<String>, Option<TimingResult>)> {
     trace!("consensus request");
     let config = request.pool_config();
     let node_keys = request.node_keys();
     let total_nodes_count = request.node_count();
     let f = min_consensus(total_nodes_count);
-    let mut replies = ReplyState::new();
-    let mut consensus = ConsensusState::new();
-    let mut fail_consensus = ConsensusState::new();
     let generator: Generator =
         Generator::from_bytes(&base58::decode(DEFAULT_GENERATOR)?).map_err(|err| {
             err_msg(
@@ -40,30 +79,63 @@ pub async fn handle_consensus_request<R: PoolRequest>(
             )
         })?;
 
-    let request_with_state_proof = state_proof_key.is_some() || custom_state_proof_parser.is_some();
+    let request_with_state_proof = (state_proof_key.is_some()
+        || custom_state_proof_parser.is_some())
+        && policy.map_or(true, |policy| policy.accept_state_proof);
+    let required_replies = policy.map_or(f + 1, |policy| policy.quorum.required_replies(f));
+    let freshness_threshold =
+        ConsensusPolicy::freshness_threshold(policy, config.freshness_threshold);
+    let require_inclusion_proof =
+        !as_read_request && policy.map_or(false, |policy| policy.require_inclusion_proof);
 
-    let init_send = if request_with_state_proof {
-        config.request_read_nodes
-    } else if as_read_request {
-        f + config.request_read_nodes
-    } else {
-        total_nodes_count
+    let init_send = match policy {
+        Some(policy) if policy.required_nodes.is_some() => total_nodes_count,
+        Some(ConsensusPolicy {
+            initial_nodes: Some(count),
+            ..
+        }) => (*count).min(total_nodes_count),
+        _ if request_with_state_proof => config.request_read_nodes,
+        _ if as_read_request => (f + config.request_read_nodes).max(required_replies),
+        _ => total_nodes_count,
     };
-    request.send_to_any(init_send, config.ack_timeout)?;
+    let mut contacted = request.send_to_any(init_send, config.ack_timeout)?.len();
     loop {
         let resend = match request.next().await {
             Some(RequestEvent::Received(node_alias, raw_msg, parsed)) => match parsed {
+                // an unsigned audit path is only checked against the root hash of the same reply
+                Message::Reply(ref reply)
+                    if require_inclusion_proof
+                        && reply.result().map_or(false, |result| {
+                            InclusionReceipt::from_result(result)
+                                .and_then(|receipt| {
+                                    receipt.verify_signed(
+                                        &node_keys,
+                                        f,
+                                        &generator,
+                                        request.signature_cache(),
+                                    )
+                                })
+                                .is_err()
+                        }) =>
+                {
+                    debug!(
+                        "Inclusion proof verification failed for node: {}",
+                        node_alias
+                    );
+                    replies.add_failed(node_alias.clone(), raw_msg);
+                    request.report_failure(node_alias.clone(), NodeFailure::StateProof)?;
+                    request.clean_timeout(node_alias)?;
+                    true
+                }
                 Message::Reply(reply) => {
                     trace!("reply on consensus request");
                     if let Some(result) = reply.result() {
                         let result_without_proof = result_without_state_proof(result);
                         replies.add_reply(node_alias.clone(), true);
-                        let hashable = HashableValue {
-                            inner: result_without_proof,
-                        };
+                        let hashable = HashableValue::new(result_without_proof);
                         let last_write_time = get_last_signed_time(result).unwrap_or(0);
                         trace!("last write {}", last_write_time);
-                        let (cnt, soonest) = {
+                        let (matched, soonest) = {
                             let set = consensus.insert(
                                 hashable,
                                 NodeResponse {
@@ -73,7 +145,12 @@ pub async fn handle_consensus_request<R: PoolRequest>(
                                 },
                             );
                             (
-                                set.len(),
+                                set.len() >= required_replies
+                                    && policy.map_or(true, |policy| {
+                                        policy.has_required_nodes(
+                                            set.iter().map(|resp| &resp.node_alias),
+                                        )
+                                    }),
                                 set.iter()
                                     .max_by_key(|resp| resp.timestamp)
                                     .map(|resp| &resp.raw_msg)
@@ -81,39 +158,53 @@ pub async fn handle_consensus_request<R: PoolRequest>(
                                     .clone(),
                             )
                         };
-                        if cnt > f
-                            || (request_with_state_proof
-                                && check_state_proof(
-                                    &result,
-                                    f,
-                                    &generator,
-                                    &node_keys,
-                                    &raw_msg,
-                                    state_proof_key.as_ref().map(Vec::as_slice),
-                                    state_proof_timestamps,
-                                    last_write_time,
-                                    config.freshness_threshold,
-                                    custom_state_proof_parser,
-                                ))
-                        {
+                        let proof = if request_with_state_proof && !matched {
+                            let context = StateProofContext {
+                                f,
+                                gen: &generator,
+                                bls_keys: &node_keys,
+                                signature_cache: request.signature_cache(),
+                                sp_key: state_proof_key.as_ref().map(Vec::as_slice),
+                                requested_timestamps: state_proof_timestamps,
+                                threshold: freshness_threshold,
+                                custom_state_proof_parser,
+                            };
+                            check_state_proof(&result, &raw_msg, last_write_time, &context)
+                        } else {
+                            StateProofResult::Missing
+                        };
+                        if matched || proof == StateProofResult::Verified {
+                            request.report_success(node_alias)?;
                             return Ok((
-                                RequestResult::Reply(if cnt > f { soonest } else { raw_msg }),
+                                RequestResult::Reply(if matched { soonest } else { raw_msg }),
                                 request.get_timing(),
                             ));
-                        } else if state_proof_key.is_some() {
+                        } else if request_with_state_proof && state_proof_key.is_some() {
                             debug!(
                                 "State proof verification failed for node: {}, sp_key: '{}'",
                                 node_alias,
                                 base64::encode(state_proof_key.as_ref().unwrap()),
                             );
+                            // honest nodes may return outdated state, which is not penalized
+                            let failure = if proof == StateProofResult::Expired {
+                                NodeFailure::Stale
+                            } else {
+                                NodeFailure::StateProof
+                            };
+                            request.report_failure(node_alias.clone(), failure)?;
                             request.clean_timeout(node_alias)?;
                             true
                         } else {
-                            false
+                            request.report_success(node_alias)?;
+                            // a custom policy may need replies beyond the nodes initially
+                            // contacted, once the pending replies can no longer meet its quorum
+                            let pending = contacted.saturating_sub(replies.len());
+                            policy.is_some() && consensus.max_len() + pending < required_replies
                         }
                     } else {
                         debug!("Error parsing result of reply from {}", node_alias);
                         replies.add_failed(node_alias.clone(), raw_msg);
+                        request.report_failure(node_alias.clone(), NodeFailure::Malformed)?;
                         request.clean_timeout(node_alias)?;
                         true
                     }
@@ -144,6 +235,7 @@ pub async fn handle_consensus_request<R: PoolRequest>(
                 _ => {
                     debug!("Unexpected response from {} {:?}", node_alias, raw_msg);
                     replies.add_failed(node_alias.clone(), raw_msg);
+                    request.report_failure(node_alias.clone(), NodeFailure::Malformed)?;
                     request.clean_timeout(node_alias)?;
                     true
                 }
@@ -168,9 +260,67 @@ pub async fn handle_consensus_request<R: PoolRequest>(
             return Ok((RequestResult::Failed(err), request.get_timing()));
         }
         if resend {
-            request.send_to_any(1, config.ack_timeout)?;
+            contacted += request.send_to_any(1, config.ack_timeout)?.len();
+        }
+    }
+}
+
+/// Summarize the replies collected for a consensus request
+fn build_report(
+    replies: &ReplyState<bool>,
+    consensus: &ConsensusState<HashableValue, NodeResponse>,
+    fail_consensus: &ConsensusState<String, String>,
+) -> ConsensusReport {
+    let mut report = ConsensusReport::default();
+    for (value, responses) in consensus.inner.iter() {
+        let mut nodes = responses
+            .iter()
+            .map(|resp| resp.node_alias.clone())
+            .collect::<Vec<_>>();
+        nodes.sort();
+        report.replies.push(ConsensusReplyGroup {
+            result: value.inner.clone(),
+            nodes,
+        });
+    }
+    report.replies.sort_by(|a, b| {
+        b.nodes
+            .len()
+            .cmp(&a.nodes.len())
+            .then(a.nodes.cmp(&b.nodes))
+    });
+    for (reason, aliases) in fail_consensus.inner.iter() {
+        let mut nodes = aliases.iter().cloned().collect::<Vec<_>>();
+        nodes.sort();
+        report.rejections.push(ConsensusRejectGroup {
+            reason: reason.clone(),
+            nodes,
+        });
+    }
+    report.rejections.sort_by(|a, b| {
+        b.nodes
+            .len()
+            .cmp(&a.nodes.len())
+            .then(a.nodes.cmp(&b.nodes))
+    });
+    for (node_alias, reply) in replies.inner.iter() {
+        match reply {
+            SingleReply::Failed(_) => {
+                if !fail_consensus
+                    .inner
+                    .values()
+                    .any(|aliases| aliases.contains(node_alias))
+                {
+                    report.failed.push(node_alias.clone())
+                }
+            }
+            SingleReply::Timeout() => report.timeouts.push(node_alias.clone()),
+            SingleReply::Reply(_) => (),
         }
     }
+    report.failed.sort();
+    report.timeouts.sort();
+    report
 }
 
 #[derive(Debug)]
@@ -245,3 +395,204 @@ pub struct ResponseMetadata {
     pub last_txn_time: Option<u64>,
     pub last_seq_no: Option<u64>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use futures_executor::block_on;
+
+    use crate::pool::helpers::{perform_ledger_request, perform_ledger_request_diagnostic};
+    use crate::pool::networker::fixtures::*;
+    use crate::pool::networker::{SimulatedNetworkerFactory, SimulatedNode, SimulatedReply};
+    use crate::pool::ReplyQuorum;
+
+    #[test]
+    fn test_consensus_report_groups_node_replies() {
+        let pool = _pool(
+            SimulatedNetworkerFactory::new()
+                .node(
+                    NODES[0],
+                    SimulatedNode::replies(vec![SimulatedReply::Reply(_nym_result("a"))]),
+                )
+                .node(
+                    NODES[1],
+                    SimulatedNode::replies(vec![SimulatedReply::Reply(_nym_result("b"))]),
+                )
+                .node(
+                    NODES[2],
+                    SimulatedNode::replies(vec![SimulatedReply::Nack("invalid".to_owned())]),
+                )
+                .default_node(SimulatedNode::replies(vec![SimulatedReply::Timeout])),
+        );
+        let request = _nym_request();
+        let (result, _timing, report) =
+            block_on(perform_ledger_request_diagnostic(&pool, &request)).unwrap();
+        match result {
+            RequestResult::Failed(err) => {
+                assert!(matches!(err.kind(), VdrErrorKind::PoolNoConsensus))
+            }
+            RequestResult::Reply(reply) => panic!("Unexpected reply: {}", reply),
+        }
+        let report = report.unwrap();
+        assert_eq!(report.replies.len(), 2);
+        assert_eq!(report.replies[0].nodes, vec![NODES[0]]);
+        assert_eq!(report.replies[0].result["verkey"], "a");
+        assert_eq!(report.replies[1].nodes, vec![NODES[1]]);
+        assert_eq!(report.rejections.len(), 1);
+        assert_eq!(report.rejections[0].reason, "invalid");
+        assert_eq!(report.rejections[0].nodes, vec![NODES[2]]);
+        assert!(report.failed.is_empty());
+        assert_eq!(report.timeouts, vec![NODES[3]]);
+    }
+
+    #[test]
+    fn test_consensus_request_applies_policy() {
+        let pool = _pool(
+            SimulatedNetworkerFactory::new()
+                .default_node(SimulatedNode::replies(vec![SimulatedReply::Reply(
+                    _nym_result("verkey"),
+                )]))
+                .node(
+                    NODES[0],
+                    SimulatedNode::replies(vec![SimulatedReply::Reply(_nym_result("other"))]),
+                )
+                .node(
+                    NODES[3],
+                    SimulatedNode::replies(vec![SimulatedReply::Timeout]),
+                ),
+        );
+        let mut request = _nym_request();
+
+        // two matching replies are not sufficient for a 2f+1 quorum
+        request
+            .set_consensus_policy(Some(ConsensusPolicy::new().quorum(ReplyQuorum::Strong)))
+            .unwrap();
+        match block_on(perform_ledger_request(&pool, &request)).unwrap().0 {
+            RequestResult::Failed(err) => {
+                assert!(matches!(err.kind(), VdrErrorKind::PoolNoConsensus))
+            }
+            RequestResult::Reply(reply) => panic!("Unexpected reply: {}", reply),
+        }
+
+        // the matching replies must include the required node
+        request
+            .set_consensus_policy(Some(
+                ConsensusPolicy::new().required_nodes(vec![NODES[0].to_owned()], 1),
+            ))
+            .unwrap();
+        match block_on(perform_ledger_request(&pool, &request)).unwrap().0 {
+            RequestResult::Failed(err) => {
+                assert!(matches!(err.kind(), VdrErrorKind::PoolNoConsensus))
+            }
+            RequestResult::Reply(reply) => panic!("Unexpected reply: {}", reply),
+        }
+
+        request
+            .set_consensus_policy(Some(
+                ConsensusPolicy::new().required_nodes(vec![NODES[1].to_owned()], 1),
+            ))
+            .unwrap();
+        match block_on(perform_ledger_request(&pool, &request)).unwrap().0 {
+            RequestResult::Reply(reply) => assert_eq!(_reply_result(&reply), _nym_result("verkey")),
+            RequestResult::Failed(err) => panic!("Unexpected error: {}", err),
+        }
+
+        request
+            .set_consensus_policy(Some(ConsensusPolicy::new().quorum(ReplyQuorum::Count(0))))
+            .unwrap_err();
+    }
+
+    /// Send a request with a custom policy, returning the result and the number of nodes contacted
+    fn _send_nym_with_policy(divergent: bool) -> (RequestResult<String>, usize) {
+        let contacted = Arc::new(AtomicUsize::new(0));
+        let node_contacted = contacted.clone();
+        let node = SimulatedNode::new(move |alias, _| {
+            node_contacted.fetch_add(1, Ordering::SeqCst);
+            let verkey = if divergent { alias } else { "verkey" };
+            vec![SimulatedReply::Reply(_nym_result(verkey))]
+        });
+        let pool = _pool(SimulatedNetworkerFactory::new().default_node(node));
+        let mut request = _nym_request();
+        request
+            .set_consensus_policy(Some(ConsensusPolicy::new().initial_nodes(Some(2))))
+            .unwrap();
+        let result = block_on(perform_ledger_request(&pool, &request)).unwrap().0;
+        (result, contacted.load(Ordering::SeqCst))
+    }
+
+    #[test]
+    fn test_consensus_request_contacts_nodes_for_policy() {
+        // the initial nodes can still meet the quorum, so no other node is contacted
+        match _send_nym_with_policy(false) {
+            (RequestResult::Reply(reply), contacted) => {
+                assert_eq!(_reply_result(&reply), _nym_result("verkey"));
+                assert_eq!(contacted, 2);
+            }
+            (RequestResult::Failed(err), _) => panic!("Unexpected error: {}", err),
+        }
+
+        // each divergent reply leaves the quorum out of reach, so another node is contacted
+        match _send_nym_with_policy(true) {
+            (RequestResult::Failed(err), contacted) => {
+                assert!(matches!(err.kind(), VdrErrorKind::PoolNoConsensus));
+                assert_eq!(contacted, NODES.len());
+            }
+            (RequestResult::Reply(reply), _) => panic!("Unexpected reply: {}", reply),
+        }
+    }
+
+    #[test]
+    fn test_consensus_request_requires_inclusion_proof() {
+        let mut request = _nym_request();
+        request
+            .set_consensus_policy(Some(ConsensusPolicy::new().require_inclusion_proof(true)))
+            .unwrap();
+
+        let pool = _pool(
+            SimulatedNetworkerFactory::new().default_node(SimulatedNode::replies(vec![
+                SimulatedReply::Reply(_nym_result("verkey")),
+            ])),
+        );
+        match block_on(perform_ledger_request(&pool, &request)).unwrap().0 {
+            RequestResult::Failed(err) => {
+                assert!(matches!(err.kind(), VdrErrorKind::PoolRequestFailed(_)))
+            }
+            RequestResult::Reply(reply) => panic!("Unexpected reply: {}", reply),
+        }
+
+        let pool = _pool(
+            SimulatedNetworkerFactory::new().default_node(SimulatedNode::replies(vec![
+                SimulatedReply::Reply(_committed_nym_result()),
+            ])),
+        );
+        match block_on(perform_ledger_request(&pool, &request)).unwrap().0 {
+            RequestResult::Reply(reply) => {
+                assert_eq!(_reply_result(&reply), _committed_nym_result())
+            }
+            RequestResult::Failed(err) => panic!("Unexpected error: {}", err),
+        }
+
+        // a multi-signature over the ledger root must be valid when present
+        let mut signed_result = _committed_nym_result();
+        signed_result["multi_signature"] = json!({
+            "participants": NODES,
+            "signature": "",
+            "value": {"txn_root_hash": signed_result["rootHash"].clone()},
+        });
+        let pool = _pool(
+            SimulatedNetworkerFactory::new().default_node(SimulatedNode::replies(vec![
+                SimulatedReply::Reply(signed_result),
+            ])),
+        );
+        match block_on(perform_ledger_request(&pool, &request)).unwrap().0 {
+            RequestResult::Failed(err) => {
+                assert!(matches!(err.kind(), VdrErrorKind::PoolRequestFailed(_)))
+            }
+            RequestResult::Reply(reply) => panic!("Unexpected reply: {}", reply),
+        }
+    }
+}
@@ -26,6 +26,7 @@ pub async fn handle_full_request<R: PoolRequest>(
                     Message::Reply(_) => {
                         trace!("reply on full request");
                         replies.add_reply(node_alias.clone(), raw_msg);
+                        request.report_success(node_alias.clone())?;
                     }
                     Message::ReqACK(_) => {
                         continue;
@@ -5,7 +5,7 @@ use serde_json::{self, Value as SJsonValue};
 
 use crate::common::error::prelude::*;
 use crate::common::merkle_tree::MerkleTree;
-use crate::utils::{base58, ValidationError};
+use crate::utils::{base58, json::canonical_json, ValidationError};
 
 use super::requests::{PoolRequest, RequestEvent};
 use super::types::{
@@ -19,7 +19,7 @@ mod full;
 mod status;
 
 pub use catchup::handle_catchup_request;
-pub use consensus::handle_consensus_request;
+pub use consensus::{get_last_signed_time, handle_consensus_request};
 pub use full::handle_full_request;
 pub use status::{handle_status_request, CatchupTarget};
 
@@ -132,7 +132,6 @@ impl<K: Eq + Hash, T: Eq + Hash> ConsensusState<K, T> {
             .max_by_key(|entry| entry.1)
     }
 
-    #[allow(dead_code)]
     fn max_len(&self) -> usize {
         self.max_entry().map(|entry| entry.1).unwrap_or(0)
     }
@@ -144,27 +143,35 @@ impl<K: Eq + Hash, T: Eq + Hash> ConsensusState<K, T> {
     }
 }
 
+/// A JSON value compared and hashed by its canonical encoding
 #[derive(Debug)]
 pub(crate) struct HashableValue {
     pub inner: SJsonValue,
+    canonical: String,
+}
+
+impl HashableValue {
+    pub fn new(inner: SJsonValue) -> Self {
+        let canonical = canonical_json(&inner);
+        Self { inner, canonical }
+    }
 }
 
 impl Hash for HashableValue {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        // FIXME does to_string produce canonical results??
-        serde_json::to_string(&self.inner).unwrap().hash(state); //TODO
+        self.canonical.hash(state);
     }
 }
 
 impl PartialEq for HashableValue {
     fn eq(&self, other: &HashableValue) -> bool {
-        self.inner.eq(&other.inner)
+        self.canonical == other.canonical
     }
 }
 
 impl Eq for HashableValue {}
 
-fn min_consensus(cnt: usize) -> usize {
+pub(crate) fn min_consensus(cnt: usize) -> usize {
     if cnt < 4 {
         return 0;
     }
@@ -229,3 +236,35 @@ pub(crate) fn build_pool_catchup_request(
     };
     Ok(Message::CatchupReq(cr))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn _hashable(json: &str) -> HashableValue {
+        HashableValue::new(serde_json::from_str(json).unwrap())
+    }
+
+    #[test]
+    fn test_consensus_state_matches_equivalent_replies() {
+        let mut consensus = ConsensusState::new();
+        consensus.insert(
+            _hashable(r#"{"seqNo": 10, "data": {"b": 1.0, "a": "x"}}"#),
+            "Node1",
+        );
+        consensus.insert(
+            _hashable(r#"{"data": {"a": "x", "b": 1}, "seqNo": 10.0}"#),
+            "Node2",
+        );
+        consensus.insert(
+            _hashable(r#"{"data":{"a":"x","b":1e0},"seqNo":1e1}"#),
+            "Node3",
+        );
+        consensus.insert(
+            _hashable(r#"{"data": {"a": "x", "b": 2}, "seqNo": 10}"#),
+            "Node4",
+        );
+        assert_eq!(consensus.inner.len(), 2);
+        assert_eq!(consensus.max_len(), 3);
+    }
+}
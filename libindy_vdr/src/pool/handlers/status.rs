@@ -30,12 +30,14 @@ pub async fn handle_status_request<R: PoolRequest>(
                     Message::LedgerStatus(ls) => {
                         trace!("Received ledger status from {}", &node_alias);
                         replies.add_reply(node_alias.clone(), true);
+                        request.report_success(node_alias.clone())?;
                         let key = (ls.merkleRoot.clone(), ls.txnSeqNo, None);
                         consensus.insert(key, node_alias.clone());
                     }
                     Message::ConsistencyProof(cp) => {
                         trace!("Received consistency proof from {}", &node_alias);
                         replies.add_reply(node_alias.clone(), true);
+                        request.report_success(node_alias.clone())?;
                         let key = (cp.newMerkleRoot.clone(), cp.seqNoEnd, Some(cp.hashes));
                         consensus.insert(key, node_alias.clone());
                     }
@@ -1,5 +1,7 @@
+use std::future::Future;
 use std::iter::FromIterator;
 
+use futures_util::future::{BoxFuture, FutureExt, LocalBoxFuture};
 use serde_json;
 
 use super::genesis::PoolTransactions;
@@ -7,23 +9,38 @@ use super::handlers::{
     build_pool_catchup_request, build_pool_status_request, handle_catchup_request,
     handle_consensus_request, handle_full_request, handle_status_request, CatchupTarget,
 };
-use super::pool::Pool;
+use super::pool::{Pool, SendPool};
 use super::requests::{PreparedRequest, RequestMethod};
-use super::types::{NodeReplies, RequestResult, SingleReply, TimingResult};
+use super::types::{ConsensusReport, NodeReplies, RequestResult, SingleReply, TimingResult};
 
 use crate::common::error::prelude::*;
 use crate::common::merkle_tree::MerkleTree;
+use crate::state_proof::parse_timestamp_from_req_for_builtin_sp;
 use crate::utils::base58;
 
+/// The new pool transactions found by a refresh, if any, and the request timing
+pub type RefreshResult = (Option<Vec<String>>, Option<TimingResult>);
+
+/// The replies received from each node for a ledger action, and the request timing
+pub type LedgerActionResult = (RequestResult<NodeReplies<String>>, Option<TimingResult>);
+
 /// Perform a pool ledger status request to see if catchup is required
 pub async fn perform_pool_status_request<T: Pool>(
     pool: &T,
     merkle_tree: MerkleTree,
 ) -> VdrResult<(RequestResult<Option<CatchupTarget>>, Option<TimingResult>)> {
+    _pool_status_request(&LocalRequests(pool), merkle_tree).await
+}
+
+async fn _pool_status_request<F: RequestFactory>(
+    factory: &F,
+    merkle_tree: MerkleTree,
+) -> VdrResult<(RequestResult<Option<CatchupTarget>>, Option<TimingResult>)> {
+    let pool = factory.pool();
     let (mt_root, mt_size) = (merkle_tree.root_hash(), merkle_tree.count());
     let message = build_pool_status_request(mt_root, mt_size, pool.get_config().protocol_version)?;
     let req_json = message.serialize()?.to_string();
-    let mut request = pool.create_request("".to_string(), req_json).await?;
+    let mut request = factory.create_request("".to_string(), req_json).await?;
     handle_status_request(&mut request, merkle_tree).await
 }
 
@@ -33,19 +50,45 @@ pub async fn perform_pool_catchup_request<T: Pool>(
     merkle_tree: MerkleTree,
     target_mt_root: Vec<u8>,
     target_mt_size: usize,
+) -> VdrResult<(RequestResult<Vec<Vec<u8>>>, Option<TimingResult>)> {
+    _pool_catchup_request(
+        &LocalRequests(pool),
+        merkle_tree,
+        target_mt_root,
+        target_mt_size,
+    )
+    .await
+}
+
+async fn _pool_catchup_request<F: RequestFactory>(
+    factory: &F,
+    merkle_tree: MerkleTree,
+    target_mt_root: Vec<u8>,
+    target_mt_size: usize,
 ) -> VdrResult<(RequestResult<Vec<Vec<u8>>>, Option<TimingResult>)> {
     let message = build_pool_catchup_request(merkle_tree.count(), target_mt_size)?;
     let req_json = message.serialize()?.to_string();
-    let mut request = pool.create_request("".to_string(), req_json).await?;
+    let mut request = factory.create_request("".to_string(), req_json).await?;
     handle_catchup_request(&mut request, merkle_tree, target_mt_root, target_mt_size).await
 }
 
 /// Perform a pool ledger status request followed by a catchup request if necessary
-pub async fn perform_refresh<T: Pool>(
-    pool: &T,
-) -> VdrResult<(Option<Vec<String>>, Option<TimingResult>)> {
-    let merkle_tree = pool.get_merkle_tree().clone();
-    let (result, timing) = perform_pool_status_request(pool, merkle_tree.clone()).await?;
+pub async fn perform_refresh<T: Pool>(pool: &T) -> VdrResult<RefreshResult> {
+    _perform_refresh(&LocalRequests(pool)).await
+}
+
+/// Perform a pool refresh, producing a future which may be sent between threads
+pub fn perform_refresh_send<T>(pool: &T) -> BoxFuture<'_, VdrResult<RefreshResult>>
+where
+    T: SendPool,
+    T::Request: Send,
+{
+    async move { _perform_refresh(&SendRequests(pool)).await }.boxed()
+}
+
+async fn _perform_refresh<F: RequestFactory>(factory: &F) -> VdrResult<RefreshResult> {
+    let merkle_tree = factory.pool().get_merkle_tree();
+    let (result, timing) = _pool_status_request(factory, merkle_tree.clone()).await?;
     trace!("Got status result: {:?}", &result);
     match result {
         RequestResult::Reply(target) => match target {
@@ -57,7 +100,7 @@ pub async fn perform_refresh<T: Pool>(
                     timing
                 );
                 let (txns, timing) =
-                    perform_catchup(pool, merkle_tree, target_mt_root, target_mt_size).await?;
+                    _perform_catchup(factory, merkle_tree, target_mt_root, target_mt_size).await?;
                 Ok((Some(txns), timing))
             }
             _ => {
@@ -72,15 +115,14 @@ pub async fn perform_refresh<T: Pool>(
     }
 }
 
-pub(crate) async fn perform_catchup<T: Pool>(
-    pool: &T,
+async fn _perform_catchup<F: RequestFactory>(
+    factory: &F,
     merkle_tree: MerkleTree,
     target_mt_root: Vec<u8>,
     target_mt_size: usize,
 ) -> VdrResult<(Vec<String>, Option<TimingResult>)> {
     let (catchup_result, timing) =
-        perform_pool_catchup_request(pool, merkle_tree, target_mt_root.clone(), target_mt_size)
-            .await?;
+        _pool_catchup_request(factory, merkle_tree, target_mt_root.clone(), target_mt_size).await?;
     match catchup_result {
         RequestResult::Reply(ref txns) => {
             info!("Catchup completed {:?}", timing);
@@ -120,8 +162,44 @@ pub async fn perform_ledger_action<T: Pool>(
     req_json: String,
     node_aliases: Option<Vec<String>>,
     timeout: Option<i64>,
-) -> VdrResult<(RequestResult<NodeReplies<String>>, Option<TimingResult>)> {
-    let mut request = pool.create_request(req_id, req_json).await?;
+) -> VdrResult<LedgerActionResult> {
+    _perform_ledger_action(
+        &LocalRequests(pool),
+        req_id,
+        req_json,
+        node_aliases,
+        timeout,
+    )
+    .await
+}
+
+/// Dispatch a request to a specific set of nodes, producing a future which may be sent
+/// between threads
+pub fn perform_ledger_action_send<T>(
+    pool: &T,
+    req_id: String,
+    req_json: String,
+    node_aliases: Option<Vec<String>>,
+    timeout: Option<i64>,
+) -> BoxFuture<'_, VdrResult<LedgerActionResult>>
+where
+    T: SendPool,
+    T::Request: Send,
+{
+    async move {
+        _perform_ledger_action(&SendRequests(pool), req_id, req_json, node_aliases, timeout).await
+    }
+    .boxed()
+}
+
+async fn _perform_ledger_action<F: RequestFactory>(
+    factory: &F,
+    req_id: String,
+    req_json: String,
+    node_aliases: Option<Vec<String>>,
+    timeout: Option<i64>,
+) -> VdrResult<LedgerActionResult> {
+    let mut request = factory.create_request(req_id, req_json).await?;
     handle_full_request(&mut request, node_aliases, timeout).await
 }
 
@@ -130,7 +208,50 @@ pub async fn perform_ledger_request<T: Pool>(
     pool: &T,
     prepared: &PreparedRequest,
 ) -> VdrResult<(RequestResult<String>, Option<TimingResult>)> {
-    let mut request = pool
+    _perform_ledger_request(&LocalRequests(pool), prepared, None).await
+}
+
+/// Dispatch a prepared ledger request to the appropriate handler, also collecting
+/// a report of the replies received from each node
+///
+/// The report is only produced for requests resolved by consensus, which excludes
+/// requests using `RequestMethod::Full`.
+pub async fn perform_ledger_request_diagnostic<T: Pool>(
+    pool: &T,
+    prepared: &PreparedRequest,
+) -> VdrResult<(
+    RequestResult<String>,
+    Option<TimingResult>,
+    Option<ConsensusReport>,
+)> {
+    let mut report = ConsensusReport::default();
+    let (result, timing) =
+        _perform_ledger_request(&LocalRequests(pool), prepared, Some(&mut report)).await?;
+    let report = match prepared.method {
+        RequestMethod::Full { .. } => None,
+        _ => Some(report),
+    };
+    Ok((result, timing, report))
+}
+
+/// Dispatch a prepared ledger request, producing a future which may be sent between threads
+pub fn perform_ledger_request_send<'a, T>(
+    pool: &'a T,
+    prepared: &'a PreparedRequest,
+) -> BoxFuture<'a, VdrResult<(RequestResult<String>, Option<TimingResult>)>>
+where
+    T: SendPool,
+    T::Request: Send,
+{
+    async move { _perform_ledger_request(&SendRequests(pool), prepared, None).await }.boxed()
+}
+
+async fn _perform_ledger_request<F: RequestFactory>(
+    factory: &F,
+    prepared: &PreparedRequest,
+    report: Option<&mut ConsensusReport>,
+) -> VdrResult<(RequestResult<String>, Option<TimingResult>)> {
+    let mut request = factory
         .create_request(prepared.req_id.clone(), prepared.req_json.to_string())
         .await?;
 
@@ -151,11 +272,31 @@ pub async fn perform_ledger_request<T: Pool>(
             sp_parser,
             sp_timestamps,
         } => (None, sp_timestamps.clone(), true, Some(sp_parser)),
-        RequestMethod::ReadConsensus => (None, (None, None), true, None),
-        RequestMethod::Consensus => (None, (None, None), false, None),
+        RequestMethod::ReadConsensus | RequestMethod::Consensus => {
+            let is_read_req = prepared.method == RequestMethod::ReadConsensus;
+            // fall back to a state proof parser registered with the pool for the request type
+            match factory.pool().get_state_proof_parser(&prepared.txn_type) {
+                Some(sp_parser) => (
+                    None,
+                    parse_timestamp_from_req_for_builtin_sp(&prepared.req_json, &prepared.txn_type),
+                    true,
+                    Some(sp_parser),
+                ),
+                None => (None, (None, None), is_read_req, None),
+            }
+        }
     };
 
-    handle_consensus_request(&mut request, sp_key, sp_timestamps, is_read_req, sp_parser).await
+    handle_consensus_request(
+        &mut request,
+        sp_key,
+        sp_timestamps,
+        is_read_req,
+        sp_parser,
+        prepared.consensus_policy.as_ref(),
+        report,
+    )
+    .await
 }
 
 /// Format a collection of node replies in the expected response format
@@ -173,3 +314,124 @@ where
     )))
     .with_input_err("Error serializing response")
 }
+
+/// Abstracts the creation of pool requests, so that the request helpers may produce either
+/// local or `Send` futures depending on the pool implementation
+trait RequestFactory {
+    type Pool: Pool;
+    type Future: Future<Output = VdrResult<<Self::Pool as Pool>::Request>>;
+
+    fn pool(&self) -> &Self::Pool;
+
+    fn create_request(&self, req_id: String, req_json: String) -> Self::Future;
+}
+
+struct LocalRequests<'a, T>(&'a T);
+
+impl<'a, T: Pool> RequestFactory for LocalRequests<'a, T> {
+    type Pool = T;
+    type Future = LocalBoxFuture<'a, VdrResult<T::Request>>;
+
+    fn pool(&self) -> &T {
+        self.0
+    }
+
+    fn create_request(&self, req_id: String, req_json: String) -> Self::Future {
+        self.0.create_request(req_id, req_json)
+    }
+}
+
+struct SendRequests<'a, T>(&'a T);
+
+impl<'a, T: SendPool> RequestFactory for SendRequests<'a, T> {
+    type Pool = T;
+    type Future = BoxFuture<'a, VdrResult<T::Request>>;
+
+    fn pool(&self) -> &T {
+        self.0
+    }
+
+    fn create_request(&self, req_id: String, req_json: String) -> Self::Future {
+        self.0.create_send_request(req_id, req_json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    use futures_executor::block_on;
+
+    use crate::config::PoolConfig;
+    use crate::pool::networker::fixtures::*;
+    use crate::pool::networker::{
+        MakeLocal, MakeShared, SimulatedNetworkerFactory, SimulatedNode, SimulatedReply,
+    };
+    use crate::pool::{LocalPool, SharedPool};
+    use crate::state_proof::{state_proof_parser_fn, StateProofParser, StateProofParsers};
+
+    #[test]
+    fn test_send_request_works_across_threads() {
+        let pool = SharedPool::build(
+            PoolConfig::default(),
+            _merkle_tree(&_transactions()),
+            MakeShared(
+                SimulatedNetworkerFactory::new().default_node(SimulatedNode::replies(vec![
+                    SimulatedReply::Reply(_nym_result("verkey")),
+                ])),
+            ),
+            None,
+            None,
+        )
+        .unwrap();
+        let request = _nym_request();
+        let result = thread::spawn(move || {
+            block_on(perform_ledger_request_send(&pool, &request))
+                .unwrap()
+                .0
+        })
+        .join()
+        .unwrap();
+        match result {
+            RequestResult::Reply(reply) => assert_eq!(_reply_result(&reply), _nym_result("verkey")),
+            RequestResult::Failed(err) => panic!("Unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn test_pool_state_proof_parser_applies_to_request() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let parser_calls = calls.clone();
+        let mut parsers = StateProofParsers::new();
+        parsers.register(
+            "1",
+            state_proof_parser_fn(move |_, _| {
+                parser_calls.fetch_add(1, Ordering::SeqCst);
+                None
+            })
+            .boxed(),
+        );
+        let pool = LocalPool::build(
+            PoolConfig::default(),
+            _merkle_tree(&_transactions()),
+            MakeLocal(
+                SimulatedNetworkerFactory::new().default_node(SimulatedNode::replies(vec![
+                    SimulatedReply::Reply(_nym_result("verkey")),
+                ])),
+            ),
+            None,
+            Some(parsers),
+        )
+        .unwrap();
+        match _send_nym(&pool) {
+            RequestResult::Reply(reply) => assert_eq!(_reply_result(&reply), _nym_result("verkey")),
+            RequestResult::Failed(err) => panic!("Unexpected error: {}", err),
+        }
+        // the reply is only accepted by consensus, after the parser finds no state proof
+        assert!(calls.load(Ordering::SeqCst) > 0);
+    }
+}
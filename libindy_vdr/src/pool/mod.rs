@@ -8,20 +8,33 @@ pub mod helpers;
 pub mod networker;
 /// General verifier pool management
 mod pool;
+/// A registry of verifier pools for multiple ledgers
+mod registry;
 /// Data types and traits for handling pending verifier pool requests
 mod requests;
 /// A pool executor that processes events in its own thread
 mod runner;
+/// Strategies for selecting the verifier nodes contacted by a request
+mod selector;
+/// Persistent storage for refreshed pool transactions
+mod store;
 mod types;
 
 pub use self::builder::PoolBuilder;
 pub use self::genesis::PoolTransactions;
-pub use self::pool::{LocalPool, Pool, PoolImpl, SharedPool};
+#[cfg(all(feature = "tokio_networker", unix))]
+pub use self::pool::TokioPool;
+pub use self::pool::{LocalPool, Pool, PoolImpl, SendPool, SharedPool};
+pub use self::registry::PoolRegistry;
 pub use self::requests::{
-    new_request_id, PoolRequest, PoolRequestImpl, PreparedRequest, RequestMethod,
+    new_request_id, ConsensusPolicy, FreshnessPolicy, NodeQuorum, PoolRequest, PoolRequestImpl,
+    PreparedRequest, ReplyQuorum, RequestMethod,
 };
-pub use self::runner::{PoolRunner, PoolRunnerStatus};
+pub use self::runner::{PoolRunner, PoolRunnerStatus, RefreshListener};
+pub use self::selector::{AdaptiveNodeSelector, NodeLatency, NodeSelector, WeightedNodeSelector};
+pub use self::store::PoolTransactionsStore;
 pub use self::types::{
-    LedgerType, NodeReplies, PoolSetup, ProtocolVersion, RequestHandle, RequestResult, SingleReply,
+    ConsensusRejectGroup, ConsensusReplyGroup, ConsensusReport, LedgerType, NodeReplies,
+    PoolChanges, PoolSetup, ProtocolVersion, RequestHandle, RequestResult, SingleReply,
     TimingResult, VerifierInfo, VerifierKey, VerifierKeys, Verifiers,
 };
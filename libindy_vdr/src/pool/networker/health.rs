@@ -0,0 +1,399 @@
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::PoolConfig;
+
+/// The maximum exponent applied to the blacklist timeout for repeated failures
+const MAX_BACKOFF_EXPONENT: usize = 6;
+
+/// A failure attributed to a verifier node
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeFailure {
+    /// The node did not reply before the request timeout
+    Timeout,
+    /// The node sent a message which could not be parsed or was unexpected
+    Malformed,
+    /// The state proof returned by the node could not be verified
+    StateProof,
+    /// The state proof returned by the node was valid, but older than the freshness threshold
+    Stale,
+}
+
+/// The current standing of a verifier node
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeStatus {
+    /// The node is responding normally
+    Healthy,
+    /// The node has recently failed and is contacted after healthy nodes
+    Demoted,
+    /// The node has failed repeatedly and is not contacted until its back-off expires
+    Blacklisted,
+    /// The node's back-off has expired and it is being contacted again
+    Probing,
+}
+
+/// Health statistics collected for a verifier node
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeHealth {
+    /// The current standing of the node
+    pub status: NodeStatus,
+    /// The number of messages received from the node, excluding acknowledgements and rejections
+    pub replies: usize,
+    /// The number of requests rejected by the node (`REQNACK` or `REJECT`)
+    pub rejected: usize,
+    /// The number of request timeouts
+    pub timeouts: usize,
+    /// The number of malformed or unexpected messages
+    pub malformed: usize,
+    /// The number of failed state proof verifications
+    pub state_proof_failures: usize,
+    /// The number of valid state proofs which failed the freshness check
+    pub stale_replies: usize,
+    /// The number of failures since the last successful reply
+    pub consecutive_failures: usize,
+    /// The number of times the node has been blacklisted
+    pub blacklist_count: usize,
+    /// The time (in seconds since the Unix epoch) at which a blacklisted node will be probed
+    pub blacklisted_until: Option<u64>,
+}
+
+impl Default for NodeHealth {
+    fn default() -> Self {
+        Self {
+            status: NodeStatus::Healthy,
+            replies: 0,
+            rejected: 0,
+            timeouts: 0,
+            malformed: 0,
+            state_proof_failures: 0,
+            stale_replies: 0,
+            consecutive_failures: 0,
+            blacklist_count: 0,
+            blacklisted_until: None,
+        }
+    }
+}
+
+impl NodeHealth {
+    /// Get the dispatch priority of the node, where lower values are contacted first
+    pub fn priority(&self, now: u64) -> usize {
+        match self.status {
+            NodeStatus::Healthy | NodeStatus::Probing => 0,
+            NodeStatus::Demoted => 1,
+            NodeStatus::Blacklisted if self.blacklisted_until.unwrap_or(0) <= now => 1,
+            NodeStatus::Blacklisted => 2,
+        }
+    }
+}
+
+/// Health statistics for each verifier node, indexed by alias
+pub type NodeHealthTable = BTreeMap<String, NodeHealth>;
+
+/// Track node replies and failures, blacklisting nodes which fail consistently
+#[derive(Debug)]
+pub(crate) struct HealthTracker {
+    threshold: usize,
+    timeout: u64,
+    max_blacklisted: usize,
+    nodes: NodeHealthTable,
+}
+
+impl HealthTracker {
+    pub fn new<I>(config: &PoolConfig, node_aliases: I) -> Self
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let nodes = node_aliases
+            .into_iter()
+            .map(|alias| (alias, NodeHealth::default()))
+            .collect::<NodeHealthTable>();
+        Self {
+            threshold: config.node_blacklist_threshold,
+            timeout: std::cmp::max(config.node_blacklist_timeout, 0) as u64,
+            max_blacklisted: _max_blacklisted(nodes.len()),
+            nodes,
+        }
+    }
+
+    /// Replace the set of tracked nodes, retaining the statistics for existing nodes
+    pub fn update_nodes<I>(&mut self, node_aliases: I)
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut prev = std::mem::take(&mut self.nodes);
+        self.nodes = node_aliases
+            .into_iter()
+            .map(|alias| {
+                let health = prev.remove(&alias).unwrap_or_default();
+                (alias, health)
+            })
+            .collect();
+        self.max_blacklisted = _max_blacklisted(self.nodes.len());
+    }
+
+    pub fn table(&self) -> NodeHealthTable {
+        self.nodes.clone()
+    }
+
+    /// Count a message received from a node, before it has been checked by the request handler
+    pub fn record_reply(&mut self, node_alias: &str) {
+        if let Some(node) = self.nodes.get_mut(node_alias) {
+            node.replies += 1;
+        }
+    }
+
+    /// Record a reply which was accepted by the request handler, reinstating the node
+    pub fn record_success(&mut self, node_alias: &str) {
+        if let Some(node) = self.nodes.get_mut(node_alias) {
+            node.consecutive_failures = 0;
+            if node.status != NodeStatus::Healthy {
+                debug!("Node reinstated: {}", node_alias);
+                node.status = NodeStatus::Healthy;
+                node.blacklisted_until = None;
+            }
+        }
+    }
+
+    pub fn record_rejected(&mut self, node_alias: &str) {
+        // rejections are usually caused by the request, so the node status is unaffected
+        if let Some(node) = self.nodes.get_mut(node_alias) {
+            node.rejected += 1;
+        }
+    }
+
+    pub fn record_failure(&mut self, node_alias: &str, failure: NodeFailure, now: u64) {
+        let blacklisted = self.blacklisted_count();
+        let (threshold, timeout) = (self.threshold, self.timeout);
+        let node = match self.nodes.get_mut(node_alias) {
+            Some(node) => node,
+            None => return,
+        };
+        match failure {
+            NodeFailure::Timeout => node.timeouts += 1,
+            NodeFailure::Malformed => node.malformed += 1,
+            NodeFailure::StateProof => node.state_proof_failures += 1,
+            NodeFailure::Stale => {
+                // a lagging node is not faulty, so its status is unaffected
+                node.stale_replies += 1;
+                return;
+            }
+        }
+        node.consecutive_failures += 1;
+        let blacklist = match node.status {
+            NodeStatus::Probing => true,
+            NodeStatus::Blacklisted => false,
+            NodeStatus::Healthy | NodeStatus::Demoted => {
+                threshold > 0
+                    && node.consecutive_failures >= threshold
+                    && blacklisted < self.max_blacklisted
+            }
+        };
+        if blacklist {
+            let exponent = std::cmp::min(
+                node.consecutive_failures.saturating_sub(threshold),
+                MAX_BACKOFF_EXPONENT,
+            );
+            let until = now + (timeout << exponent);
+            debug!(
+                "Node blacklisted after {} failures: {}, until {}",
+                node.consecutive_failures, node_alias, until
+            );
+            node.status = NodeStatus::Blacklisted;
+            node.blacklisted_until = Some(until);
+            node.blacklist_count += 1;
+        } else if node.status == NodeStatus::Healthy {
+            debug!("Node demoted after {:?}: {}", failure, node_alias);
+            node.status = NodeStatus::Demoted;
+        }
+    }
+
+    /// Check whether a request may be sent to a node, starting a probe if its back-off has expired
+    pub fn check_dispatch(&mut self, node_alias: &str, now: u64) -> bool {
+        match self.nodes.get_mut(node_alias) {
+            Some(node) if node.status == NodeStatus::Blacklisted => {
+                if node.blacklisted_until.unwrap_or(0) <= now {
+                    debug!("Probing blacklisted node: {}", node_alias);
+                    node.status = NodeStatus::Probing;
+                    true
+                } else {
+                    false
+                }
+            }
+            _ => true,
+        }
+    }
+
+    fn blacklisted_count(&self) -> usize {
+        self.nodes
+            .values()
+            .filter(|node| matches!(node.status, NodeStatus::Blacklisted | NodeStatus::Probing))
+            .count()
+    }
+}
+
+/// Never blacklist more nodes than the pool can tolerate as faulty
+fn _max_blacklisted(node_count: usize) -> usize {
+    if node_count < 4 {
+        0
+    } else {
+        (node_count - 1) / 3
+    }
+}
+
+/// Move demoted and blacklisted nodes to the end of a node ordering
+pub(crate) fn order_by_health(nodes: &mut [String], health: &NodeHealthTable) {
+    if health.is_empty() {
+        return;
+    }
+    let now = unix_now();
+    nodes.sort_by_key(|alias| {
+        health
+            .get(alias)
+            .map(|node| node.priority(now))
+            .unwrap_or(0)
+    });
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|dur| dur.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NODES: [&str; 4] = ["Node1", "Node2", "Node3", "Node4"];
+
+    fn _tracker() -> HealthTracker {
+        let config = PoolConfig {
+            node_blacklist_threshold: 2,
+            node_blacklist_timeout: 10,
+            ..PoolConfig::default()
+        };
+        HealthTracker::new(&config, NODES.iter().map(|alias| alias.to_string()))
+    }
+
+    #[test]
+    fn test_failure_demotes_node() {
+        let mut tracker = _tracker();
+        tracker.record_failure(NODES[0], NodeFailure::Timeout, 100);
+        let node = &tracker.table()[NODES[0]];
+        assert_eq!(node.status, NodeStatus::Demoted);
+        assert_eq!(node.timeouts, 1);
+        assert!(tracker.check_dispatch(NODES[0], 100));
+
+        tracker.record_success(NODES[0]);
+        let node = &tracker.table()[NODES[0]];
+        assert_eq!(node.status, NodeStatus::Healthy);
+        assert_eq!(node.consecutive_failures, 0);
+    }
+
+    #[test]
+    fn test_rejection_keeps_status() {
+        let mut tracker = _tracker();
+        tracker.record_rejected(NODES[0]);
+        tracker.record_rejected(NODES[0]);
+        let node = &tracker.table()[NODES[0]];
+        assert_eq!(node.status, NodeStatus::Healthy);
+        assert_eq!(node.rejected, 2);
+    }
+
+    #[test]
+    fn test_blacklist_backoff_and_probe() {
+        let mut tracker = _tracker();
+        tracker.record_failure(NODES[0], NodeFailure::Timeout, 100);
+        tracker.record_failure(NODES[0], NodeFailure::StateProof, 100);
+        let node = &tracker.table()[NODES[0]];
+        assert_eq!(node.status, NodeStatus::Blacklisted);
+        assert_eq!(node.blacklisted_until, Some(110));
+        assert!(!tracker.check_dispatch(NODES[0], 105));
+
+        // failed probe doubles the back-off
+        assert!(tracker.check_dispatch(NODES[0], 110));
+        assert_eq!(tracker.table()[NODES[0]].status, NodeStatus::Probing);
+        tracker.record_failure(NODES[0], NodeFailure::Timeout, 110);
+        let node = &tracker.table()[NODES[0]];
+        assert_eq!(node.status, NodeStatus::Blacklisted);
+        assert_eq!(node.blacklisted_until, Some(130));
+        assert_eq!(node.blacklist_count, 2);
+
+        // successful probe reinstates the node
+        assert!(tracker.check_dispatch(NODES[0], 130));
+        tracker.record_success(NODES[0]);
+        let node = &tracker.table()[NODES[0]];
+        assert_eq!(node.status, NodeStatus::Healthy);
+        assert_eq!(node.blacklisted_until, None);
+    }
+
+    #[test]
+    fn test_state_proof_failures_blacklist_node() {
+        let config = PoolConfig {
+            node_blacklist_threshold: 3,
+            node_blacklist_timeout: 10,
+            ..PoolConfig::default()
+        };
+        let mut tracker = HealthTracker::new(&config, NODES.iter().map(|alias| alias.to_string()));
+        for _ in 0..3 {
+            // receiving the reply does not reset the failure count
+            tracker.record_reply(NODES[0]);
+            tracker.record_failure(NODES[0], NodeFailure::StateProof, 100);
+        }
+        let node = &tracker.table()[NODES[0]];
+        assert_eq!(node.status, NodeStatus::Blacklisted);
+        assert_eq!(node.replies, 3);
+        assert_eq!(node.state_proof_failures, 3);
+    }
+
+    #[test]
+    fn test_stale_reply_keeps_status() {
+        let mut tracker = _tracker();
+        tracker.record_failure(NODES[0], NodeFailure::Stale, 100);
+        tracker.record_failure(NODES[0], NodeFailure::Stale, 100);
+        let node = &tracker.table()[NODES[0]];
+        assert_eq!(node.status, NodeStatus::Healthy);
+        assert_eq!(node.stale_replies, 2);
+        assert_eq!(node.consecutive_failures, 0);
+    }
+
+    #[test]
+    fn test_blacklist_limited_to_faulty_nodes() {
+        let mut tracker = _tracker();
+        for alias in &NODES[..2] {
+            tracker.record_failure(alias, NodeFailure::Timeout, 100);
+            tracker.record_failure(alias, NodeFailure::Timeout, 100);
+        }
+        let table = tracker.table();
+        assert_eq!(table[NODES[0]].status, NodeStatus::Blacklisted);
+        assert_eq!(table[NODES[1]].status, NodeStatus::Demoted);
+    }
+
+    #[test]
+    fn test_update_nodes_retains_health() {
+        let mut tracker = _tracker();
+        tracker.record_failure(NODES[0], NodeFailure::Timeout, 100);
+        tracker.update_nodes(vec![NODES[0].to_string(), "Node5".to_string()]);
+        let table = tracker.table();
+        assert_eq!(table.len(), 2);
+        assert_eq!(table[NODES[0]].status, NodeStatus::Demoted);
+        assert_eq!(table["Node5"], NodeHealth::default());
+    }
+
+    #[test]
+    fn test_order_by_health() {
+        let mut tracker = _tracker();
+        tracker.record_failure(NODES[0], NodeFailure::Timeout, unix_now());
+        tracker.record_failure(NODES[0], NodeFailure::Timeout, unix_now());
+        tracker.record_failure(NODES[1], NodeFailure::Malformed, unix_now());
+        let mut order = NODES
+            .iter()
+            .map(|alias| alias.to_string())
+            .collect::<Vec<_>>();
+        order_by_health(&mut order, &tracker.table());
+        assert_eq!(order, vec!["Node3", "Node4", "Node2", "Node1"]);
+    }
+}
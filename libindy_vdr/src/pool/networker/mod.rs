@@ -9,6 +9,28 @@ use crate::config::types::PoolConfig;
 use super::requests::RequestExtEvent;
 use super::types::{self, RequestHandle, Verifiers};
 
+mod health;
+pub(crate) use self::health::{order_by_health, unix_now, HealthTracker};
+pub use self::health::{NodeFailure, NodeHealth, NodeHealthTable, NodeStatus};
+
+mod record;
+pub use self::record::{
+    RecordedEvent, RecordingNetworker, RecordingNetworkerFactory, ReplayNetworker,
+    ReplayNetworkerFactory,
+};
+
+mod simulated;
+#[cfg(test)]
+pub(crate) use self::simulated::fixtures;
+pub use self::simulated::{
+    SimulatedHandler, SimulatedNetworker, SimulatedNetworkerFactory, SimulatedNode, SimulatedReply,
+};
+
+#[cfg(all(feature = "tokio_networker", unix))]
+mod tokio_zmq;
+#[cfg(all(feature = "tokio_networker", unix))]
+pub use self::tokio_zmq::{TokioNetworker, TokioNetworkerFactory};
+
 mod zmq;
 pub use self::zmq::{ZMQNetworker, ZMQNetworkerFactory};
 
@@ -36,11 +58,24 @@ pub enum NetworkerEvent {
         String, // node alias
         i64,    // timeout
     ),
+    ReportFailure(
+        String, // node alias
+        NodeFailure,
+    ),
+    ReportSuccess(
+        String, // node alias
+    ),
+    UpdateVerifiers(Verifiers),
 }
 
 /// A simple trait implemented by all networker types
 pub trait Networker {
     fn send(&self, event: NetworkerEvent) -> VdrResult<()>;
+
+    /// Get the current health statistics for the verifier nodes, if tracked
+    fn node_health(&self) -> VdrResult<NodeHealthTable> {
+        Ok(NodeHealthTable::new())
+    }
 }
 
 /// A factory for `Networker` instances
@@ -73,6 +108,10 @@ where
     fn send(&self, event: NetworkerEvent) -> VdrResult<()> {
         self.as_ref().send(event)
     }
+
+    fn node_health(&self) -> VdrResult<NodeHealthTable> {
+        self.as_ref().node_health()
+    }
 }
 
 /// A `Networker` instance which can be cloned and used across multiple threads
@@ -105,6 +144,17 @@ impl Networker for Arc<Mutex<dyn Networker + Send>> {
             })?
             .send(event)
     }
+
+    fn node_health(&self) -> VdrResult<NodeHealthTable> {
+        self.lock()
+            .map_err(|_| {
+                err_msg(
+                    VdrErrorKind::Unexpected,
+                    "Error acquiring networker, mutex poisoned",
+                )
+            })?
+            .node_health()
+    }
 }
 
 /*
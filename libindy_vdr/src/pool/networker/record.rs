@@ -0,0 +1,605 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures_executor::LocalPool;
+use futures_util::stream::StreamExt;
+use futures_util::task::LocalSpawnExt;
+
+use crate::common::error::prelude::*;
+use crate::config::PoolConfig;
+
+use super::types::{Message, Verifiers};
+use super::{
+    Networker, NetworkerEvent, NetworkerFactory, NodeHealthTable, RequestExtEvent, RequestHandle,
+};
+
+/// A single entry in a recording of node traffic
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum RecordedEvent {
+    /// A new request was submitted to the networker
+    Request {
+        req_id: String,
+        body: String,
+        time: u64,
+    },
+    /// The request was sent to a node
+    Sent {
+        req_id: String,
+        node_alias: String,
+        time: u64,
+    },
+    /// A message was received from a node
+    Received {
+        req_id: String,
+        node_alias: String,
+        message: String,
+        time: u64,
+    },
+    /// A node did not reply before the request timeout
+    Timeout {
+        req_id: String,
+        node_alias: String,
+        time: u64,
+    },
+}
+
+impl RecordedEvent {
+    /// Get the request identifier of the event
+    pub fn req_id(&self) -> &str {
+        match self {
+            Self::Request { req_id, .. }
+            | Self::Sent { req_id, .. }
+            | Self::Received { req_id, .. }
+            | Self::Timeout { req_id, .. } => req_id,
+        }
+    }
+
+    /// Get the node alias of the event, if any
+    pub fn node_alias(&self) -> Option<&str> {
+        match self {
+            Self::Request { .. } => None,
+            Self::Sent { node_alias, .. }
+            | Self::Received { node_alias, .. }
+            | Self::Timeout { node_alias, .. } => Some(node_alias),
+        }
+    }
+
+    /// Load a list of events from a JSONL recording
+    pub fn read_jsonl<P: AsRef<Path>>(path: P) -> VdrResult<Vec<Self>> {
+        let file = File::open(path.as_ref())
+            .map_input_err(|| format!("Error opening recording: {}", path.as_ref().display()))?;
+        Self::from_reader(file)
+    }
+
+    /// Parse a list of events from a JSONL reader
+    pub fn from_reader<R: std::io::Read>(reader: R) -> VdrResult<Vec<Self>> {
+        let mut events = vec![];
+        for (idx, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line.with_input_err("Error reading recording")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            events.push(
+                serde_json::from_str(&line)
+                    .map_input_err(|| format!("Invalid recorded event on line {}", idx + 1))?,
+            );
+        }
+        Ok(events)
+    }
+
+    fn from_ext_event(req_id: &str, event: &RequestExtEvent) -> Option<Self> {
+        let req_id = req_id.to_owned();
+        match event {
+            RequestExtEvent::Init | RequestExtEvent::Error(_) => None,
+            RequestExtEvent::Sent(node_alias, time) => Some(Self::Sent {
+                req_id,
+                node_alias: node_alias.clone(),
+                time: _to_millis(*time),
+            }),
+            RequestExtEvent::Received(node_alias, message, _, time) => Some(Self::Received {
+                req_id,
+                node_alias: node_alias.clone(),
+                message: message.clone(),
+                time: _to_millis(*time),
+            }),
+            RequestExtEvent::Timeout(node_alias) => Some(Self::Timeout {
+                req_id,
+                node_alias: node_alias.clone(),
+                time: _to_millis(SystemTime::now()),
+            }),
+        }
+    }
+}
+
+type RecordWriter = Arc<Mutex<dyn Write + Send>>;
+
+/// A `NetworkerFactory` wrapper which records all node traffic to a JSONL file
+pub struct RecordingNetworkerFactory<T: NetworkerFactory> {
+    inner: T,
+    writer: RecordWriter,
+}
+
+impl<T: NetworkerFactory> RecordingNetworkerFactory<T> {
+    /// Wrap a factory, appending recorded events to the file at `path`
+    pub fn new<P: AsRef<Path>>(inner: T, path: P) -> VdrResult<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.as_ref())
+            .map_input_err(|| format!("Error opening recording: {}", path.as_ref().display()))?;
+        Ok(Self::with_writer(inner, file))
+    }
+
+    /// Wrap a factory, writing recorded events to an arbitrary output
+    pub fn with_writer<W: Write + Send + 'static>(inner: T, writer: W) -> Self {
+        Self {
+            inner,
+            writer: Arc::new(Mutex::new(writer)),
+        }
+    }
+}
+
+impl<T> NetworkerFactory for RecordingNetworkerFactory<T>
+where
+    T: NetworkerFactory,
+{
+    type Output = RecordingNetworker<T::Output>;
+    fn make_networker(
+        &self,
+        config: PoolConfig,
+        verifiers: &Verifiers,
+    ) -> VdrResult<RecordingNetworker<T::Output>> {
+        let inner = self.inner.make_networker(config, verifiers)?;
+        let (cmd_send, cmd_recv) = unbounded::<ForwardRequest>();
+        let writer = self.writer.clone();
+        let worker = thread::spawn(move || {
+            let mut pool = LocalPool::new();
+            let spawner = pool.spawner();
+            pool.run_until(cmd_recv.for_each(|forward| {
+                let writer = writer.clone();
+                if spawner
+                    .spawn_local(_forward_events(forward, writer))
+                    .is_err()
+                {
+                    warn!("Error spawning recorder task");
+                }
+                futures_util::future::ready(())
+            }));
+            // wait for the remaining requests to be released by the inner networker
+            pool.run();
+            trace!("Recording networker exited");
+        });
+        Ok(RecordingNetworker {
+            inner: Some(inner),
+            writer: self.writer.clone(),
+            cmd_send,
+            worker: Some(worker),
+        })
+    }
+}
+
+/// A `Networker` wrapper which records all node traffic
+pub struct RecordingNetworker<T: Networker> {
+    inner: Option<T>,
+    writer: RecordWriter,
+    cmd_send: UnboundedSender<ForwardRequest>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl<T: Networker> Networker for RecordingNetworker<T> {
+    fn send(&self, event: NetworkerEvent) -> VdrResult<()> {
+        let inner = self
+            .inner
+            .as_ref()
+            .ok_or_else(|| err_msg(VdrErrorKind::Unexpected, "Networker already closed"))?;
+        match event {
+            NetworkerEvent::NewRequest(handle, req_id, body, sender) => {
+                _write_event(
+                    &self.writer,
+                    &RecordedEvent::Request {
+                        req_id: req_id.clone(),
+                        body: body.clone(),
+                        time: _to_millis(SystemTime::now()),
+                    },
+                );
+                let (fwd_send, fwd_recv) = unbounded();
+                self.cmd_send
+                    .unbounded_send(ForwardRequest {
+                        req_id: req_id.clone(),
+                        events: fwd_recv,
+                        sender,
+                    })
+                    .map_err(|_| {
+                        err_msg(VdrErrorKind::Resource, "Error sending recorder command")
+                    })?;
+                inner.send(NetworkerEvent::NewRequest(handle, req_id, body, fwd_send))
+            }
+            event => inner.send(event),
+        }
+    }
+
+    fn node_health(&self) -> VdrResult<NodeHealthTable> {
+        match self.inner.as_ref() {
+            Some(inner) => inner.node_health(),
+            None => Ok(NodeHealthTable::new()),
+        }
+    }
+}
+
+impl<T: Networker> Drop for RecordingNetworker<T> {
+    fn drop(&mut self) {
+        // the inner networker must release its request senders before the recorder can finish
+        self.inner.take();
+        self.cmd_send.close_channel();
+        if let Some(worker) = self.worker.take() {
+            debug!("Drop recording networker thread");
+            worker.join().unwrap()
+        }
+    }
+}
+
+struct ForwardRequest {
+    req_id: String,
+    events: UnboundedReceiver<RequestExtEvent>,
+    sender: UnboundedSender<RequestExtEvent>,
+}
+
+async fn _forward_events(forward: ForwardRequest, writer: RecordWriter) {
+    let ForwardRequest {
+        req_id,
+        mut events,
+        sender,
+    } = forward;
+    while let Some(event) = events.next().await {
+        if let Some(recorded) = RecordedEvent::from_ext_event(&req_id, &event) {
+            _write_event(&writer, &recorded);
+        }
+        if sender.unbounded_send(event).is_err() {
+            trace!("Recorded request dropped: {}", req_id);
+            break;
+        }
+    }
+}
+
+fn _write_event(writer: &RecordWriter, event: &RecordedEvent) {
+    let line = match serde_json::to_string(event) {
+        Ok(line) => line,
+        Err(err) => {
+            warn!("Error serializing recorded event: {}", err);
+            return;
+        }
+    };
+    match writer.lock() {
+        Ok(mut writer) => {
+            if let Err(err) = writeln!(writer, "{}", line).and_then(|_| writer.flush()) {
+                warn!("Error writing recorded event: {}", err);
+            }
+        }
+        Err(_) => warn!("Error acquiring recording writer, mutex poisoned"),
+    }
+}
+
+/// A `NetworkerFactory` which replays recorded node traffic instead of contacting the nodes
+///
+/// Requests are matched to recordings by request ID, falling back to the order in
+/// which the requests were recorded. Dispatching to a node delivers the messages
+/// recorded for that node, or a timeout if the node was not contacted in the recording.
+#[derive(Clone, Debug, Default)]
+pub struct ReplayNetworkerFactory {
+    events: Arc<Vec<RecordedEvent>>,
+}
+
+impl ReplayNetworkerFactory {
+    /// Create a factory replaying a list of recorded events
+    pub fn new(events: Vec<RecordedEvent>) -> Self {
+        Self {
+            events: Arc::new(events),
+        }
+    }
+
+    /// Create a factory replaying a JSONL recording
+    pub fn from_file<P: AsRef<Path>>(path: P) -> VdrResult<Self> {
+        Ok(Self::new(RecordedEvent::read_jsonl(path)?))
+    }
+
+    /// Get the request IDs and message bodies of the recorded requests, in order
+    pub fn requests(&self) -> Vec<(String, String)> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                RecordedEvent::Request { req_id, body, .. } => Some((req_id.clone(), body.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl NetworkerFactory for ReplayNetworkerFactory {
+    type Output = ReplayNetworker;
+    fn make_networker(
+        &self,
+        _config: PoolConfig,
+        _verifiers: &Verifiers,
+    ) -> VdrResult<ReplayNetworker> {
+        let pending = self
+            .requests()
+            .into_iter()
+            .map(|(req_id, _)| req_id)
+            .collect();
+        Ok(ReplayNetworker {
+            events: self.events.clone(),
+            state: Mutex::new(ReplayState {
+                pending,
+                requests: HashMap::new(),
+            }),
+        })
+    }
+}
+
+/// Replaying `Networker` implementation
+pub struct ReplayNetworker {
+    events: Arc<Vec<RecordedEvent>>,
+    state: Mutex<ReplayState>,
+}
+
+struct ReplayState {
+    pending: VecDeque<String>,
+    requests: HashMap<RequestHandle, ReplayRequest>,
+}
+
+struct ReplayRequest {
+    req_id: Option<String>,
+    sender: UnboundedSender<RequestExtEvent>,
+    dispatched: HashSet<String>,
+}
+
+impl Networker for ReplayNetworker {
+    fn send(&self, event: NetworkerEvent) -> VdrResult<()> {
+        let mut state = self.state.lock().map_err(|_| {
+            err_msg(
+                VdrErrorKind::Unexpected,
+                "Error acquiring replay state, mutex poisoned",
+            )
+        })?;
+        match event {
+            NetworkerEvent::NewRequest(handle, req_id, _body, sender) => {
+                let recorded = if let Some(pos) = state.pending.iter().position(|r| *r == req_id) {
+                    state.pending.remove(pos)
+                } else {
+                    state.pending.pop_front()
+                };
+                if recorded.is_none() {
+                    warn!("No recorded traffic for request: {}", req_id);
+                }
+                if sender.unbounded_send(RequestExtEvent::Init).is_ok() {
+                    state.requests.insert(
+                        handle,
+                        ReplayRequest {
+                            req_id: recorded,
+                            sender,
+                            dispatched: HashSet::new(),
+                        },
+                    );
+                }
+            }
+            NetworkerEvent::Dispatch(handle, node_aliases, _timeout) => {
+                if let Some(request) = state.requests.get_mut(&handle) {
+                    for node_alias in node_aliases {
+                        if request.dispatched.insert(node_alias.clone()) {
+                            self.replay_node(request, &node_alias);
+                        }
+                    }
+                } else {
+                    debug!("Unknown request ID for dispatch: {}", handle);
+                }
+            }
+            NetworkerEvent::FinishRequest(handle) => {
+                trace!("Removing, finished {}", handle);
+                state.requests.remove(&handle);
+            }
+            NetworkerEvent::CleanTimeout(..)
+            | NetworkerEvent::ExtendTimeout(..)
+            | NetworkerEvent::ReportFailure(..)
+            | NetworkerEvent::ReportSuccess(..)
+            | NetworkerEvent::UpdateVerifiers(..) => (),
+        }
+        Ok(())
+    }
+}
+
+impl ReplayNetworker {
+    fn replay_node(&self, request: &ReplayRequest, node_alias: &str) {
+        let mut sent = false;
+        let mut replied = false;
+        let req_id = request.req_id.as_deref();
+        for event in self.events.iter().filter(|event| {
+            Some(event.req_id()) == req_id && event.node_alias() == Some(node_alias)
+        }) {
+            let ext_event = match event {
+                RecordedEvent::Sent { time, .. } if !sent => {
+                    sent = true;
+                    RequestExtEvent::Sent(node_alias.to_owned(), _from_millis(*time))
+                }
+                RecordedEvent::Received { message, time, .. } => {
+                    match Message::from_raw_str(message) {
+                        Ok(parsed) => {
+                            replied = true;
+                            RequestExtEvent::Received(
+                                node_alias.to_owned(),
+                                message.clone(),
+                                parsed,
+                                _from_millis(*time),
+                            )
+                        }
+                        Err(err) => {
+                            debug!("Skipping invalid recorded message: {}", err);
+                            continue;
+                        }
+                    }
+                }
+                RecordedEvent::Timeout { .. } => {
+                    replied = true;
+                    RequestExtEvent::Timeout(node_alias.to_owned())
+                }
+                _ => continue,
+            };
+            if !sent {
+                sent = true;
+                let _ = request.sender.unbounded_send(RequestExtEvent::Sent(
+                    node_alias.to_owned(),
+                    SystemTime::now(),
+                ));
+            }
+            let _ = request.sender.unbounded_send(ext_event);
+        }
+        if !sent {
+            let _ = request.sender.unbounded_send(RequestExtEvent::Sent(
+                node_alias.to_owned(),
+                SystemTime::now(),
+            ));
+        }
+        if !replied {
+            trace!("No recorded reply from {}, replaying timeout", node_alias);
+            let _ = request
+                .sender
+                .unbounded_send(RequestExtEvent::Timeout(node_alias.to_owned()));
+        }
+    }
+}
+
+fn _to_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|dur| dur.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn _from_millis(millis: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_executor::block_on;
+    use indy_test_utils::genesis::GenesisTransactions;
+
+    use crate::pool::helpers::perform_ledger_request;
+    use crate::pool::networker::{
+        MakeLocal, SimulatedNetworkerFactory, SimulatedNode, SimulatedReply,
+    };
+    use crate::pool::{LocalPool, Pool, PoolTransactions, PreparedRequest, RequestResult};
+    use crate::utils::did::DidValue;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn _pool<F>(factory: F) -> LocalPool
+    where
+        F: NetworkerFactory,
+        F::Output: 'static,
+    {
+        let txns = GenesisTransactions::new(Some(4)).transactions;
+        let merkle_tree = PoolTransactions::from_json_transactions(&txns)
+            .unwrap()
+            .merkle_tree()
+            .unwrap();
+        LocalPool::build(
+            PoolConfig::default(),
+            merkle_tree,
+            MakeLocal(factory),
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    fn _nym_request(pool: &LocalPool) -> PreparedRequest {
+        pool.get_request_builder()
+            .build_nym_request(
+                &DidValue("V4SGRU86Z58d6TV7PBUe6f".to_owned()),
+                &DidValue("VsKV7grR1BUE29mG2Fm2kX".to_owned()),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+    }
+
+    fn _reply(pool: &LocalPool, request: &PreparedRequest) -> String {
+        match block_on(perform_ledger_request(pool, request)).unwrap().0 {
+            RequestResult::Reply(reply) => reply,
+            RequestResult::Failed(err) => panic!("Unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn test_recorded_request_replays() {
+        let buffer = SharedBuffer::default();
+        let simulated =
+            SimulatedNetworkerFactory::new().default_node(SimulatedNode::replies(vec![
+                SimulatedReply::Ack,
+                SimulatedReply::Reply(json!({"type": "1", "dest": "VsKV7grR1BUE29mG2Fm2kX"})),
+            ]));
+        let (request, reply) = {
+            let pool = _pool(RecordingNetworkerFactory::with_writer(
+                simulated,
+                buffer.clone(),
+            ));
+            let request = _nym_request(&pool);
+            let reply = _reply(&pool, &request);
+            (request, reply)
+        };
+
+        let events = RecordedEvent::from_reader(buffer.0.lock().unwrap().as_slice()).unwrap();
+        assert!(matches!(events[0], RecordedEvent::Request { .. }));
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(event, RecordedEvent::Sent { .. }))
+                .count(),
+            4
+        );
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, RecordedEvent::Received { .. })));
+
+        let factory = ReplayNetworkerFactory::new(events);
+        let (req_id, body) = factory.requests().remove(0);
+        assert_eq!(req_id, request.req_id);
+        let pool = _pool(factory);
+        let replayed = PreparedRequest::from_request_json(&body).unwrap();
+        assert_eq!(_reply(&pool, &replayed), reply);
+    }
+
+    #[test]
+    fn test_replay_times_out_unrecorded_nodes() {
+        let pool = _pool(ReplayNetworkerFactory::default());
+        let request = _nym_request(&pool);
+        match block_on(perform_ledger_request(&pool, &request)).unwrap().0 {
+            RequestResult::Failed(err) => {
+                assert!(matches!(err.kind(), VdrErrorKind::PoolTimeout))
+            }
+            RequestResult::Reply(reply) => panic!("Unexpected reply: {}", reply),
+        }
+    }
+}
@@ -0,0 +1,767 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::iter::FromIterator;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use futures_channel::mpsc::UnboundedSender;
+use serde_json::Value as SJsonValue;
+
+use crate::common::error::prelude::*;
+use crate::config::PoolConfig;
+
+use super::types::{CatchupRep, ConsistencyProof, LedgerStatus, LedgerType, Message, Verifiers};
+use super::{Networker, NetworkerEvent, NetworkerFactory, RequestExtEvent, RequestHandle};
+
+/// A message produced by a simulated verifier node in response to a request
+#[derive(Clone, Debug)]
+pub enum SimulatedReply {
+    /// Acknowledge the request (`REQACK`)
+    Ack,
+    /// Refuse the request (`REQNACK`) with the given reason
+    Nack(String),
+    /// Reject the request (`REJECT`) with the given reason
+    Reject(String),
+    /// Send a `REPLY` with the given `result` object
+    Reply(SJsonValue),
+    /// Send a pool `LEDGER_STATUS` message
+    LedgerStatus {
+        txn_seq_no: usize,
+        merkle_root: String,
+    },
+    /// Send a pool `CONSISTENCY_PROOF` message
+    ConsistencyProof {
+        seq_no_start: usize,
+        seq_no_end: usize,
+        old_merkle_root: String,
+        new_merkle_root: String,
+        hashes: Vec<String>,
+    },
+    /// Send a pool `CATCHUP_REP` message with transactions indexed by sequence number
+    CatchupRep {
+        txns: BTreeMap<usize, SJsonValue>,
+        cons_proof: Vec<String>,
+    },
+    /// Send a raw message body
+    Raw(String),
+    /// Report a timeout for the node without waiting for the request timeout
+    Timeout,
+    /// Wait before sending the following messages
+    Delay(Duration),
+}
+
+impl SimulatedReply {
+    fn to_message(&self, req_id: u64) -> VdrResult<Option<(String, Message)>> {
+        let raw = match self {
+            Self::Ack => json!({"op": "REQACK", "reqId": req_id}).to_string(),
+            Self::Nack(reason) => {
+                json!({"op": "REQNACK", "reqId": req_id, "reason": reason}).to_string()
+            }
+            Self::Reject(reason) => {
+                json!({"op": "REJECT", "reqId": req_id, "reason": reason}).to_string()
+            }
+            Self::Reply(result) => {
+                let mut result = result.clone();
+                if let Some(result) = result.as_object_mut() {
+                    if !result.contains_key("reqId") {
+                        result.insert("reqId".to_owned(), json!(req_id));
+                    }
+                }
+                json!({"op": "REPLY", "result": result}).to_string()
+            }
+            Self::LedgerStatus {
+                txn_seq_no,
+                merkle_root,
+            } => Message::LedgerStatus(LedgerStatus {
+                txnSeqNo: *txn_seq_no,
+                merkleRoot: merkle_root.clone(),
+                ledgerId: LedgerType::POOL as u8,
+                ppSeqNo: None,
+                viewNo: None,
+                protocolVersion: None,
+            })
+            .serialize()?
+            .to_string(),
+            Self::ConsistencyProof {
+                seq_no_start,
+                seq_no_end,
+                old_merkle_root,
+                new_merkle_root,
+                hashes,
+            } => Message::ConsistencyProof(ConsistencyProof {
+                seqNoStart: *seq_no_start,
+                seqNoEnd: *seq_no_end,
+                ledgerId: LedgerType::POOL as usize,
+                hashes: hashes.clone(),
+                oldMerkleRoot: old_merkle_root.clone(),
+                newMerkleRoot: new_merkle_root.clone(),
+            })
+            .serialize()?
+            .to_string(),
+            Self::CatchupRep { txns, cons_proof } => Message::CatchupRep(CatchupRep {
+                ledgerId: LedgerType::POOL as usize,
+                consProof: cons_proof.clone(),
+                txns: txns
+                    .iter()
+                    .map(|(seq_no, txn)| (seq_no.to_string(), txn.clone()))
+                    .collect(),
+            })
+            .serialize()?
+            .to_string(),
+            Self::Raw(raw) => raw.clone(),
+            Self::Timeout | Self::Delay(_) => return Ok(None),
+        };
+        let parsed = Message::from_raw_str(&raw)?;
+        Ok(Some((raw, parsed)))
+    }
+}
+
+/// A callback producing the replies of a simulated node for a request body
+pub type SimulatedHandler = Arc<dyn Fn(&str, &SJsonValue) -> Vec<SimulatedReply> + Send + Sync>;
+
+/// A scripted verifier node used by `SimulatedNetworkerFactory`
+#[derive(Clone)]
+pub struct SimulatedNode {
+    handler: SimulatedHandler,
+    latency: Duration,
+}
+
+impl SimulatedNode {
+    /// Create a node which produces replies from a handler function
+    pub fn new<F>(handler: F) -> Self
+    where
+        F: Fn(&str, &SJsonValue) -> Vec<SimulatedReply> + Send + Sync + 'static,
+    {
+        Self {
+            handler: Arc::new(handler),
+            latency: Duration::from_secs(0),
+        }
+    }
+
+    /// Create a node which sends the same replies to every request
+    pub fn replies(replies: Vec<SimulatedReply>) -> Self {
+        Self::new(move |_, _| replies.clone())
+    }
+
+    /// Create a node which never replies, so that requests wait for their timeout
+    pub fn silent() -> Self {
+        Self::replies(vec![])
+    }
+
+    /// Set the delay before the node's replies are delivered
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+}
+
+impl std::fmt::Debug for SimulatedNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SimulatedNode {{ latency: {:?} }}", self.latency)
+    }
+}
+
+/// In-process `NetworkerFactory` implementation answering requests from scripted nodes
+#[derive(Clone, Debug, Default)]
+pub struct SimulatedNetworkerFactory {
+    nodes: HashMap<String, SimulatedNode>,
+    default_node: Option<SimulatedNode>,
+}
+
+impl SimulatedNetworkerFactory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Script the behaviour of a single node by alias
+    pub fn node(mut self, alias: &str, node: SimulatedNode) -> Self {
+        self.nodes.insert(alias.to_owned(), node);
+        self
+    }
+
+    /// Script the behaviour of all nodes without a specific entry
+    pub fn default_node(mut self, node: SimulatedNode) -> Self {
+        self.default_node.replace(node);
+        self
+    }
+}
+
+impl NetworkerFactory for SimulatedNetworkerFactory {
+    type Output = SimulatedNetworker;
+    fn make_networker(
+        &self,
+        _config: PoolConfig,
+        verifiers: &Verifiers,
+    ) -> VdrResult<SimulatedNetworker> {
+        let node_aliases = HashSet::from_iter(verifiers.keys().cloned());
+        let nodes = self.nodes.clone();
+        let default_node = self.default_node.clone();
+        let (evt_send, evt_recv) = mpsc::channel::<SimulatorCommand>();
+        let worker = thread::spawn(move || {
+            let mut sim_thread = SimulatorThread::new(node_aliases, nodes, default_node, evt_recv);
+            sim_thread.work();
+            trace!("Simulated networker exited");
+        });
+        Ok(SimulatedNetworker {
+            evt_send,
+            worker: Some(worker),
+        })
+    }
+}
+
+/// Simulated `Networker` implementation
+pub struct SimulatedNetworker {
+    evt_send: mpsc::Sender<SimulatorCommand>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl Networker for SimulatedNetworker {
+    fn send(&self, event: NetworkerEvent) -> VdrResult<()> {
+        self.evt_send
+            .send(SimulatorCommand::Event(event))
+            .with_err_msg(VdrErrorKind::Resource, "Error sending networker event")
+    }
+}
+
+impl Drop for SimulatedNetworker {
+    fn drop(&mut self) {
+        if self.evt_send.send(SimulatorCommand::Exit).is_err() {
+            trace!("Simulated networker already exited")
+        }
+        if let Some(worker) = self.worker.take() {
+            debug!("Drop simulated networker thread");
+            worker.join().unwrap()
+        }
+    }
+}
+
+enum SimulatorCommand {
+    Event(NetworkerEvent),
+    Exit,
+}
+
+struct SimulatedRequest {
+    body: SJsonValue,
+    sender: UnboundedSender<RequestExtEvent>,
+    timeouts: HashMap<String, Instant>,
+}
+
+impl SimulatedRequest {
+    fn send_event(&self, event: RequestExtEvent) -> bool {
+        self.sender.unbounded_send(event).is_ok()
+    }
+}
+
+struct ScheduledReply {
+    at: Instant,
+    handle: RequestHandle,
+    node_alias: String,
+    message: Option<(String, Message)>,
+}
+
+struct SimulatorThread {
+    node_aliases: HashSet<String>,
+    nodes: HashMap<String, SimulatedNode>,
+    default_node: Option<SimulatedNode>,
+    evt_recv: mpsc::Receiver<SimulatorCommand>,
+    requests: BTreeMap<RequestHandle, SimulatedRequest>,
+    scheduled: Vec<ScheduledReply>,
+}
+
+impl SimulatorThread {
+    fn new(
+        node_aliases: HashSet<String>,
+        nodes: HashMap<String, SimulatedNode>,
+        default_node: Option<SimulatedNode>,
+        evt_recv: mpsc::Receiver<SimulatorCommand>,
+    ) -> Self {
+        Self {
+            node_aliases,
+            nodes,
+            default_node,
+            evt_recv,
+            requests: BTreeMap::new(),
+            scheduled: vec![],
+        }
+    }
+
+    fn work(&mut self) {
+        loop {
+            let command = match self.next_deadline() {
+                Some(deadline) => {
+                    let wait = deadline.saturating_duration_since(Instant::now());
+                    match self.evt_recv.recv_timeout(wait) {
+                        Ok(command) => Some(command),
+                        Err(mpsc::RecvTimeoutError::Timeout) => None,
+                        Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+                None => match self.evt_recv.recv() {
+                    Ok(command) => Some(command),
+                    Err(_) => break,
+                },
+            };
+            match command {
+                Some(SimulatorCommand::Event(event)) => self.process_event(event),
+                Some(SimulatorCommand::Exit) => break,
+                None => (),
+            }
+            self.deliver_due();
+        }
+    }
+
+    fn next_deadline(&self) -> Option<Instant> {
+        let next_reply = self.scheduled.iter().map(|reply| reply.at).min();
+        let next_timeout = self
+            .requests
+            .values()
+            .flat_map(|req| req.timeouts.values().cloned())
+            .min();
+        match (next_reply, next_timeout) {
+            (Some(reply), Some(timeout)) => Some(std::cmp::min(reply, timeout)),
+            (reply, timeout) => reply.or(timeout),
+        }
+    }
+
+    fn process_event(&mut self, event: NetworkerEvent) {
+        match event {
+            NetworkerEvent::NewRequest(handle, _sub_id, body, sender) => {
+                trace!("New simulated request {}", handle);
+                let body = serde_json::from_str(&body).unwrap_or(SJsonValue::Null);
+                let request = SimulatedRequest {
+                    body,
+                    sender,
+                    timeouts: HashMap::new(),
+                };
+                if request.send_event(RequestExtEvent::Init) {
+                    self.requests.insert(handle, request);
+                } else {
+                    trace!("Sender dropped before Init {}", handle);
+                }
+            }
+            NetworkerEvent::FinishRequest(handle) => {
+                trace!("Removing, finished {}", handle);
+                self.remove_request(handle);
+            }
+            NetworkerEvent::Dispatch(handle, node_aliases, timeout) => {
+                trace!("Dispatch {} {:?}", handle, node_aliases);
+                self.dispatch_request(handle, node_aliases, timeout);
+            }
+            NetworkerEvent::CleanTimeout(handle, node_alias) => {
+                if let Some(request) = self.requests.get_mut(&handle) {
+                    request.timeouts.remove(&node_alias);
+                }
+            }
+            NetworkerEvent::ExtendTimeout(handle, node_alias, timeout) => {
+                if let Some(request) = self.requests.get_mut(&handle) {
+                    if let Some(expiry) = request.timeouts.get_mut(&node_alias) {
+                        *expiry = Instant::now() + _timeout_duration(timeout);
+                    } else {
+                        debug!("late REQACK for {}, node {}", handle, node_alias);
+                    }
+                }
+            }
+            NetworkerEvent::ReportFailure(node_alias, failure) => {
+                trace!("Ignoring failure report for {}: {:?}", node_alias, failure);
+            }
+            NetworkerEvent::ReportSuccess(node_alias) => {
+                trace!("Ignoring success report for {}", node_alias);
+            }
+            NetworkerEvent::UpdateVerifiers(verifiers) => {
+                // removed nodes are retained so that pending requests may still complete
+                self.node_aliases.extend(verifiers.keys().cloned());
+            }
+        }
+    }
+
+    fn dispatch_request(&mut self, handle: RequestHandle, node_aliases: Vec<String>, timeout: i64) {
+        let request = match self.requests.get_mut(&handle) {
+            Some(request) => request,
+            None => {
+                debug!("Unknown request ID for dispatch: {}", handle);
+                return;
+            }
+        };
+        let req_id = request.body["reqId"].as_u64().unwrap_or(0);
+        let mut disconnected = false;
+        for node_alias in node_aliases {
+            if !self.node_aliases.contains(&node_alias) {
+                warn!("Cannot send to unknown node alias: {}", node_alias);
+                continue;
+            }
+            let now = Instant::now();
+            if !request.send_event(RequestExtEvent::Sent(node_alias.clone(), SystemTime::now())) {
+                disconnected = true;
+                break;
+            }
+            request
+                .timeouts
+                .insert(node_alias.clone(), now + _timeout_duration(timeout));
+            let node = match self.nodes.get(&node_alias).or(self.default_node.as_ref()) {
+                Some(node) => node,
+                None => continue,
+            };
+            let mut at = now + node.latency;
+            for reply in (node.handler)(&node_alias, &request.body) {
+                if let SimulatedReply::Delay(delay) = reply {
+                    at += delay;
+                    continue;
+                }
+                let message = match reply.to_message(req_id) {
+                    Ok(message) => message,
+                    Err(err) => {
+                        debug!("Error building simulated reply: {}", err);
+                        continue;
+                    }
+                };
+                self.scheduled.push(ScheduledReply {
+                    at,
+                    handle,
+                    node_alias: node_alias.clone(),
+                    message,
+                });
+            }
+        }
+        if disconnected {
+            trace!("Removing, sender disconnected {}", handle);
+            self.remove_request(handle);
+        }
+    }
+
+    fn deliver_due(&mut self) {
+        loop {
+            let now = Instant::now();
+            let next_reply = self
+                .scheduled
+                .iter()
+                .enumerate()
+                .filter(|(_, reply)| reply.at <= now)
+                .min_by_key(|(_, reply)| reply.at)
+                .map(|(idx, _)| idx);
+            if let Some(idx) = next_reply {
+                let reply = self.scheduled.remove(idx);
+                self.deliver_reply(reply);
+                continue;
+            }
+            let expired = self.requests.iter().find_map(|(handle, req)| {
+                req.timeouts
+                    .iter()
+                    .find(|(_, expiry)| **expiry <= now)
+                    .map(|(alias, _)| (*handle, alias.clone()))
+            });
+            if let Some((handle, node_alias)) = expired {
+                self.deliver_reply(ScheduledReply {
+                    at: now,
+                    handle,
+                    node_alias,
+                    message: None,
+                });
+                continue;
+            }
+            break;
+        }
+    }
+
+    fn deliver_reply(&mut self, reply: ScheduledReply) {
+        let ScheduledReply {
+            handle,
+            node_alias,
+            message,
+            ..
+        } = reply;
+        let sent = if let Some(request) = self.requests.get_mut(&handle) {
+            match message {
+                Some((raw, parsed)) => request.send_event(RequestExtEvent::Received(
+                    node_alias,
+                    raw,
+                    parsed,
+                    SystemTime::now(),
+                )),
+                None => {
+                    request.timeouts.remove(&node_alias);
+                    request.send_event(RequestExtEvent::Timeout(node_alias))
+                }
+            }
+        } else {
+            trace!("Request ID not found: {}", handle);
+            return;
+        };
+        if !sent {
+            trace!("Removing, sender disconnected {}", handle);
+            self.remove_request(handle);
+        }
+    }
+
+    fn remove_request(&mut self, handle: RequestHandle) {
+        self.requests.remove(&handle);
+        self.scheduled.retain(|reply| reply.handle != handle);
+    }
+}
+
+fn _timeout_duration(timeout: i64) -> Duration {
+    Duration::from_secs(std::cmp::max(timeout, 0) as u64)
+}
+
+/// Fixtures for tests using pools of simulated verifier nodes
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::*;
+
+    use futures_executor::block_on;
+    use indy_test_utils::genesis::GenesisTransactions;
+
+    use crate::common::merkle_tree::MerkleTree;
+    use crate::ledger::RequestBuilder;
+    use crate::pool::helpers::perform_ledger_request;
+    use crate::pool::networker::MakeLocal;
+    use crate::pool::{LocalPool, PoolTransactions, PreparedRequest, RequestResult};
+    use crate::utils::base58;
+    use crate::utils::did::DidValue;
+    use crate::utils::hash::{TreeHash, SHA256::DigestType as Sha256};
+
+    pub const NODES: [&str; 4] = ["Node1", "Node2", "Node3", "Node4"];
+
+    pub fn _transactions() -> Vec<String> {
+        GenesisTransactions::new(Some(4)).transactions.clone()
+    }
+
+    pub fn _merkle_tree(txns: &[String]) -> MerkleTree {
+        PoolTransactions::from_json_transactions(txns)
+            .unwrap()
+            .merkle_tree()
+            .unwrap()
+    }
+
+    pub fn _pool(factory: SimulatedNetworkerFactory) -> LocalPool {
+        LocalPool::build(
+            PoolConfig::default(),
+            _merkle_tree(&_transactions()),
+            MakeLocal(factory),
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    pub fn _nym_request() -> PreparedRequest {
+        RequestBuilder::default()
+            .build_nym_request(
+                &DidValue("V4SGRU86Z58d6TV7PBUe6f".to_owned()),
+                &DidValue("VsKV7grR1BUE29mG2Fm2kX".to_owned()),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+    }
+
+    pub fn _nym_result(verkey: &str) -> SJsonValue {
+        json!({
+            "type": "1",
+            "dest": "VsKV7grR1BUE29mG2Fm2kX",
+            "verkey": verkey,
+            "seqNo": 10,
+        })
+    }
+
+    pub fn _committed_nym_result() -> SJsonValue {
+        let txn = json!({
+            "txn": {"type": "1", "data": {"dest": "VsKV7grR1BUE29mG2Fm2kX"}},
+            "txnMetadata": {"seqNo": 1},
+            "ver": "1",
+        });
+        let leaf = rmp_serde::to_vec(&txn).unwrap();
+        let mut result = txn;
+        result["auditPath"] = json!([]);
+        result["rootHash"] = json!(base58::encode(Sha256::hash_leaf(&leaf).unwrap()));
+        result
+    }
+
+    pub fn _send_nym(pool: &LocalPool) -> RequestResult<String> {
+        block_on(perform_ledger_request(pool, &_nym_request()))
+            .unwrap()
+            .0
+    }
+
+    pub fn _reply_result(reply: &str) -> SJsonValue {
+        let mut result = serde_json::from_str::<SJsonValue>(reply).unwrap()["result"].clone();
+        result.as_object_mut().unwrap().remove("reqId");
+        result
+    }
+
+    /// Create the transaction adding a fifth verifier to the pool
+    pub fn _new_node_txn() -> SJsonValue {
+        let mut txn = serde_json::from_str::<SJsonValue>(&_transactions()[3]).unwrap();
+        txn["txn"]["data"]["dest"] = json!("GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL");
+        txn["txn"]["data"]["data"]["alias"] = json!("Node5");
+        txn["txn"]["data"]["data"]["client_port"] = json!(9710);
+        txn["txn"]["data"]["data"]["node_port"] = json!(9709);
+        txn["txnMetadata"]["seqNo"] = json!(5);
+        txn
+    }
+
+    /// Create a node which serves catchup of a single added verifier, returning its transaction
+    pub fn _catchup_node(fallback: SimulatedReply) -> (SimulatedNode, SJsonValue) {
+        let mut txns = _transactions();
+        let new_txn = _new_node_txn();
+        txns.push(new_txn.to_string());
+        let target = _merkle_tree(&txns);
+        let (target_size, target_root) = (target.count(), base58::encode(target.root_hash()));
+
+        let catchup_txn = new_txn.clone();
+        let node = SimulatedNode::new(move |_, body| match body["op"].as_str() {
+            Some("LEDGER_STATUS") => vec![SimulatedReply::LedgerStatus {
+                txn_seq_no: target_size,
+                merkle_root: target_root.clone(),
+            }],
+            Some("CATCHUP_REQ") => vec![SimulatedReply::CatchupRep {
+                txns: BTreeMap::from_iter(vec![(target_size, catchup_txn.clone())]),
+                cons_proof: vec![],
+            }],
+            _ => vec![fallback.clone()],
+        })
+        .latency(Duration::from_millis(5));
+        (node, new_txn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixtures::*;
+    use super::*;
+
+    use futures_executor::block_on;
+
+    use crate::pool::helpers::perform_refresh;
+    use crate::pool::RequestResult;
+    use crate::utils::base58;
+
+    #[test]
+    fn test_consensus_request_works() {
+        let pool = _pool(
+            SimulatedNetworkerFactory::new().default_node(SimulatedNode::replies(vec![
+                SimulatedReply::Ack,
+                SimulatedReply::Reply(_nym_result("verkey")),
+            ])),
+        );
+        match _send_nym(&pool) {
+            RequestResult::Reply(reply) => assert_eq!(_reply_result(&reply), _nym_result("verkey")),
+            RequestResult::Failed(err) => panic!("Unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn test_consensus_request_works_for_divergent_node() {
+        let pool = _pool(
+            SimulatedNetworkerFactory::new()
+                .default_node(
+                    SimulatedNode::replies(vec![SimulatedReply::Reply(_nym_result("verkey"))])
+                        .latency(Duration::from_millis(20)),
+                )
+                .node(
+                    NODES[0],
+                    SimulatedNode::replies(vec![SimulatedReply::Reply(_nym_result("other"))]),
+                ),
+        );
+        match _send_nym(&pool) {
+            RequestResult::Reply(reply) => assert_eq!(_reply_result(&reply), _nym_result("verkey")),
+            RequestResult::Failed(err) => panic!("Unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn test_consensus_request_fails_for_no_consensus() {
+        let pool = _pool(
+            SimulatedNetworkerFactory::new()
+                .node(
+                    NODES[0],
+                    SimulatedNode::replies(vec![SimulatedReply::Reply(_nym_result("a"))]),
+                )
+                .node(
+                    NODES[1],
+                    SimulatedNode::replies(vec![SimulatedReply::Reply(_nym_result("b"))]),
+                )
+                .default_node(SimulatedNode::replies(vec![SimulatedReply::Timeout])),
+        );
+        match _send_nym(&pool) {
+            RequestResult::Failed(err) => {
+                assert!(matches!(err.kind(), VdrErrorKind::PoolNoConsensus))
+            }
+            RequestResult::Reply(reply) => panic!("Unexpected reply: {}", reply),
+        }
+    }
+
+    #[test]
+    fn test_consensus_request_fails_for_nack() {
+        let pool = _pool(
+            SimulatedNetworkerFactory::new().default_node(SimulatedNode::replies(vec![
+                SimulatedReply::Nack("invalid".to_owned()),
+            ])),
+        );
+        match _send_nym(&pool) {
+            RequestResult::Failed(err) => match err.kind() {
+                VdrErrorKind::PoolRequestFailed(msg) => assert!(msg.contains("invalid")),
+                _ => panic!("Unexpected error: {}", err),
+            },
+            RequestResult::Reply(reply) => panic!("Unexpected reply: {}", reply),
+        }
+    }
+
+    #[test]
+    fn test_consensus_request_fails_for_timeout() {
+        let pool = _pool(
+            SimulatedNetworkerFactory::new()
+                .default_node(SimulatedNode::replies(vec![SimulatedReply::Timeout])),
+        );
+        match _send_nym(&pool) {
+            RequestResult::Failed(err) => {
+                assert!(matches!(err.kind(), VdrErrorKind::PoolTimeout))
+            }
+            RequestResult::Reply(reply) => panic!("Unexpected reply: {}", reply),
+        }
+    }
+
+    #[test]
+    fn test_status_request_works_for_current_ledger() {
+        let tree = _merkle_tree(&_transactions());
+        let pool = _pool(
+            SimulatedNetworkerFactory::new().default_node(SimulatedNode::replies(vec![
+                SimulatedReply::LedgerStatus {
+                    txn_seq_no: tree.count(),
+                    merkle_root: base58::encode(tree.root_hash()),
+                },
+            ])),
+        );
+        let (txns, _timing) = block_on(perform_refresh(&pool)).unwrap();
+        assert_eq!(txns, None);
+    }
+
+    #[test]
+    fn test_status_request_fails_for_mismatched_ledger() {
+        let tree = _merkle_tree(&_transactions());
+        let pool = _pool(
+            SimulatedNetworkerFactory::new().default_node(SimulatedNode::replies(vec![
+                SimulatedReply::LedgerStatus {
+                    txn_seq_no: tree.count(),
+                    merkle_root: base58::encode(&[0u8; 32]),
+                },
+            ])),
+        );
+        block_on(perform_refresh(&pool)).unwrap_err();
+    }
+
+    #[test]
+    fn test_catchup_request_works() {
+        let (node, new_txn) = _catchup_node(SimulatedReply::Nack("unexpected".to_owned()));
+        let pool = _pool(SimulatedNetworkerFactory::new().default_node(node));
+        let (new_txns, _timing) = block_on(perform_refresh(&pool)).unwrap();
+        let new_txns = new_txns.unwrap();
+        assert_eq!(new_txns.len(), 1);
+        assert_eq!(
+            serde_json::from_str::<SJsonValue>(&new_txns[0]).unwrap(),
+            new_txn
+        );
+    }
+}
@@ -0,0 +1,906 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use futures_channel::mpsc::UnboundedSender;
+
+use tokio::io::unix::AsyncFd;
+use tokio::runtime::Handle;
+use tokio::sync::mpsc::{
+    unbounded_channel, UnboundedReceiver as TaskReceiver, UnboundedSender as TaskSender,
+};
+
+use crate::common::error::prelude::*;
+use crate::config::PoolConfig;
+
+use super::types::{Message, Verifiers};
+use super::zmq::{_get_remotes, RemoteNode};
+use super::{
+    unix_now, HealthTracker, Networker, NetworkerEvent, NetworkerFactory, NodeFailure,
+    NodeHealthTable, RequestExtEvent, RequestHandle,
+};
+
+new_handle_type!(TokioConnectionHandle, TCH_COUNTER);
+
+/// Tokio-based ZeroMQ `NetworkerFactory` implementation
+#[derive(Clone, Debug, Default)]
+pub struct TokioNetworkerFactory {
+    handle: Option<Handle>,
+}
+
+impl TokioNetworkerFactory {
+    /// Create a new factory using the Tokio runtime which is active when the networker is created
+    pub fn new() -> Self {
+        Self { handle: None }
+    }
+
+    /// Create a new factory spawning networker tasks on a specific Tokio runtime
+    pub fn with_handle(handle: Handle) -> Self {
+        Self {
+            handle: Some(handle),
+        }
+    }
+}
+
+impl NetworkerFactory for TokioNetworkerFactory {
+    type Output = TokioNetworker;
+    fn make_networker(
+        &self,
+        config: PoolConfig,
+        verifiers: &Verifiers,
+    ) -> VdrResult<TokioNetworker> {
+        let handle = match self.handle.as_ref() {
+            Some(handle) => handle.clone(),
+            None => Handle::try_current()
+                .map_err(|_| err_msg(VdrErrorKind::Resource, "No Tokio runtime available"))?,
+        };
+        let remotes = _get_remotes(verifiers);
+        let health = Arc::new(Mutex::new(HealthTracker::new(
+            &config,
+            remotes.iter().map(|r| r.name.clone()),
+        )));
+        let (sender, receiver) = unbounded_channel();
+        let (task, socket_events) = NetworkerTask::new(config, remotes, health.clone());
+        handle.spawn(task.run(receiver, socket_events));
+        Ok(TokioNetworker { sender, health })
+    }
+}
+
+/// Tokio-based ZeroMQ `Networker` implementation
+///
+/// Instances are cheaply cloneable and may be shared between threads.
+#[derive(Clone)]
+pub struct TokioNetworker {
+    sender: TaskSender<NetworkerEvent>,
+    health: Arc<Mutex<HealthTracker>>,
+}
+
+impl Networker for TokioNetworker {
+    fn send(&self, event: NetworkerEvent) -> VdrResult<()> {
+        self.sender
+            .send(event)
+            .map_err(|_| err_msg(VdrErrorKind::Resource, "Networker task has exited"))
+    }
+
+    fn node_health(&self) -> VdrResult<NodeHealthTable> {
+        let health = self.health.lock().map_err(|_| {
+            err_msg(
+                VdrErrorKind::Unexpected,
+                "Error acquiring node health, mutex poisoned",
+            )
+        })?;
+        Ok(health.table())
+    }
+}
+
+struct NetworkerTask {
+    config: PoolConfig,
+    zmq_ctx: zmq::Context,
+    remotes: Vec<RemoteNode>,
+    requests: BTreeMap<RequestHandle, PendingRequest>,
+    last_connection: Option<TokioConnectionHandle>,
+    connections: BTreeMap<TokioConnectionHandle, TokioConnection>,
+    health: Arc<Mutex<HealthTracker>>,
+    socket_send: TaskSender<SocketEvent>,
+}
+
+impl NetworkerTask {
+    fn new(
+        config: PoolConfig,
+        remotes: Vec<RemoteNode>,
+        health: Arc<Mutex<HealthTracker>>,
+    ) -> (Self, TaskReceiver<SocketEvent>) {
+        let (socket_send, socket_recv) = unbounded_channel();
+        (
+            Self {
+                config,
+                zmq_ctx: zmq::Context::new(),
+                remotes,
+                requests: BTreeMap::new(),
+                last_connection: None,
+                connections: BTreeMap::new(),
+                health,
+                socket_send,
+            },
+            socket_recv,
+        )
+    }
+
+    async fn run(
+        mut self,
+        mut events: TaskReceiver<NetworkerEvent>,
+        mut socket_events: TaskReceiver<SocketEvent>,
+    ) {
+        loop {
+            let deadline = self.next_deadline();
+            tokio::select! {
+                event = events.recv() => match event {
+                    Some(event) => self.process_event(event),
+                    None => break,
+                },
+                Some(event) = socket_events.recv() => self.process_socket_event(event),
+                _ = _sleep_until(deadline) => self.process_timeouts(Instant::now()),
+            }
+        }
+        trace!("Networker task ended");
+    }
+
+    fn process_event(&mut self, event: NetworkerEvent) {
+        match event {
+            NetworkerEvent::NewRequest(handle, sub_id, body, sender) => {
+                trace!("New request {}", handle);
+                let conn_id = match self.get_active_connection(&sub_id) {
+                    Ok(conn_id) => conn_id,
+                    Err(err) => {
+                        warn!("Error creating pool connection: {}", err);
+                        let _ = sender.unbounded_send(RequestExtEvent::Error(err));
+                        return;
+                    }
+                };
+                let mut pending = PendingRequest {
+                    conn_id,
+                    sender,
+                    sub_id,
+                    body,
+                };
+                if pending.send_event(RequestExtEvent::Init) {
+                    self.requests.insert(handle, pending);
+                } else {
+                    trace!("Removing, sender dropped before Init {}", handle);
+                    self.check_remove_connection(conn_id, Some(&pending.sub_id));
+                }
+            }
+            NetworkerEvent::FinishRequest(handle) => {
+                trace!("Removing, finished {}", handle);
+                self.remove_request(handle);
+            }
+            NetworkerEvent::Dispatch(handle, node_aliases, timeout) => {
+                trace!("Dispatch {} {:?}", handle, node_aliases);
+                self.dispatch_request(handle, node_aliases, timeout);
+            }
+            NetworkerEvent::CleanTimeout(handle, node_alias) => {
+                if let Some(request) = self.requests.get(&handle) {
+                    if let Some(conn) = self.connections.get_mut(&request.conn_id) {
+                        conn.clean_timeout(&request.sub_id, Some(&node_alias));
+                    }
+                } else {
+                    debug!("Unknown request ID for clean timeout: {}", handle)
+                }
+            }
+            NetworkerEvent::ExtendTimeout(handle, node_alias, timeout) => {
+                if let Some(request) = self.requests.get(&handle) {
+                    if let Some(conn) = self.connections.get_mut(&request.conn_id) {
+                        conn.extend_timeout(&request.sub_id, &node_alias, timeout);
+                    }
+                } else {
+                    debug!("Unknown request ID for extend timeout: {}", handle)
+                }
+            }
+            NetworkerEvent::ReportFailure(node_alias, failure) => {
+                trace!("Node failure reported {}: {:?}", node_alias, failure);
+                self.update_health(|health| {
+                    health.record_failure(&node_alias, failure, unix_now())
+                });
+            }
+            NetworkerEvent::ReportSuccess(node_alias) => {
+                trace!("Node success reported {}", node_alias);
+                self.update_health(|health| health.record_success(&node_alias));
+            }
+            NetworkerEvent::UpdateVerifiers(verifiers) => {
+                trace!("Update verifiers {:?}", verifiers.keys());
+                let remotes = _get_remotes(&verifiers);
+                self.update_health(|health| {
+                    health.update_nodes(remotes.iter().map(|r| r.name.clone()))
+                });
+                // earlier connections keep the previous node set until their requests complete
+                if let Some(conn) = self
+                    .last_connection
+                    .and_then(|conn_id| self.connections.get_mut(&conn_id))
+                {
+                    conn.update_remotes(&remotes);
+                }
+                self.remotes = remotes;
+            }
+        }
+    }
+
+    fn process_socket_event(&mut self, event: SocketEvent) {
+        let SocketEvent {
+            conn_id,
+            node_alias,
+            message,
+        } = event;
+        let parsed = message.and_then(|raw| match Message::from_raw_str(&raw) {
+            Ok(meta) => Some((raw, meta)),
+            Err(err) => {
+                debug!("Error parsing received message: {:?}", err);
+                None
+            }
+        });
+        let (raw, meta) = match parsed {
+            Some(parsed) => parsed,
+            None => {
+                self.update_health(|health| {
+                    health.record_failure(&node_alias, NodeFailure::Malformed, unix_now())
+                });
+                return;
+            }
+        };
+        trace!("Socket reply {} {}", node_alias, raw);
+        let req_id = meta.request_id().unwrap_or_default();
+        if let Some(conn) = self.connections.get_mut(&conn_id) {
+            conn.clean_idle_timeout(&req_id);
+        }
+        match meta {
+            // acknowledgements do not affect the node health
+            Message::ReqACK(_) => (),
+            Message::ReqNACK(_) | Message::Reject(_) => {
+                self.update_health(|health| health.record_rejected(&node_alias))
+            }
+            _ => self.update_health(|health| health.record_reply(&node_alias)),
+        }
+        if let Some(handle) = self.select_request(conn_id, &req_id) {
+            self.send_event(
+                handle,
+                RequestExtEvent::Received(node_alias, raw, meta, SystemTime::now()),
+            );
+        } else {
+            trace!("Unknown request ID: {}", req_id)
+        }
+    }
+
+    fn process_timeouts(&mut self, now: Instant) {
+        let mut expired = vec![];
+        for (conn_id, conn) in self.connections.iter_mut() {
+            for sub_id in conn.take_expired_idle(now) {
+                expired.push((*conn_id, sub_id, None));
+            }
+            for (sub_id, node_alias) in conn.take_expired(now) {
+                expired.push((*conn_id, sub_id, Some(node_alias)));
+            }
+        }
+        for (conn_id, sub_id, node_alias) in expired {
+            let handle = self.select_request(conn_id, &sub_id);
+            if let Some(node_alias) = node_alias {
+                self.update_health(|health| {
+                    health.record_failure(&node_alias, NodeFailure::Timeout, unix_now())
+                });
+                if let Some(handle) = handle {
+                    self.send_event(handle, RequestExtEvent::Timeout(node_alias));
+                }
+            } else if let Some(handle) = handle {
+                trace!("Remove idle {}", handle);
+                self.remove_request(handle);
+            }
+        }
+        let idle = self
+            .connections
+            .iter()
+            .filter(|(_, conn)| conn.is_idle())
+            .map(|(conn_id, _)| *conn_id)
+            .collect::<Vec<_>>();
+        for conn_id in idle {
+            trace!("Removing pool connection {}", conn_id);
+            self.connections.remove(&conn_id);
+        }
+    }
+
+    fn next_deadline(&self) -> Option<Instant> {
+        self.connections
+            .values()
+            .filter_map(TokioConnection::next_deadline)
+            .min()
+    }
+
+    fn update_health<F>(&self, f: F)
+    where
+        F: FnOnce(&mut HealthTracker),
+    {
+        match self.health.lock() {
+            Ok(mut health) => f(&mut health),
+            Err(_) => warn!("Error acquiring node health, mutex poisoned"),
+        }
+    }
+
+    fn select_request(
+        &self,
+        conn_id: TokioConnectionHandle,
+        sub_id: &str,
+    ) -> Option<RequestHandle> {
+        self.requests.iter().find_map(|(handle, req)| {
+            if req.conn_id == conn_id && req.sub_id == sub_id {
+                Some(*handle)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Forward an event to a pending request, returning `false` if the request was removed
+    fn send_event(&mut self, handle: RequestHandle, event: RequestExtEvent) -> bool {
+        if let Some(req) = self.requests.get_mut(&handle) {
+            if req.send_event(event) {
+                return true;
+            }
+            trace!("Removing, sender disconnected {}", handle);
+            self.remove_request(handle);
+        } else {
+            trace!("Request ID not found: {}", handle);
+        }
+        false
+    }
+
+    fn remove_request(&mut self, handle: RequestHandle) {
+        if let Some(req) = self.requests.remove(&handle) {
+            self.check_remove_connection(req.conn_id, Some(&req.sub_id));
+        }
+    }
+
+    fn check_remove_connection(&mut self, conn_id: TokioConnectionHandle, sub_id: Option<&str>) {
+        if let Some(conn) = self.connections.get_mut(&conn_id) {
+            if let Some(sub_id) = sub_id {
+                conn.clean_timeout(sub_id, None);
+            }
+            if conn.is_idle() {
+                trace!("Removing pool connection {}", conn_id);
+                self.connections.remove(&conn_id);
+            }
+        }
+    }
+
+    fn dispatch_request(&mut self, handle: RequestHandle, node_aliases: Vec<String>, timeout: i64) {
+        let (conn_id, sub_id, body) = match self.requests.get(&handle) {
+            Some(req) => (req.conn_id, req.sub_id.clone(), req.body.clone()),
+            None => {
+                debug!("Unknown request ID for dispatch: {}", handle);
+                return;
+            }
+        };
+        if !self.connections.contains_key(&conn_id) {
+            warn!("Removing, pool connection expired {}", handle);
+            self.remove_request(handle);
+            return;
+        }
+        for node_alias in node_aliases {
+            let conn = match self.connections.get_mut(&conn_id) {
+                Some(conn) => conn,
+                None => break,
+            };
+            if !conn.has_remote(&node_alias) {
+                warn!("Cannot send to unknown node alias: {}", node_alias);
+                continue;
+            }
+            let available = match self.health.lock() {
+                Ok(mut health) => health.check_dispatch(&node_alias, unix_now()),
+                Err(_) => true,
+            };
+            let event = if !available {
+                // report blacklisted nodes as timed out so the request can move on
+                debug!("Skipping blacklisted node: {}", node_alias);
+                RequestExtEvent::Timeout(node_alias)
+            } else {
+                match conn.send_request(
+                    &self.zmq_ctx,
+                    &node_alias,
+                    self.config.socks_proxy.clone(),
+                    &sub_id,
+                    body.clone(),
+                    timeout,
+                ) {
+                    Ok(()) => RequestExtEvent::Sent(node_alias, SystemTime::now()),
+                    Err(err) => {
+                        warn!("Error sending request to node {}: {}", node_alias, err);
+                        RequestExtEvent::Timeout(node_alias)
+                    }
+                }
+            };
+            if !self.send_event(handle, event) {
+                break;
+            }
+        }
+    }
+
+    fn get_active_connection(&mut self, sub_id: &str) -> VdrResult<TokioConnectionHandle> {
+        if let Some(conn_id) = self.last_connection {
+            if let Some(conn) = self.connections.get_mut(&conn_id) {
+                if conn.is_active()
+                    && conn.req_cnt < self.config.conn_request_limit
+                    && !conn.req_log.contains(sub_id)
+                {
+                    conn.init_request(sub_id.to_owned());
+                    return Ok(conn_id);
+                }
+            }
+        }
+        let conn_id = TokioConnectionHandle::next();
+        let mut conn = TokioConnection::new(
+            conn_id,
+            self.remotes.clone(),
+            self.config.conn_active_timeout,
+            self.config.ack_timeout,
+            self.socket_send.clone(),
+        )?;
+        conn.init_request(sub_id.to_owned());
+        self.connections.insert(conn_id, conn);
+        self.last_connection.replace(conn_id);
+        debug!("New {}", conn_id);
+        Ok(conn_id)
+    }
+}
+
+struct TokioConnection {
+    id: TokioConnectionHandle,
+    remotes: Vec<RemoteNode>,
+    key_pair: zmq::CurveKeyPair,
+    sockets: HashMap<String, TaskSender<String>>,
+    // keeps the sockets for replaced nodes open until the connection is dropped
+    #[allow(dead_code)]
+    retired: Vec<TaskSender<String>>,
+    idle_timeouts: HashMap<String, Instant>,
+    socket_timeouts: HashMap<(String, String), Instant>,
+    time_created: Instant,
+    req_cnt: usize,
+    req_log: HashSet<String>,
+    active_timeout: i64,
+    idle_timeout: i64,
+    events: TaskSender<SocketEvent>,
+}
+
+impl TokioConnection {
+    fn new(
+        id: TokioConnectionHandle,
+        remotes: Vec<RemoteNode>,
+        active_timeout: i64,
+        idle_timeout: i64,
+        events: TaskSender<SocketEvent>,
+    ) -> VdrResult<Self> {
+        Ok(Self {
+            id,
+            remotes,
+            key_pair: zmq::CurveKeyPair::new()?,
+            sockets: HashMap::new(),
+            retired: vec![],
+            idle_timeouts: HashMap::new(),
+            socket_timeouts: HashMap::new(),
+            time_created: Instant::now(),
+            req_cnt: 0,
+            req_log: HashSet::new(),
+            active_timeout,
+            idle_timeout,
+            events,
+        })
+    }
+
+    fn send_request(
+        &mut self,
+        ctx: &zmq::Context,
+        node_alias: &str,
+        socks_proxy: Option<String>,
+        sub_id: &str,
+        body: String,
+        timeout: i64,
+    ) -> VdrResult<()> {
+        if !self.sockets.contains_key(node_alias) {
+            // prefer the most recent entry for nodes which have been updated
+            let remote = self
+                .remotes
+                .iter()
+                .rev()
+                .find(|remote| remote.name == node_alias)
+                .ok_or_else(|| input_err(format!("Unknown node alias: {}", node_alias)))?;
+            debug!("Open new socket for node {}", node_alias);
+            let socket = AsyncSocket::new(remote.connect(ctx, &self.key_pair, socks_proxy)?)?;
+            let (send, recv) = unbounded_channel();
+            tokio::spawn(_run_socket(
+                socket,
+                self.id,
+                node_alias.to_owned(),
+                recv,
+                self.events.clone(),
+            ));
+            self.sockets.insert(node_alias.to_owned(), send);
+        }
+        if self.sockets[node_alias].send(body).is_err() {
+            // allow the socket to be reopened by a later request
+            self.sockets.remove(node_alias);
+            return Err(err_msg(VdrErrorKind::Connection, "Socket task has exited"));
+        }
+        if self.idle_timeouts.contains_key(sub_id) {
+            // will only be present if this request has received no responses
+            self.set_idle_timeout(sub_id.to_owned());
+        }
+        self.socket_timeouts.insert(
+            (sub_id.to_owned(), node_alias.to_owned()),
+            Instant::now() + _duration(timeout),
+        );
+        Ok(())
+    }
+
+    fn has_remote(&self, node_alias: &str) -> bool {
+        self.remotes.iter().any(|remote| remote.name == node_alias)
+    }
+
+    /// Add new and changed remote nodes, retaining the existing sockets
+    ///
+    /// Sockets for changed nodes stay open so that pending requests may still receive replies.
+    fn update_remotes(&mut self, remotes: &[RemoteNode]) {
+        for remote in remotes {
+            if !self.remotes.contains(remote) {
+                if let Some(socket) = self.sockets.remove(&remote.name) {
+                    self.retired.push(socket);
+                }
+                self.remotes.push(remote.clone());
+            }
+        }
+    }
+
+    fn extend_timeout(&mut self, sub_id: &str, node_alias: &str, timeout: i64) {
+        if let Some(expiry) = self
+            .socket_timeouts
+            .get_mut(&(sub_id.to_owned(), node_alias.to_owned()))
+        {
+            *expiry = Instant::now() + _duration(timeout);
+        } else {
+            debug!("late REQACK for req_id {}, node {}", sub_id, node_alias);
+        }
+    }
+
+    fn clean_timeout(&mut self, sub_id: &str, node_alias: Option<&str>) {
+        match node_alias {
+            Some(node_alias) => {
+                if self
+                    .socket_timeouts
+                    .remove(&(sub_id.to_owned(), node_alias.to_owned()))
+                    .is_some()
+                    && !self.has_socket_timeouts(sub_id)
+                {
+                    self.set_idle_timeout(sub_id.to_owned())
+                }
+            }
+            None => {
+                self.socket_timeouts
+                    .retain(|(req_id, _), _| req_id != sub_id);
+                self.idle_timeouts.remove(sub_id);
+            }
+        }
+    }
+
+    /// Remove and return the node timeouts which have expired
+    fn take_expired(&mut self, now: Instant) -> Vec<(String, String)> {
+        let expired = self
+            .socket_timeouts
+            .iter()
+            .filter(|(_, expiry)| **expiry <= now)
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for (sub_id, node_alias) in expired.iter() {
+            self.clean_timeout(sub_id, Some(node_alias));
+        }
+        expired
+    }
+
+    /// Remove and return the requests which have been idle past their timeout
+    fn take_expired_idle(&mut self, now: Instant) -> Vec<String> {
+        let expired = self
+            .idle_timeouts
+            .iter()
+            .filter(|(_, expiry)| **expiry <= now)
+            .map(|(sub_id, _)| sub_id.clone())
+            .collect::<Vec<_>>();
+        for sub_id in expired.iter() {
+            self.idle_timeouts.remove(sub_id);
+        }
+        expired
+    }
+
+    fn next_deadline(&self) -> Option<Instant> {
+        let next = self
+            .socket_timeouts
+            .values()
+            .chain(self.idle_timeouts.values())
+            .min()
+            .cloned();
+        if next.is_none() {
+            // wake up to close the connection once it expires
+            Some(self.time_created + _duration(self.active_timeout))
+        } else {
+            next
+        }
+    }
+
+    fn has_socket_timeouts(&self, sub_id: &str) -> bool {
+        self.socket_timeouts
+            .keys()
+            .any(|(req_id, _)| req_id == sub_id)
+    }
+
+    fn set_idle_timeout(&mut self, sub_id: String) {
+        self.idle_timeouts
+            .insert(sub_id, Instant::now() + _duration(self.idle_timeout));
+    }
+
+    fn clean_idle_timeout(&mut self, sub_id: &str) {
+        self.idle_timeouts.remove(sub_id);
+    }
+
+    fn init_request(&mut self, sub_id: String) {
+        self.req_cnt += 1;
+        self.req_log.insert(sub_id.clone());
+        self.set_idle_timeout(sub_id);
+    }
+
+    fn is_active(&self) -> bool {
+        self.time_created.elapsed() < _duration(self.active_timeout)
+    }
+
+    fn is_idle(&self) -> bool {
+        !self.is_active() && self.socket_timeouts.is_empty() && self.idle_timeouts.is_empty()
+    }
+}
+
+/// Wraps the ZeroMQ notification file descriptor for registration with Tokio
+struct SocketFd(RawFd);
+
+impl AsRawFd for SocketFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+struct AsyncSocket {
+    // the descriptor must be deregistered before the socket is closed
+    fd: AsyncFd<SocketFd>,
+    socket: zmq::Socket,
+}
+
+impl AsyncSocket {
+    fn new(socket: zmq::Socket) -> VdrResult<Self> {
+        let fd = AsyncFd::new(SocketFd(socket.get_fd()?))
+            .with_err_msg(VdrErrorKind::Connection, "Error registering ZMQ socket")?;
+        Ok(Self { fd, socket })
+    }
+
+    /// Receive all messages which are currently queued on the socket
+    fn recv_pending(&self) -> VdrResult<Vec<Vec<u8>>> {
+        let mut messages = vec![];
+        // the ZeroMQ descriptor is edge-triggered, so pending events must be checked
+        while self.socket.get_events()?.contains(zmq::POLLIN) {
+            match self.socket.recv_bytes(zmq::DONTWAIT) {
+                Ok(msg) => messages.push(msg),
+                Err(zmq::Error::EAGAIN) => break,
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(messages)
+    }
+}
+
+async fn _run_socket(
+    socket: AsyncSocket,
+    conn_id: TokioConnectionHandle,
+    node_alias: String,
+    mut outgoing: TaskReceiver<String>,
+    events: TaskSender<SocketEvent>,
+) {
+    loop {
+        let messages = match socket.recv_pending() {
+            Ok(messages) => messages,
+            Err(err) => {
+                warn!("Error reading from node {}: {}", node_alias, err);
+                break;
+            }
+        };
+        for msg in messages {
+            let event = SocketEvent {
+                conn_id,
+                node_alias: node_alias.clone(),
+                message: String::from_utf8(msg).ok(),
+            };
+            if events.send(event).is_err() {
+                return;
+            }
+        }
+        // only the descriptor is held across the await, as ZeroMQ sockets are not `Sync`
+        tokio::select! {
+            msg = outgoing.recv() => match msg {
+                Some(msg) => {
+                    if let Err(err) = socket.socket.send(msg.as_bytes(), zmq::DONTWAIT) {
+                        warn!("Error sending to node {}: {}", node_alias, err);
+                    }
+                }
+                None => break,
+            },
+            ready = socket.fd.readable() => match ready {
+                Ok(mut guard) => guard.clear_ready(),
+                Err(err) => {
+                    warn!("Error polling socket for node {}: {}", node_alias, err);
+                    break;
+                }
+            },
+        }
+    }
+    trace!("Socket closed for node {}", node_alias);
+}
+
+async fn _sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+        None => futures_util::future::pending().await,
+    }
+}
+
+fn _duration(secs: i64) -> Duration {
+    Duration::from_secs(std::cmp::max(secs, 0) as u64)
+}
+
+#[derive(Debug)]
+struct SocketEvent {
+    conn_id: TokioConnectionHandle,
+    node_alias: String,
+    message: Option<String>,
+}
+
+#[derive(Debug)]
+struct PendingRequest {
+    conn_id: TokioConnectionHandle,
+    sender: UnboundedSender<RequestExtEvent>,
+    sub_id: String,
+    body: String,
+}
+
+impl PendingRequest {
+    fn send_event(&mut self, event: RequestExtEvent) -> bool {
+        self.sender.unbounded_send(event).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_channel::mpsc::unbounded;
+    use futures_util::stream::StreamExt;
+
+    use crate::pool::VerifierInfo;
+
+    fn _node_socket(ctx: &zmq::Context) -> (zmq::Socket, Verifiers) {
+        let server_keys = zmq::CurveKeyPair::new().unwrap();
+        let server = ctx.socket(zmq::ROUTER).unwrap();
+        server.set_curve_server(true).unwrap();
+        server.set_curve_secretkey(&server_keys.secret_key).unwrap();
+        server.set_linger(0).unwrap();
+        server.bind("tcp://127.0.0.1:*").unwrap();
+        let client_addr = server.get_last_endpoint().unwrap().unwrap();
+        let mut verifiers = Verifiers::new();
+        verifiers.insert(
+            "Node1".to_string(),
+            VerifierInfo {
+                client_addr,
+                node_addr: String::new(),
+                public_key: String::new(),
+                enc_key: server_keys.public_key.to_vec(),
+                bls_key: None,
+            },
+        );
+        (server, verifiers)
+    }
+
+    fn _runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_tokio_networker_round_trip() {
+        let ctx = zmq::Context::new();
+        let (server, verifiers) = _node_socket(&ctx);
+        let node = std::thread::spawn(move || {
+            let parts = server.recv_multipart(0).unwrap();
+            let reply = json!({"op": "REQACK", "reqId": 1}).to_string();
+            server
+                .send_multipart(vec![parts[0].as_slice(), reply.as_bytes()], 0)
+                .unwrap();
+        });
+
+        _runtime().block_on(async {
+            let networker = TokioNetworkerFactory::new()
+                .make_networker(PoolConfig::default(), &verifiers)
+                .unwrap();
+            let handle = RequestHandle::next();
+            let (sender, mut events) = unbounded();
+            networker
+                .send(NetworkerEvent::NewRequest(
+                    handle,
+                    "1".to_string(),
+                    "{}".to_string(),
+                    sender,
+                ))
+                .unwrap();
+            assert!(matches!(events.next().await, Some(RequestExtEvent::Init)));
+            networker
+                .send(NetworkerEvent::Dispatch(
+                    handle,
+                    vec!["Node1".to_string()],
+                    5,
+                ))
+                .unwrap();
+            assert!(matches!(
+                events.next().await,
+                Some(RequestExtEvent::Sent(..))
+            ));
+            match events.next().await {
+                Some(RequestExtEvent::Received(node_alias, _, Message::ReqACK(_), _)) => {
+                    assert_eq!(node_alias, "Node1")
+                }
+                other => panic!("Unexpected event: {:?}", other),
+            }
+            assert_eq!(networker.node_health().unwrap()["Node1"].replies, 1);
+        });
+        node.join().unwrap();
+    }
+
+    #[test]
+    fn test_tokio_networker_request_timeout() {
+        let ctx = zmq::Context::new();
+        // the node socket receives the request but never replies
+        let (_server, verifiers) = _node_socket(&ctx);
+
+        _runtime().block_on(async {
+            let networker = TokioNetworkerFactory::new()
+                .make_networker(PoolConfig::default(), &verifiers)
+                .unwrap();
+            let handle = RequestHandle::next();
+            let (sender, mut events) = unbounded();
+            networker
+                .send(NetworkerEvent::NewRequest(
+                    handle,
+                    "1".to_string(),
+                    "{}".to_string(),
+                    sender,
+                ))
+                .unwrap();
+            assert!(matches!(events.next().await, Some(RequestExtEvent::Init)));
+            networker
+                .send(NetworkerEvent::Dispatch(
+                    handle,
+                    vec!["Node1".to_string()],
+                    1,
+                ))
+                .unwrap();
+            assert!(matches!(
+                events.next().await,
+                Some(RequestExtEvent::Sent(..))
+            ));
+            match events.next().await {
+                Some(RequestExtEvent::Timeout(node_alias)) => assert_eq!(node_alias, "Node1"),
+                other => panic!("Unexpected event: {:?}", other),
+            }
+            let health = networker.node_health().unwrap();
+            assert_eq!(health["Node1"].timeouts, 1);
+            assert_eq!(health["Node1"].replies, 0);
+        });
+    }
+}
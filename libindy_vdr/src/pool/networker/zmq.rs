@@ -1,6 +1,5 @@
 use std::collections::{BTreeMap, HashMap, HashSet};
-use std::iter::FromIterator;
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant, SystemTime};
 
@@ -14,7 +13,10 @@ use crate::config::PoolConfig;
 use crate::utils::{base58, base64};
 
 use super::types::{Message, Verifiers};
-use super::{Networker, NetworkerEvent, NetworkerFactory, RequestExtEvent, RequestHandle};
+use super::{
+    unix_now, HealthTracker, Networker, NetworkerEvent, NetworkerFactory, NodeFailure,
+    NodeHealthTable, RequestExtEvent, RequestHandle,
+};
 
 new_handle_type!(ZMQSocketHandle, ZSC_COUNTER);
 
@@ -33,12 +35,18 @@ impl NetworkerFactory for ZMQNetworkerFactory {
     type Output = ZMQNetworker;
     fn make_networker(&self, config: PoolConfig, verifiers: &Verifiers) -> VdrResult<ZMQNetworker> {
         let remotes = _get_remotes(verifiers);
+        let health = Arc::new(Mutex::new(HealthTracker::new(
+            &config,
+            remotes.iter().map(|r| r.name.clone()),
+        )));
+        let thread_health = health.clone();
         let socket_handle = *ZMQSocketHandle::next();
         let (zmq_ctx, cmd_send, cmd_recv) =
             _create_pair_of_sockets(&format!("zmqnet_{}", socket_handle));
         let (evt_send, evt_recv) = mpsc::channel::<NetworkerEvent>();
         let worker = thread::spawn(move || {
-            let mut zmq_thread = ZMQThread::new(config, zmq_ctx, cmd_recv, evt_recv, remotes);
+            let mut zmq_thread =
+                ZMQThread::new(config, zmq_ctx, cmd_recv, evt_recv, remotes, thread_health);
             if let Err(err) = zmq_thread.work() {
                 warn!("ZMQ worker exited with error: {}", err.to_string())
             } else {
@@ -48,6 +56,7 @@ impl NetworkerFactory for ZMQNetworkerFactory {
         Ok(ZMQNetworker {
             cmd_send,
             evt_send,
+            health,
             worker: Some(worker),
         })
     }
@@ -57,6 +66,7 @@ impl NetworkerFactory for ZMQNetworkerFactory {
 pub struct ZMQNetworker {
     cmd_send: zmq::Socket,
     evt_send: mpsc::Sender<NetworkerEvent>,
+    health: Arc<Mutex<HealthTracker>>,
     worker: Option<thread::JoinHandle<()>>,
 }
 
@@ -70,6 +80,16 @@ impl Networker for ZMQNetworker {
             .send("", 0)
             .with_err_msg(VdrErrorKind::Resource, "Error sending networker command")
     }
+
+    fn node_health(&self) -> VdrResult<NodeHealthTable> {
+        let health = self.health.lock().map_err(|_| {
+            err_msg(
+                VdrErrorKind::Unexpected,
+                "Error acquiring node health, mutex poisoned",
+            )
+        })?;
+        Ok(health.table())
+    }
 }
 
 impl Drop for ZMQNetworker {
@@ -91,11 +111,11 @@ struct ZMQThread {
     zmq_ctx: zmq::Context,
     cmd_recv: zmq::Socket,
     evt_recv: mpsc::Receiver<NetworkerEvent>,
-    node_aliases: HashSet<String>,
     remotes: Vec<RemoteNode>,
     requests: BTreeMap<RequestHandle, PendingRequest>,
     last_connection: Option<ZMQConnectionHandle>,
     pool_connections: BTreeMap<ZMQConnectionHandle, ZMQConnection>,
+    health: Arc<Mutex<HealthTracker>>,
 }
 
 impl ZMQThread {
@@ -105,18 +125,18 @@ impl ZMQThread {
         cmd_recv: zmq::Socket,
         evt_recv: mpsc::Receiver<NetworkerEvent>,
         remotes: Vec<RemoteNode>,
+        health: Arc<Mutex<HealthTracker>>,
     ) -> Self {
-        let node_aliases = HashSet::from_iter(remotes.iter().map(|r| r.name.clone()));
         ZMQThread {
             config,
             zmq_ctx,
             cmd_recv,
             evt_recv,
-            node_aliases,
             remotes,
             requests: BTreeMap::new(),
             last_connection: None,
             pool_connections: BTreeMap::new(),
+            health,
         }
     }
 
@@ -191,6 +211,14 @@ impl ZMQThread {
                 if let Some(conn) = self.pool_connections.get_mut(&conn_id) {
                     conn.clean_idle_timeout(&req_id);
                 }
+                match meta {
+                    // acknowledgements do not affect the node health
+                    Message::ReqACK(_) => (),
+                    Message::ReqNACK(_) | Message::Reject(_) => {
+                        self.update_health(|health| health.record_rejected(&node_alias))
+                    }
+                    _ => self.update_health(|health| health.record_reply(&node_alias)),
+                }
                 (
                     req_id,
                     RequestExtEvent::Received(node_alias, message, meta, time),
@@ -207,8 +235,17 @@ impl ZMQThread {
                 if let Some(conn) = self.pool_connections.get_mut(&conn_id) {
                     conn.clean_timeout(&req_id, Some(node_alias.clone()));
                 }
+                self.update_health(|health| {
+                    health.record_failure(&node_alias, NodeFailure::Timeout, unix_now())
+                });
                 (req_id, RequestExtEvent::Timeout(node_alias))
             }
+            ConnectionEvent::Malformed(node_alias) => {
+                self.update_health(|health| {
+                    health.record_failure(&node_alias, NodeFailure::Malformed, unix_now())
+                });
+                return;
+            }
             ConnectionEvent::Timeout() => {
                 self.check_remove_connection(conn_id, None);
                 return;
@@ -221,6 +258,16 @@ impl ZMQThread {
         }
     }
 
+    fn update_health<F>(&self, f: F)
+    where
+        F: FnOnce(&mut HealthTracker),
+    {
+        match self.health.lock() {
+            Ok(mut health) => f(&mut health),
+            Err(_) => warn!("Error acquiring node health, mutex poisoned"),
+        }
+    }
+
     fn select_request(&self, conn_id: ZMQConnectionHandle, sub_id: &str) -> Option<RequestHandle> {
         self.requests.iter().find_map(|(handle, req)| {
             if req.conn_id == conn_id && req.sub_id == sub_id {
@@ -280,9 +327,38 @@ impl ZMQThread {
                 self.extend_timeout(handle, node_alias, timeout).unwrap();
                 Ok(true)
             }
+            NetworkerEvent::ReportFailure(node_alias, failure) => {
+                trace!("Node failure reported {}: {:?}", node_alias, failure);
+                self.update_health(|health| {
+                    health.record_failure(&node_alias, failure, unix_now())
+                });
+                Ok(true)
+            }
+            NetworkerEvent::ReportSuccess(node_alias) => {
+                trace!("Node success reported {}", node_alias);
+                self.update_health(|health| health.record_success(&node_alias));
+                Ok(true)
+            }
+            NetworkerEvent::UpdateVerifiers(verifiers) => {
+                trace!("Update verifiers {:?}", verifiers.keys());
+                self.update_remotes(_get_remotes(&verifiers));
+                Ok(true)
+            }
         }
     }
 
+    fn update_remotes(&mut self, remotes: Vec<RemoteNode>) {
+        self.update_health(|health| health.update_nodes(remotes.iter().map(|r| r.name.clone())));
+        // earlier connections keep the previous node set until their requests complete
+        if let Some(conn) = self
+            .last_connection
+            .and_then(|conn_id| self.pool_connections.get_mut(&conn_id))
+        {
+            conn.update_remotes(&remotes);
+        }
+        self.remotes = remotes;
+    }
+
     fn add_request(
         &mut self,
         handle: RequestHandle,
@@ -339,10 +415,24 @@ impl ZMQThread {
         if let Some(request) = self.requests.get_mut(&handle) {
             if let Some(conn) = self.pool_connections.get_mut(&request.conn_id) {
                 for node_alias in node_aliases {
-                    if !self.node_aliases.contains(&node_alias) {
+                    if !conn.has_remote(&node_alias) {
                         warn!("Cannot send to unknown node alias: {}", node_alias);
                         continue;
                     }
+                    let available = match self.health.lock() {
+                        Ok(mut health) => health.check_dispatch(&node_alias, unix_now()),
+                        Err(_) => true,
+                    };
+                    if !available {
+                        // report blacklisted nodes as timed out so the request can move on
+                        debug!("Skipping blacklisted node: {}", node_alias);
+                        if !request.send_event(RequestExtEvent::Timeout(node_alias)) {
+                            trace!("Removing, sender disconnected {}", handle);
+                            self.remove_request(handle);
+                            break;
+                        }
+                        continue;
+                    }
                     conn.send_request(
                         request.sub_id.clone(),
                         request.body.clone(),
@@ -530,7 +620,10 @@ impl ZMQConnection {
                                 SystemTime::now(),
                             ));
                         }
-                        Err(err) => debug!("Error parsing received message: {:?}", err),
+                        Err(err) => {
+                            debug!("Error parsing received message: {:?}", err);
+                            return Some(ConnectionEvent::Malformed(rn.name.clone()));
+                        }
                     }
                 }
             }
@@ -604,7 +697,11 @@ impl ZMQConnection {
         timeout: i64,
     ) -> VdrResult<()> {
         trace!("send_request >> req_id: {} node: {}", req_id, node_alias);
-        let node_index = self.remotes.iter().position(|node| node.name == node_alias);
+        // prefer the most recent entry for nodes which have been updated
+        let node_index = self
+            .remotes
+            .iter()
+            .rposition(|node| node.name == node_alias);
         if let Some(node_index) = node_index {
             let s = self._get_socket(node_index)?;
             s.send(&msg, zmq::DONTWAIT)?;
@@ -621,6 +718,22 @@ impl ZMQConnection {
         Ok(())
     }
 
+    fn has_remote(&self, node_alias: &str) -> bool {
+        self.remotes.iter().any(|node| node.name == node_alias)
+    }
+
+    /// Add new and changed remote nodes, retaining the existing sockets
+    ///
+    /// Previous entries are kept so that pending requests may still complete.
+    fn update_remotes(&mut self, remotes: &[RemoteNode]) {
+        for remote in remotes {
+            if !self.remotes.contains(remote) {
+                self.remotes.push(remote.clone());
+                self.sockets.push(None);
+            }
+        }
+    }
+
     fn add_timeout(&mut self, req_id: String, alias: String, timeout: i64) {
         self.socket_timeouts.insert(
             (req_id, alias),
@@ -712,15 +825,14 @@ impl ZMQConnection {
 }
 
 #[derive(Clone, Eq, PartialEq, Hash)]
-struct RemoteNode {
+pub(super) struct RemoteNode {
     pub name: String,
     pub enc_key: Vec<u8>,
     pub zaddr: String,
-    pub is_blacklisted: bool,
 }
 
 impl RemoteNode {
-    fn connect(
+    pub(super) fn connect(
         &self,
         ctx: &zmq::Context,
         key_pair: &zmq::CurveKeyPair,
@@ -756,8 +868,8 @@ impl std::fmt::Debug for RemoteNode {
         let pubkey = base58::encode(&self.enc_key);
         write!(
             f,
-            "RemoteNode {{ name: {}, public_key: {}, zaddr: {}, is_blacklisted: {:?} }}",
-            self.name, pubkey, self.zaddr, self.is_blacklisted
+            "RemoteNode {{ name: {}, public_key: {}, zaddr: {} }}",
+            self.name, pubkey, self.zaddr
         )
     }
 }
@@ -773,14 +885,13 @@ fn _create_pair_of_sockets(addr: &str) -> (zmq::Context, zmq::Socket, zmq::Socke
     (zmq_ctx, send_cmd_sock, recv_cmd_sock)
 }
 
-fn _get_remotes(verifiers: &Verifiers) -> Vec<RemoteNode> {
+pub(super) fn _get_remotes(verifiers: &Verifiers) -> Vec<RemoteNode> {
     verifiers
         .iter()
         .map(|(alias, info)| RemoteNode {
             name: alias.clone(),
             enc_key: info.enc_key.clone(),
             zaddr: info.client_addr.clone(),
-            is_blacklisted: false,
         })
         .collect()
 }
@@ -805,6 +916,9 @@ enum ConnectionEvent {
         String, // req id
         String, // node alias
     ),
+    Malformed(
+        String, // node alias
+    ),
     Timeout(),
 }
 
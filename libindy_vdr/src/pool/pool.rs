@@ -1,25 +1,28 @@
-extern crate rand;
 extern crate rmp_serde;
 
-use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::Arc;
 
 use futures_channel::mpsc::unbounded;
-use futures_util::future::{lazy, FutureExt, LocalBoxFuture};
-use rand::seq::SliceRandom;
+use futures_util::future::{lazy, BoxFuture, FutureExt, LocalBoxFuture};
+use serde_json::Value as SJsonValue;
 
 use super::genesis::{build_node_transaction_map, build_verifiers, PoolTransactions};
+#[cfg(all(feature = "tokio_networker", unix))]
+use super::networker::TokioNetworker;
 use super::networker::{
-    LocalNetworker, Networker, NetworkerEvent, NetworkerFactory, SharedNetworker,
+    order_by_health, LocalNetworker, Networker, NetworkerEvent, NetworkerFactory, NodeHealthTable,
+    SharedNetworker,
 };
 use super::requests::{PoolRequest, PoolRequestImpl};
+use super::selector::NodeSelector;
 use super::types::{PoolSetup, RequestHandle, Verifiers};
 
 use crate::common::error::prelude::*;
 use crate::common::merkle_tree::MerkleTree;
 use crate::config::PoolConfig;
 use crate::ledger::RequestBuilder;
+use crate::state_proof::{BoxedSPParser, StateProofParsers};
 use crate::utils::base58;
 
 /// A generic verifier pool with support for creating pool transaction requests
@@ -60,6 +63,24 @@ pub trait Pool: Clone {
 
     /// Get the summarized verifier details.
     fn get_verifier_info(&self) -> VdrResult<Verifiers>;
+
+    /// Get the health statistics collected for each verifier node.
+    fn get_node_health(&self) -> VdrResult<NodeHealthTable>;
+
+    /// Get the custom state proof parser registered with the pool for a transaction type.
+    fn get_state_proof_parser(&self, _txn_type: &str) -> Option<&BoxedSPParser> {
+        None
+    }
+}
+
+/// A verifier pool which can be shared between threads, producing `Send` request futures
+pub trait SendPool: Pool + Send + Sync {
+    /// Create a new pool request instance which may be awaited from any thread
+    fn create_send_request<'a>(
+        &'a self,
+        req_id: String,
+        req_json: String,
+    ) -> BoxFuture<'a, VdrResult<Self::Request>>;
 }
 
 /// The default `Pool` implementation
@@ -75,6 +96,10 @@ pub type LocalPool = PoolImpl<Rc<PoolSetup>, LocalNetworker>;
 /// A verifier pool instance which can be shared between threads
 pub type SharedPool = PoolImpl<Arc<PoolSetup>, SharedNetworker>;
 
+/// A verifier pool instance driven by tasks on a Tokio runtime
+#[cfg(all(feature = "tokio_networker", unix))]
+pub type TokioPool = PoolImpl<Arc<PoolSetup>, TokioNetworker>;
+
 impl<S, T> PoolImpl<S, T>
 where
     S: AsRef<PoolSetup> + Clone + From<Box<PoolSetup>>,
@@ -89,7 +114,8 @@ where
         config: PoolConfig,
         merkle_tree: MerkleTree,
         networker_factory: F,
-        node_weights: Option<HashMap<String, f32>>,
+        node_selector: Option<Arc<dyn NodeSelector>>,
+        state_proof_parsers: Option<StateProofParsers>,
     ) -> VdrResult<Self>
     where
         F: NetworkerFactory<Output = T>,
@@ -97,9 +123,67 @@ where
         let txn_map = build_node_transaction_map(&merkle_tree, config.protocol_version)?;
         let verifiers = build_verifiers(txn_map)?;
         let networker = networker_factory.make_networker(config.clone(), &verifiers)?;
-        let setup = PoolSetup::new(config.clone(), merkle_tree, node_weights, verifiers);
+        let setup = PoolSetup::new(
+            config.clone(),
+            merkle_tree,
+            node_selector,
+            verifiers,
+            state_proof_parsers,
+        );
         Ok(Self::new(S::from(Box::new(setup)), networker))
     }
+
+    /// Apply new pool ledger transactions to this instance, reusing the existing networker.
+    ///
+    /// The networker only connects to added or changed verifiers, and requests created
+    /// before the update complete using the previous set of verifiers.
+    ///
+    /// Transactions are identified by their sequence number: those already included in the
+    /// pool ledger are skipped, so the result of a catchup may safely be applied more than once.
+    /// The remaining transactions must directly extend the pool ledger.
+    pub fn apply_transactions<I>(&mut self, new_txns: I) -> VdrResult<()>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let prev = self.setup.as_ref();
+        let mut next_seq_no = prev.merkle_tree.count() + 1;
+        let mut added = vec![];
+        for txn in new_txns {
+            let seq_no = _txn_seq_no(txn.as_ref())?;
+            if seq_no < next_seq_no {
+                continue;
+            }
+            if seq_no > next_seq_no {
+                return Err(input_err(format!(
+                    "Missing pool transaction with sequence number {}",
+                    next_seq_no
+                )));
+            }
+            added.push(txn);
+            next_seq_no += 1;
+        }
+        if added.is_empty() {
+            return Ok(());
+        }
+
+        let mut txns = PoolTransactions::from(&prev.merkle_tree);
+        txns.extend_from_json(added)?;
+        let merkle_tree = txns.into_merkle_tree()?;
+        let txn_map = build_node_transaction_map(&merkle_tree, prev.config.protocol_version)?;
+        let verifiers = build_verifiers(txn_map)?;
+        let setup = PoolSetup::new(
+            prev.config.clone(),
+            merkle_tree,
+            Some(prev.node_selector.clone()),
+            verifiers.clone(),
+            Some(prev.state_proof_parsers.clone()),
+        );
+        self.networker
+            .send(NetworkerEvent::UpdateVerifiers(verifiers))?;
+        self.setup = S::from(Box::new(setup));
+        Ok(())
+    }
 }
 
 impl<S, T> Pool for PoolImpl<S, T>
@@ -116,18 +200,7 @@ where
     ) -> LocalBoxFuture<'a, VdrResult<Self::Request>> {
         let setup = self.setup.clone();
         let networker = self.networker.clone();
-        lazy(move |_| {
-            let (tx, rx) = unbounded();
-            let handle = RequestHandle::next();
-            let setup_ref = setup.as_ref();
-            let node_order = choose_nodes(&setup_ref.verifiers, setup_ref.node_weights.clone());
-            debug!("New {}: reqId({})", handle, req_id);
-            networker.send(NetworkerEvent::NewRequest(handle, req_id, req_json, tx))?;
-            Ok(PoolRequestImpl::new(
-                handle, rx, setup, networker, node_order,
-            ))
-        })
-        .boxed_local()
+        lazy(move |_| _init_request(setup, networker, req_id, req_json)).boxed_local()
     }
 
     fn get_config(&self) -> &PoolConfig {
@@ -145,35 +218,105 @@ where
     fn get_verifier_info(&self) -> VdrResult<Verifiers> {
         Ok(self.setup.as_ref().verifiers.clone())
     }
+
+    fn get_node_health(&self) -> VdrResult<NodeHealthTable> {
+        self.networker.node_health()
+    }
+
+    fn get_state_proof_parser(&self, txn_type: &str) -> Option<&BoxedSPParser> {
+        self.setup.as_ref().state_proof_parsers.get(txn_type)
+    }
 }
 
-pub(crate) fn choose_nodes(
-    verifiers: &Verifiers,
-    weights: Option<HashMap<String, f32>>,
-) -> Vec<String> {
-    let mut weighted = verifiers
-        .keys()
-        .map(|name| {
-            (
-                weights
-                    .as_ref()
-                    .and_then(|w| w.get(name))
-                    .cloned()
-                    .unwrap_or(1.0),
-                name.as_str(),
-            )
-        })
-        .collect::<Vec<(f32, &str)>>();
-    let mut rng = rand::thread_rng();
-    let mut result = vec![];
-    for _ in 0..weighted.len() {
-        let found = weighted
-            .choose_weighted_mut(&mut rng, |item| item.0)
+impl<S, T> SendPool for PoolImpl<S, T>
+where
+    S: AsRef<PoolSetup> + Clone + Send + Sync,
+    T: Networker + Clone + Send + Sync,
+{
+    fn create_send_request<'a>(
+        &'a self,
+        req_id: String,
+        req_json: String,
+    ) -> BoxFuture<'a, VdrResult<Self::Request>> {
+        let setup = self.setup.clone();
+        let networker = self.networker.clone();
+        lazy(move |_| _init_request(setup, networker, req_id, req_json)).boxed()
+    }
+}
+
+fn _txn_seq_no(txn: &str) -> VdrResult<usize> {
+    let txn = serde_json::from_str::<SJsonValue>(txn)
+        .with_input_err("Error deserializing transaction as JSON")?;
+    txn["txnMetadata"]["seqNo"]
+        .as_u64()
+        .map(|seq_no| seq_no as usize)
+        .ok_or_else(|| input_err("Pool transaction has no sequence number"))
+}
+
+fn _init_request<S, T>(
+    setup: S,
+    networker: T,
+    req_id: String,
+    req_json: String,
+) -> VdrResult<PoolRequestImpl<S, T>>
+where
+    S: AsRef<PoolSetup> + Clone,
+    T: Networker + Clone,
+{
+    let (tx, rx) = unbounded();
+    let handle = RequestHandle::next();
+    let setup_ref = setup.as_ref();
+    let mut node_order = setup_ref.node_selector.select_nodes(&setup_ref.verifiers);
+    order_by_health(&mut node_order, &networker.node_health()?);
+    debug!("New {}: reqId({})", handle, req_id);
+    networker.send(NetworkerEvent::NewRequest(handle, req_id, req_json, tx))?;
+    Ok(PoolRequestImpl::new(
+        handle, rx, setup, networker, node_order,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::pool::networker::fixtures::*;
+    use crate::pool::networker::{SimulatedNetworkerFactory, SimulatedNode, SimulatedReply};
+    use crate::pool::RequestResult;
+
+    #[test]
+    fn test_apply_transactions_updates_verifiers() {
+        let mut pool = _pool(SimulatedNetworkerFactory::new().default_node(
+            SimulatedNode::replies(vec![SimulatedReply::Reply(_nym_result("verkey"))]),
+        ));
+        let prev = pool.clone();
+        pool.apply_transactions(&[_new_node_txn().to_string()])
             .unwrap();
-        found.0 = 0.0;
-        result.push(found.1.to_string());
+        assert_eq!(pool.get_merkle_tree().count(), 5);
+        assert!(pool.get_node_aliases().contains(&"Node5".to_string()));
+        assert_eq!(prev.get_node_aliases().len(), 4);
+        match _send_nym(&pool) {
+            RequestResult::Reply(reply) => assert_eq!(_reply_result(&reply), _nym_result("verkey")),
+            RequestResult::Failed(err) => panic!("Unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn test_apply_transactions_skips_applied_transactions() {
+        let mut pool = _pool(SimulatedNetworkerFactory::new());
+        let new_txn = _new_node_txn().to_string();
+        pool.apply_transactions(&[&new_txn]).unwrap();
+        let root = pool.get_merkle_tree().root_hash().clone();
+
+        // an overlapping catchup result is only applied once
+        pool.apply_transactions(&[&new_txn]).unwrap();
+        assert_eq!(pool.get_merkle_tree().count(), 5);
+        assert_eq!(pool.get_merkle_tree().root_hash(), &root);
+
+        let mut gap_txn = _new_node_txn();
+        gap_txn["txnMetadata"]["seqNo"] = json!(7);
+        pool.apply_transactions(&[gap_txn.to_string()]).unwrap_err();
+        assert_eq!(pool.get_merkle_tree().count(), 5);
     }
-    result
 }
 
 /*
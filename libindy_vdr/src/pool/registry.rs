@@ -0,0 +1,366 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value as SJsonValue;
+
+use super::genesis::PoolTransactions;
+use super::helpers::perform_ledger_request;
+use super::pool::Pool;
+use super::requests::PreparedRequest;
+use super::types::RequestResult;
+use crate::common::error::prelude::*;
+
+/// The file names of genesis transactions within a genesis directory
+const GENESIS_FILE_NAMES: [&str; 2] = [
+    "pool_transactions_genesis",
+    "pool_transactions_genesis.json",
+];
+
+static NAMESPACE: Lazy<Regex> = Lazy::new(|| Regex::new("^[a-z0-9_-]+(?::[a-z0-9_-]+)*$").unwrap());
+
+/// A collection of verifier pools for multiple ledgers, keyed by `did:indy` namespace
+///
+/// Requests for `did:indy` identifiers are routed to the pool serving their namespace.
+/// Requests for unqualified DIDs, `did:sov` DIDs and legacy object identifiers are sent
+/// to every pool in parallel.
+#[derive(Clone, Debug)]
+pub struct PoolRegistry<T: Pool> {
+    pools: BTreeMap<String, T>,
+}
+
+impl<T: Pool> PoolRegistry<T> {
+    /// Create a new, empty `PoolRegistry`
+    pub fn new() -> Self {
+        Self {
+            pools: BTreeMap::new(),
+        }
+    }
+
+    /// Create a `PoolRegistry` from a directory of genesis transaction files
+    ///
+    /// The directory follows the layout of the `indy-did-networks` repository: each
+    /// `pool_transactions_genesis` file is registered under the namespace formed by its
+    /// relative directory path, so that `sovrin/staging/pool_transactions_genesis` is
+    /// registered as `sovrin:staging`. A `.json` extension is also accepted, but a
+    /// directory may not contain both files.
+    pub fn from_genesis_dir<P, F>(path: P, mut create: F) -> VdrResult<Self>
+    where
+        P: AsRef<Path>,
+        F: FnMut(&str, PoolTransactions) -> VdrResult<T>,
+    {
+        let mut found = BTreeMap::new();
+        _find_genesis_files(path.as_ref(), &mut vec![], &mut found)?;
+        let mut registry = Self::new();
+        for (namespace, file_path) in found {
+            let txns = PoolTransactions::from_json_file(&file_path)?;
+            let pool = create(&namespace, txns)?;
+            registry.insert(namespace, pool)?;
+        }
+        Ok(registry)
+    }
+
+    /// Register the pool for a namespace, returning the pool it replaces
+    pub fn insert(&mut self, namespace: String, pool: T) -> VdrResult<Option<T>> {
+        if !NAMESPACE.is_match(&namespace) {
+            return Err(input_err(format!(
+                "Invalid ledger namespace: {}",
+                namespace
+            )));
+        }
+        Ok(self.pools.insert(namespace, pool))
+    }
+
+    /// Remove the pool for a namespace
+    pub fn remove(&mut self, namespace: &str) -> Option<T> {
+        self.pools.remove(namespace)
+    }
+
+    /// Get the pool for a namespace
+    pub fn get(&self, namespace: &str) -> Option<&T> {
+        self.pools.get(namespace)
+    }
+
+    /// Get the registered namespaces in sorted order
+    pub fn namespaces(&self) -> impl Iterator<Item = &str> {
+        self.pools.keys().map(String::as_str)
+    }
+
+    /// Iterate over the registered namespaces and pools
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &T)> {
+        self.pools.iter().map(|(ns, pool)| (ns.as_str(), pool))
+    }
+
+    /// Get the number of registered pools
+    pub fn len(&self) -> usize {
+        self.pools.len()
+    }
+
+    /// Check whether no pools are registered
+    pub fn is_empty(&self) -> bool {
+        self.pools.is_empty()
+    }
+
+    /// Select the pools to query for a DID, DID URL or ledger object identifier
+    ///
+    /// A `did:indy` identifier selects the pool for its namespace, which must be registered.
+    /// Any other identifier selects all pools.
+    pub fn route(&self, id: &str) -> VdrResult<Vec<(&str, &T)>> {
+        if let Some(did) = id.strip_prefix("did:indy:") {
+            let namespace = _did_indy_namespace(did)
+                .ok_or_else(|| input_err(format!("Invalid did:indy identifier: {}", id)))?;
+            match self.pools.get_key_value(namespace) {
+                Some((namespace, pool)) => Ok(vec![(namespace.as_str(), pool)]),
+                None => Err(input_err(format!(
+                    "No pool registered for ledger namespace: {}",
+                    namespace
+                ))),
+            }
+        } else {
+            Ok(self.iter().collect())
+        }
+    }
+
+    /// Perform a read request against the pools selected for an identifier
+    ///
+    /// The request is built for each selected pool and the pools are queried in parallel.
+    /// The first reply containing a ledger entry is returned along with the namespace of
+    /// its pool. When no pool has a matching entry, the reply of the first pool to respond
+    /// is returned instead, or the first error when every request failed.
+    pub async fn perform_request<F>(
+        &self,
+        id: &str,
+        build: F,
+    ) -> VdrResult<(String, RequestResult<String>)>
+    where
+        F: Fn(&T) -> VdrResult<PreparedRequest>,
+    {
+        let pools = self.route(id)?;
+        if pools.is_empty() {
+            return Err(input_err("No pools registered"));
+        }
+        let mut requests = pools
+            .into_iter()
+            .map(|(namespace, pool)| _perform_request(namespace, pool, build(pool)))
+            .collect::<FuturesUnordered<_>>();
+
+        let mut fallback = None;
+        while let Some(result) = requests.next().await {
+            let found = match &result {
+                Ok((_, RequestResult::Reply(reply))) => _reply_has_entry(reply),
+                _ => false,
+            };
+            if found {
+                return result;
+            }
+            // prefer a reply from any pool over a failure
+            let replace = matches!((&fallback, &result), (None, _) | (Some(Err(_)), Ok(_)));
+            if replace {
+                fallback = Some(result);
+            }
+        }
+        fallback.unwrap_or_else(|| Err(input_err("No pools registered")))
+    }
+}
+
+impl<T: Pool> Default for PoolRegistry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn _perform_request<T: Pool>(
+    namespace: &str,
+    pool: &T,
+    request: VdrResult<PreparedRequest>,
+) -> VdrResult<(String, RequestResult<String>)> {
+    let (result, _timing) = perform_ledger_request(pool, &request?).await?;
+    Ok((namespace.to_string(), result))
+}
+
+/// Check whether a read reply contains a ledger entry
+///
+/// Replies to lookups which were not found carry a null sequence number, or null data
+/// for replies without a sequence number.
+fn _reply_has_entry(reply: &str) -> bool {
+    let result = match serde_json::from_str::<SJsonValue>(reply) {
+        Ok(mut reply) => reply["result"].take(),
+        Err(_) => return false,
+    };
+    match result.get("seqNo") {
+        Some(seq_no) => !seq_no.is_null(),
+        None => !result["data"].is_null(),
+    }
+}
+
+/// Extract the namespace from a `did:indy` DID or DID URL, following the method prefix
+fn _did_indy_namespace(did: &str) -> Option<&str> {
+    let did = did
+        .split(|c| c == '/' || c == '?' || c == '#')
+        .next()
+        .unwrap_or_default();
+    let pos = did.rfind(':')?;
+    let (namespace, id) = (&did[..pos], &did[pos + 1..]);
+    if NAMESPACE.is_match(namespace) && !id.is_empty() {
+        Some(namespace)
+    } else {
+        None
+    }
+}
+
+fn _find_genesis_files(
+    path: &Path,
+    namespace: &mut Vec<String>,
+    found: &mut BTreeMap<String, PathBuf>,
+) -> VdrResult<()> {
+    let entries = fs::read_dir(path).map_err(|err| {
+        err_msg(
+            VdrErrorKind::FileSystem(err),
+            format!("Error reading genesis directory: {:?}", path),
+        )
+    })?;
+    for entry in entries {
+        let entry = entry.map_err(|err| {
+            err_msg(
+                VdrErrorKind::FileSystem(err),
+                format!("Error reading genesis directory: {:?}", path),
+            )
+        })?;
+        let entry_path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if entry_path.is_dir() {
+            namespace.push(name);
+            _find_genesis_files(&entry_path, namespace, found)?;
+            namespace.pop();
+        } else if GENESIS_FILE_NAMES.contains(&name.as_str()) {
+            if namespace.is_empty() {
+                warn!(
+                    "Ignoring genesis file without a namespace: {:?}",
+                    entry_path
+                );
+            } else if let Some(prev) = found.insert(namespace.join(":"), entry_path.clone()) {
+                return Err(err_msg(
+                    VdrErrorKind::Config,
+                    format!(
+                        "Multiple genesis files found: {:?} and {:?}",
+                        prev, entry_path
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::{LocalPool, PoolBuilder};
+    use indy_test_utils::genesis::GenesisTransactions;
+
+    fn _genesis_dir(name: &str, namespaces: &[&str]) -> PathBuf {
+        let path = std::env::temp_dir().join("indy_vdr_tests").join(format!(
+            "{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&path);
+        for namespace in namespaces {
+            let dir = namespace
+                .split(':')
+                .fold(path.clone(), |dir, part| dir.join(part));
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join(GENESIS_FILE_NAMES[0]),
+                GenesisTransactions::new(None).transactions.join("\n"),
+            )
+            .unwrap();
+        }
+        path
+    }
+
+    fn _registry(path: &Path) -> PoolRegistry<LocalPool> {
+        PoolRegistry::from_genesis_dir(path, |_, txns| {
+            PoolBuilder::default().transactions(txns)?.into_local()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_load_genesis_dir() {
+        let path = _genesis_dir("load_genesis_dir", &["sovrin", "sovrin:staging", "idunion"]);
+        let registry = _registry(&path);
+        assert_eq!(
+            registry.namespaces().collect::<Vec<_>>(),
+            vec!["idunion", "sovrin", "sovrin:staging"]
+        );
+    }
+
+    #[test]
+    fn test_route_by_namespace() {
+        let path = _genesis_dir("route_by_namespace", &["sovrin", "sovrin:staging"]);
+        let registry = _registry(&path);
+
+        let pools = registry
+            .route("did:indy:sovrin:staging:F72i3Y3Q4i466efjYJYCHM/anoncreds/v0/SCHEMA/npdb/4.3.4")
+            .unwrap();
+        assert_eq!(pools.len(), 1);
+        assert_eq!(pools[0].0, "sovrin:staging");
+
+        assert_eq!(registry.route("F72i3Y3Q4i466efjYJYCHM").unwrap().len(), 2);
+        assert_eq!(
+            registry
+                .route("F72i3Y3Q4i466efjYJYCHM:2:npdb:4.3.4")
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(registry
+            .route("did:indy:idunion:F72i3Y3Q4i466efjYJYCHM")
+            .is_err());
+        assert!(registry.route("did:indy:sovrin").is_err());
+    }
+
+    #[test]
+    fn test_did_indy_namespace() {
+        assert_eq!(
+            _did_indy_namespace("sovrin:staging:F72i3Y3Q4i466efjYJYCHM?versionId=1"),
+            Some("sovrin:staging")
+        );
+        assert_eq!(
+            _did_indy_namespace("sovrin:F72i3Y3Q4i466efjYJYCHM/anoncreds/v0/SCHEMA/npdb/4.3.4"),
+            Some("sovrin")
+        );
+        assert_eq!(_did_indy_namespace("F72i3Y3Q4i466efjYJYCHM"), None);
+        assert_eq!(_did_indy_namespace("Sovrin:F72i3Y3Q4i466efjYJYCHM"), None);
+    }
+
+    #[test]
+    fn test_reject_duplicate_genesis_files() {
+        let path = _genesis_dir("duplicate_genesis_files", &["sovrin"]);
+        fs::copy(
+            path.join("sovrin").join(GENESIS_FILE_NAMES[0]),
+            path.join("sovrin").join(GENESIS_FILE_NAMES[1]),
+        )
+        .unwrap();
+        let result = PoolRegistry::<LocalPool>::from_genesis_dir(&path, |_, txns| {
+            PoolBuilder::default().transactions(txns)?.into_local()
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_reply_has_entry() {
+        assert!(_reply_has_entry(
+            r#"{"result": {"seqNo": 10, "data": "{}"}}"#
+        ));
+        assert!(!_reply_has_entry(
+            r#"{"result": {"seqNo": null, "data": null}}"#
+        ));
+        assert!(!_reply_has_entry(r#"{"result": {"data": null}}"#));
+        assert!(_reply_has_entry(r#"{"result": {"data": {"value": {}}}}"#));
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::iter::FromIterator;
 use std::pin::Pin;
+use std::time::SystemTime;
 
 use futures_channel::mpsc::UnboundedReceiver;
 use futures_util::stream::{FusedStream, Stream};
@@ -10,8 +11,9 @@ use pin_utils::unsafe_pinned;
 
 use crate::common::error::prelude::*;
 use crate::config::PoolConfig;
+use crate::state_proof::SignatureCache;
 
-use super::networker::{Networker, NetworkerEvent};
+use super::networker::{Networker, NetworkerEvent, NodeFailure};
 use super::types::{RequestHandle, TimingResult, VerifierKeys};
 use super::PoolSetup;
 use super::{RequestEvent, RequestExtEvent, RequestState, RequestTiming};
@@ -27,6 +29,9 @@ pub trait PoolRequest: std::fmt::Debug + Stream<Item = RequestEvent> + FusedStre
     fn node_keys(&self) -> VerifierKeys;
     fn node_order(&self) -> Vec<String>;
     fn pool_config(&self) -> PoolConfig;
+    fn report_failure(&self, node_alias: String, failure: NodeFailure) -> VdrResult<()>;
+    fn report_success(&self, node_alias: String) -> VdrResult<()>;
+    fn signature_cache(&self) -> Option<&SignatureCache>;
     fn send_to_all(&mut self, timeout: i64) -> VdrResult<()>;
     fn send_to_any(&mut self, count: usize, timeout: i64) -> VdrResult<Vec<String>>;
     fn send_to(&mut self, node_aliases: Vec<String>, timeout: i64) -> VdrResult<Vec<String>>;
@@ -132,6 +137,18 @@ where
         self.pool_setup.as_ref().config.clone()
     }
 
+    fn report_failure(&self, node_alias: String, failure: NodeFailure) -> VdrResult<()> {
+        self.trigger(NetworkerEvent::ReportFailure(node_alias, failure))
+    }
+
+    fn report_success(&self, node_alias: String) -> VdrResult<()> {
+        self.trigger(NetworkerEvent::ReportSuccess(node_alias))
+    }
+
+    fn signature_cache(&self) -> Option<&SignatureCache> {
+        Some(&self.pool_setup.as_ref().signature_cache)
+    }
+
     fn send_to_all(&mut self, timeout: i64) -> VdrResult<()> {
         let aliases = self.node_order();
         let count = aliases.len();
@@ -202,6 +219,10 @@ where
 {
     fn drop(&mut self) {
         trace!("Finish dropped request: {}", self.handle);
+        let selector = &self.pool_setup.as_ref().node_selector;
+        for (node_alias, latency) in self.timing.latencies(SystemTime::now()) {
+            selector.record_latency(&node_alias, latency);
+        }
         self.trigger(NetworkerEvent::FinishRequest(self.handle))
             .unwrap_or(()) // don't mind if the receiver disconnected
     }
@@ -226,7 +247,11 @@ where
                                     trace!("Request active {}", self.handle);
                                     self.state = RequestState::Active
                                 } else {
-                                    trace!("Request aborted {}", self.handle);
+                                    if let Some(RequestExtEvent::Error(err)) = val {
+                                        warn!("Request failed {}: {}", self.handle, err);
+                                    } else {
+                                        trace!("Request aborted {}", self.handle);
+                                    }
                                     // events.close(); ?
                                     self.as_mut().events().set(None);
                                     self.state = RequestState::Terminated
@@ -0,0 +1,269 @@
+use crate::utils::{Validatable, ValidationError};
+
+/// The number of matching replies required to accept a consensus result
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplyQuorum {
+    /// `f + 1` matching replies, where `f` is the number of tolerated faulty nodes
+    #[default]
+    Weak,
+    /// `2f + 1` matching replies
+    Strong,
+    /// A fixed number of matching replies
+    Count(usize),
+}
+
+impl ReplyQuorum {
+    /// Get the number of matching replies required for a given number of tolerated faulty nodes
+    pub fn required_replies(&self, f: usize) -> usize {
+        match self {
+            Self::Weak => f + 1,
+            Self::Strong => 2 * f + 1,
+            Self::Count(count) => *count,
+        }
+    }
+}
+
+/// The freshness requirement for accepting a reply with a state proof
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FreshnessPolicy {
+    /// Apply the freshness threshold of the pool configuration
+    #[default]
+    Pool,
+    /// Accept state proofs regardless of when the state was signed, as for immutable objects
+    Skip,
+    /// Require the state to be signed within a number of seconds
+    Within(u64),
+}
+
+impl FreshnessPolicy {
+    /// Get the freshness threshold to apply, if any, given the configured threshold
+    pub fn threshold(&self, pool_threshold: u64) -> Option<u64> {
+        match self {
+            Self::Pool => Some(pool_threshold),
+            Self::Skip => None,
+            Self::Within(threshold) => Some(*threshold),
+        }
+    }
+}
+
+/// A minimum number of matching replies which must come from a specific set of nodes
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeQuorum {
+    /// The aliases of the nodes
+    pub node_aliases: Vec<String>,
+    /// The number of these nodes which must be among the matching replies
+    pub min_replies: usize,
+}
+
+/// Rules for accepting the replies to a request resolved by consensus
+///
+/// The default policy reproduces the standard behaviour: `f + 1` matching replies
+/// are required, unless a single reply carries a valid state proof.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsensusPolicy {
+    /// The number of matching replies required
+    #[serde(default)]
+    pub quorum: ReplyQuorum,
+    /// Whether a single reply with a valid state proof is accepted without a quorum
+    #[serde(default = "ConsensusPolicy::default_accept_state_proof")]
+    pub accept_state_proof: bool,
+    /// The freshness requirement for accepting a reply with a state proof
+    #[serde(default)]
+    pub freshness: FreshnessPolicy,
+    /// The number of nodes to contact initially, overriding the default for the request type
+    #[serde(default)]
+    pub initial_nodes: Option<usize>,
+    /// A set of nodes which must be among the matching replies.
+    /// When present, the request is sent to all nodes.
+    #[serde(default)]
+    pub required_nodes: Option<NodeQuorum>,
+    /// Whether write replies must carry a valid audit path for the committed transaction
+    ///
+    /// A multi-signature over the ledger root is also verified when present. Without one,
+    /// the audit path is only checked against the root hash reported by the same node, which
+    /// adds no assurance beyond the matching replies required for consensus.
+    #[serde(default)]
+    pub require_inclusion_proof: bool,
+}
+
+impl ConsensusPolicy {
+    /// Create a new `ConsensusPolicy` with the default rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of matching replies required
+    pub fn quorum(mut self, quorum: ReplyQuorum) -> Self {
+        self.quorum = quorum;
+        self
+    }
+
+    /// Set whether a single reply with a valid state proof is accepted without a quorum
+    pub fn accept_state_proof(mut self, accept: bool) -> Self {
+        self.accept_state_proof = accept;
+        self
+    }
+
+    /// Set the freshness requirement for accepting a reply with a state proof
+    pub fn freshness(mut self, freshness: FreshnessPolicy) -> Self {
+        self.freshness = freshness;
+        self
+    }
+
+    /// Get the freshness threshold to apply, if any, given the configured threshold
+    pub fn freshness_threshold(policy: Option<&Self>, pool_threshold: u64) -> Option<u64> {
+        policy
+            .map(|policy| policy.freshness)
+            .unwrap_or_default()
+            .threshold(pool_threshold)
+    }
+
+    /// Set the number of nodes to contact initially
+    pub fn initial_nodes(mut self, count: Option<usize>) -> Self {
+        self.initial_nodes = count;
+        self
+    }
+
+    /// Require that a minimum number of the matching replies come from the given nodes
+    pub fn required_nodes(mut self, node_aliases: Vec<String>, min_replies: usize) -> Self {
+        self.required_nodes.replace(NodeQuorum {
+            node_aliases,
+            min_replies,
+        });
+        self
+    }
+
+    /// Set whether write replies must carry a valid audit path for the committed transaction
+    pub fn require_inclusion_proof(mut self, require: bool) -> Self {
+        self.require_inclusion_proof = require;
+        self
+    }
+
+    /// Check whether a set of nodes with matching replies satisfies the required nodes
+    pub fn has_required_nodes<'a, I>(&self, node_aliases: I) -> bool
+    where
+        I: IntoIterator<Item = &'a String>,
+    {
+        match self.required_nodes.as_ref() {
+            Some(required) => {
+                node_aliases
+                    .into_iter()
+                    .filter(|alias| required.node_aliases.contains(alias))
+                    .count()
+                    >= required.min_replies
+            }
+            None => true,
+        }
+    }
+
+    /// The default for accepting a reply with a valid state proof (enabled)
+    pub fn default_accept_state_proof() -> bool {
+        true
+    }
+}
+
+impl Default for ConsensusPolicy {
+    fn default() -> Self {
+        Self {
+            quorum: ReplyQuorum::default(),
+            accept_state_proof: Self::default_accept_state_proof(),
+            freshness: FreshnessPolicy::default(),
+            initial_nodes: None,
+            required_nodes: None,
+            require_inclusion_proof: false,
+        }
+    }
+}
+
+impl Validatable for ConsensusPolicy {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.quorum == ReplyQuorum::Count(0) {
+            return Err(invalid!("Reply quorum must be greater than 0"));
+        }
+        if self.freshness == FreshnessPolicy::Within(0) {
+            return Err(invalid!("Freshness threshold must be greater than 0"));
+        }
+        if self.initial_nodes == Some(0) {
+            return Err(invalid!("`initial_nodes` must be greater than 0"));
+        }
+        if let Some(required) = self.required_nodes.as_ref() {
+            if required.min_replies == 0 || required.min_replies > required.node_aliases.len() {
+                return Err(invalid!(
+                    "Required node replies must be between 1 and the number of required nodes"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_replies() {
+        assert_eq!(ReplyQuorum::Weak.required_replies(2), 3);
+        assert_eq!(ReplyQuorum::Strong.required_replies(2), 5);
+        assert_eq!(ReplyQuorum::Count(4).required_replies(2), 4);
+    }
+
+    #[test]
+    fn test_freshness_threshold() {
+        assert_eq!(ConsensusPolicy::freshness_threshold(None, 300), Some(300));
+        let policy = ConsensusPolicy::new().freshness(FreshnessPolicy::Skip);
+        assert_eq!(
+            ConsensusPolicy::freshness_threshold(Some(&policy), 300),
+            None
+        );
+        let policy = ConsensusPolicy::new().freshness(FreshnessPolicy::Within(10));
+        assert_eq!(
+            ConsensusPolicy::freshness_threshold(Some(&policy), 300),
+            Some(10)
+        );
+        let policy: ConsensusPolicy = serde_json::from_str(r#"{"freshness": "skip"}"#).unwrap();
+        assert_eq!(policy.freshness, FreshnessPolicy::Skip);
+    }
+
+    #[test]
+    fn test_has_required_nodes() {
+        let policy = ConsensusPolicy::new()
+            .required_nodes(vec!["Node1".to_string(), "Node2".to_string()], 2);
+        let nodes = vec!["Node1".to_string(), "Node3".to_string()];
+        assert!(!policy.has_required_nodes(&nodes));
+        let nodes = vec!["Node2".to_string(), "Node1".to_string()];
+        assert!(policy.has_required_nodes(&nodes));
+        assert!(ConsensusPolicy::new().has_required_nodes(&vec![]));
+    }
+
+    #[test]
+    fn test_deserialize_policy() {
+        let policy: ConsensusPolicy =
+            serde_json::from_str(r#"{"quorum": {"count": 3}, "accept_state_proof": false}"#)
+                .unwrap();
+        assert_eq!(
+            policy,
+            ConsensusPolicy::new()
+                .quorum(ReplyQuorum::Count(3))
+                .accept_state_proof(false)
+        );
+        let policy: ConsensusPolicy = serde_json::from_str(r#"{"quorum": "strong"}"#).unwrap();
+        assert_eq!(policy.quorum, ReplyQuorum::Strong);
+        assert!(policy.accept_state_proof);
+    }
+
+    #[test]
+    fn test_validate_policy() {
+        assert!(ConsensusPolicy::new().validate().is_ok());
+        assert!(ConsensusPolicy::new()
+            .quorum(ReplyQuorum::Count(0))
+            .validate()
+            .is_err());
+        assert!(ConsensusPolicy::new()
+            .required_nodes(vec!["Node1".to_string()], 2)
+            .validate()
+            .is_err());
+    }
+}
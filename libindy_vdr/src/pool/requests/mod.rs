@@ -3,11 +3,17 @@ use std::iter::FromIterator;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use super::networker;
+use super::selector::NodeLatency;
 use super::types::{self, Message, PoolSetup, TimingResult};
+use crate::common::error::VdrError;
 
 mod base;
 pub use base::{PoolRequest, PoolRequestImpl};
 
+/// Rules for accepting consensus replies
+mod consensus_policy;
+pub use consensus_policy::{ConsensusPolicy, FreshnessPolicy, NodeQuorum, ReplyQuorum};
+
 /// Assembled ledger transaction request
 mod prepared_request;
 pub use prepared_request::{PreparedRequest, RequestMethod};
@@ -50,6 +56,9 @@ pub enum RequestExtEvent {
     Timeout(
         String, // node_alias
     ),
+    Error(
+        VdrError, // reason the request could not be processed
+    ),
 }
 
 /// Basic state enum for ledger transaction requests
@@ -98,6 +107,24 @@ impl RequestTiming {
         });
     }
 
+    pub fn latencies(&self, now: SystemTime) -> Vec<(String, NodeLatency)> {
+        self.replies
+            .iter()
+            .map(|(alias, (send_time, duration))| {
+                let latency = if *duration >= 0.0 {
+                    NodeLatency::Replied(*duration)
+                } else {
+                    NodeLatency::Unanswered(
+                        now.duration_since(*send_time)
+                            .unwrap_or(Duration::new(0, 0))
+                            .as_secs_f32(),
+                    )
+                };
+                (alias.clone(), latency)
+            })
+            .collect()
+    }
+
     pub fn result(&self) -> Option<TimingResult> {
         Some(HashMap::from_iter(
             self.replies.iter().map(|(k, (_, v))| (k.clone(), *v)),
@@ -1,6 +1,7 @@
 use serde_json::{self, Value as SJsonValue};
 
 use super::new_request_id;
+use super::ConsensusPolicy;
 use crate::common::error::prelude::*;
 use crate::ledger::constants::READ_REQUESTS;
 use crate::ledger::TxnAuthrAgrmtAcceptanceData;
@@ -59,6 +60,8 @@ pub struct PreparedRequest {
     pub req_json: SJsonValue,
     /// Determine the request handler to use
     pub method: RequestMethod,
+    /// Override the rules for accepting consensus replies
+    pub consensus_policy: Option<ConsensusPolicy>,
 }
 
 impl PreparedRequest {
@@ -77,6 +80,7 @@ impl PreparedRequest {
             req_id,
             req_json,
             method,
+            consensus_policy: None,
         }
     }
 
@@ -142,6 +146,15 @@ impl PreparedRequest {
         Ok(())
     }
 
+    /// Assign the consensus policy used to accept the replies to the prepared request
+    pub fn set_consensus_policy(&mut self, policy: Option<ConsensusPolicy>) -> VdrResult<()> {
+        if let Some(policy) = policy.as_ref() {
+            policy.validate()?;
+        }
+        self.consensus_policy = policy;
+        Ok(())
+    }
+
     /// Construct a prepared request from user-provided JSON
     pub fn from_request_json<T: AsRef<[u8]>>(message: T) -> VdrResult<PreparedRequest> {
         let req_json: SJsonValue =
@@ -1,54 +1,85 @@
-use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::rc::Rc;
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
 
 use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
 use futures_executor::block_on;
 use futures_util::stream::{FuturesUnordered, StreamExt};
 use futures_util::{select, FutureExt};
 
-use super::helpers::{perform_ledger_request, perform_refresh};
+use super::handlers::min_consensus;
+use super::helpers::{perform_ledger_request_diagnostic, perform_refresh, RefreshResult};
 use super::networker::{Networker, NetworkerFactory};
 use super::requests::PreparedRequest;
-use super::types::{RequestResult, TimingResult, Verifiers};
-use super::{LocalPool, Pool};
+use super::selector::NodeSelector;
+use super::store::PoolTransactionsStore;
+use super::types::{PoolChanges, RequestResult, TimingResult, Verifiers};
+use super::{LocalPool, Pool, PoolTransactions};
 use crate::common::error::prelude::*;
 use crate::common::merkle_tree::MerkleTree;
 use crate::config::PoolConfig;
+use crate::state_proof::StateProofParsers;
 use crate::utils::base58;
 
 /// The `PoolRunner` instance creates a separate thread for handling pool events,
 /// allowing the use of callbacks instead of async functions for interacting
 /// with the pool as well as simplifying validator pool refreshes.
+///
+/// When enabled in the pool configuration, the validator pool is also refreshed
+/// automatically on an interval or after a request times out.
 pub struct PoolRunner {
     sender: Option<UnboundedSender<PoolEvent>>,
     worker: Option<thread::JoinHandle<()>>,
+    timer_stop: Option<Sender<()>>,
+    timer: Option<thread::JoinHandle<()>>,
 }
 
 impl PoolRunner {
     /// Create a new `PoolRunner` instance and run the associated worker thread.
+    ///
+    /// When a transactions store and the genesis transactions are provided, the pool
+    /// transactions are stored after each refresh which applies new transactions.
     pub fn new<F>(
         config: PoolConfig,
         merkle_tree: MerkleTree,
         networker_factory: F,
-        node_weights: Option<HashMap<String, f32>>,
+        node_selector: Option<Arc<dyn NodeSelector>>,
+        store: Option<(PoolTransactionsStore, PoolTransactions)>,
+        state_proof_parsers: Option<StateProofParsers>,
     ) -> Self
     where
         F: NetworkerFactory<Output = Rc<dyn Networker>> + Send + 'static,
     {
         let (sender, receiver) = unbounded();
+        let (timer_stop, timer) = if config.refresh_interval > 0 {
+            let interval = Duration::from_secs(config.refresh_interval);
+            let (stop, timer) = _spawn_refresh_timer(interval, sender.clone());
+            (Some(stop), Some(timer))
+        } else {
+            (None, None)
+        };
         let worker = thread::spawn(move || {
             // FIXME handle error on build
-            let pool =
-                LocalPool::build(config.clone(), merkle_tree, networker_factory, node_weights)
-                    .unwrap();
-            let mut thread = PoolThread::new(pool, receiver);
+            let pool = LocalPool::build(
+                config.clone(),
+                merkle_tree,
+                networker_factory,
+                node_selector,
+                state_proof_parsers,
+            )
+            .unwrap();
+            let mut thread = PoolThread::new(pool, receiver, store);
             thread.run();
             debug!("Pool thread ended")
         });
         Self {
             sender: Some(sender),
             worker: Some(worker),
+            timer_stop,
+            timer,
         }
     }
 
@@ -67,19 +98,25 @@ impl PoolRunner {
         self.send_event(PoolEvent::GetVerifiers(callback))
     }
 
-    /// Fetch the latest pool transactions and switch to the new validator
-    /// pool if necessary.
+    /// Fetch the latest pool transactions and apply them to the validator
+    /// pool if necessary. Refreshes are performed one at a time, so the
+    /// callback may be queued until a pending refresh has completed.
     pub fn refresh(&self, callback: Callback<RefreshResponse>) -> VdrResult<()> {
         self.send_event(PoolEvent::Refresh(callback))
     }
 
+    /// Register a listener to be notified of the verifier changes applied by each refresh.
+    pub fn add_refresh_listener(&self, listener: RefreshListener) -> VdrResult<()> {
+        self.send_event(PoolEvent::AddRefreshListener(listener))
+    }
+
     /// Submit a request to the validator pool.
     pub fn send_request(
         &self,
         request: PreparedRequest,
         callback: Callback<SendReqResponse>,
     ) -> VdrResult<()> {
-        self.send_event(PoolEvent::SendRequest(request, callback))
+        self.send_event(PoolEvent::SendRequest(Box::new(request), callback))
     }
 
     /// Send an event to the worker thread.
@@ -96,6 +133,7 @@ impl PoolRunner {
 
     /// Shut down the associated worker thread and release any pool resources.
     pub fn close(&mut self) -> bool {
+        drop(self.timer_stop.take());
         if self.sender.is_none() {
             return false;
         } else {
@@ -108,15 +146,25 @@ impl PoolRunner {
 impl Drop for PoolRunner {
     fn drop(&mut self) {
         self.close();
+        if let Some(timer) = self.timer.take() {
+            if timer.join().is_err() {
+                warn!("Pool refresh timer panicked");
+            }
+        }
         if let Some(worker) = self.worker.take() {
             debug!("Drop pool runner thread");
-            worker.join().unwrap()
+            if worker.join().is_err() {
+                warn!("Pool runner thread panicked");
+            }
         }
     }
 }
 
 type Callback<R> = Box<dyn (FnOnce(R) -> ()) + Send>;
 
+/// A listener notified of the verifier changes applied by a pool refresh
+pub type RefreshListener = Box<dyn Fn(&PoolChanges) + Send>;
+
 type GetStatusResponse = VdrResult<PoolRunnerStatus>;
 
 type GetTxnsResponse = VdrResult<Vec<String>>;
@@ -132,7 +180,19 @@ enum PoolEvent {
     GetTransactions(Callback<GetTxnsResponse>),
     GetVerifiers(Callback<GetVerifiersResponse>),
     Refresh(Callback<RefreshResponse>),
-    SendRequest(PreparedRequest, Callback<SendReqResponse>),
+    AutoRefresh,
+    AddRefreshListener(RefreshListener),
+    SendRequest(Box<PreparedRequest>, Callback<SendReqResponse>),
+}
+
+/// The outcome of a task performed by the pool thread
+enum TaskResult {
+    /// The task did not affect the pool
+    Done,
+    /// A refresh completed, returning any new transactions to be applied by the pool thread
+    Refreshed(VdrResult<RefreshResult>, Option<Callback<RefreshResponse>>),
+    /// A request saw timeouts from more nodes than the pool can tolerate as faulty
+    Timeout,
 }
 
 /// The current status of a validator pool.
@@ -157,11 +217,26 @@ impl PoolRunnerStatus {
 struct PoolThread {
     pool: LocalPool,
     receiver: UnboundedReceiver<PoolEvent>,
+    listeners: Vec<RefreshListener>,
+    refreshing: bool,
+    refresh_queue: VecDeque<Callback<RefreshResponse>>,
+    store: Option<(PoolTransactionsStore, PoolTransactions)>,
 }
 
 impl PoolThread {
-    fn new(pool: LocalPool, receiver: UnboundedReceiver<PoolEvent>) -> Self {
-        Self { pool, receiver }
+    fn new(
+        pool: LocalPool,
+        receiver: UnboundedReceiver<PoolEvent>,
+        store: Option<(PoolTransactionsStore, PoolTransactions)>,
+    ) -> Self {
+        Self {
+            pool,
+            receiver,
+            store,
+            listeners: vec![],
+            refreshing: false,
+            refresh_queue: VecDeque::new(),
+        }
     }
 
     fn run(&mut self) {
@@ -193,11 +268,29 @@ impl PoolThread {
                             callback(vers);
                         }
                         Some(PoolEvent::Refresh(callback)) => {
-                            let fut = _perform_refresh(&self.pool, callback);
-                            futures.push(fut.boxed_local());
+                            if self.refreshing {
+                                trace!("Queueing pool refresh");
+                                self.refresh_queue.push_back(callback);
+                            } else {
+                                self.refreshing = true;
+                                let fut = _perform_refresh(self.pool.clone(), Some(callback));
+                                futures.push(fut.boxed_local());
+                            }
+                        }
+                        Some(PoolEvent::AutoRefresh) => {
+                            if !self.refreshing {
+                                debug!("Starting automatic pool refresh");
+                                self.refreshing = true;
+                                let fut = _perform_refresh(self.pool.clone(), None);
+                                futures.push(fut.boxed_local());
+                            }
+                        }
+                        Some(PoolEvent::AddRefreshListener(listener)) => {
+                            self.listeners.push(listener);
                         }
                         Some(PoolEvent::SendRequest(request, callback)) => {
-                            let fut = _perform_ledger_request(&self.pool, request, callback);
+                            let fut =
+                                _perform_ledger_request(self.pool.clone(), *request, callback);
                             futures.push(fut.boxed_local());
                         }
                         None => { trace!("Pool runner sender dropped") }
@@ -205,7 +298,47 @@ impl PoolThread {
                 }
                 req_evt = futures.next() => {
                     match req_evt {
-                        Some(()) => trace!("Callback response dispatched"),
+                        Some(TaskResult::Refreshed(result, callback)) => {
+                            let (pool, store) = (&mut self.pool, self.store.as_ref());
+                            let mut changes = None;
+                            let response = result.and_then(|(new_txns, timing)| {
+                                let (old_txns, upd_changes) =
+                                    _apply_refresh(pool, new_txns.as_ref(), store)?;
+                                changes = upd_changes;
+                                Ok((old_txns, new_txns, timing))
+                            });
+                            match callback {
+                                Some(callback) => callback(response),
+                                None => {
+                                    if let Err(err) = response {
+                                        warn!("Error performing automatic pool refresh: {}", err);
+                                    }
+                                }
+                            }
+                            trace!("Refreshed pool, callback response dispatched");
+                            if let Some(changes) = changes.filter(|changes| !changes.is_empty()) {
+                                for listener in self.listeners.iter() {
+                                    listener(&changes);
+                                }
+                            }
+                            if let Some(callback) = self.refresh_queue.pop_front() {
+                                let fut = _perform_refresh(self.pool.clone(), Some(callback));
+                                futures.push(fut.boxed_local());
+                            } else {
+                                self.refreshing = false;
+                            }
+                        }
+                        Some(TaskResult::Timeout) => {
+                            trace!("Callback response dispatched");
+                            let refresh = self.pool.get_config().refresh_on_timeout;
+                            if refresh && !self.refreshing {
+                                debug!("Starting pool refresh after request timeouts");
+                                self.refreshing = true;
+                                let fut = _perform_refresh(self.pool.clone(), None);
+                                futures.push(fut.boxed_local());
+                            }
+                        }
+                        Some(TaskResult::Done) => trace!("Callback response dispatched"),
                         None => trace!("No pending callbacks")
                     }
                 }
@@ -215,24 +348,208 @@ impl PoolThread {
     }
 }
 
-async fn _perform_refresh(pool: &LocalPool, callback: Callback<RefreshResponse>) {
-    let result = {
-        match perform_refresh(pool).await {
-            Ok((new_txns, timing)) => match pool.get_json_transactions() {
-                Ok(old_txns) => Ok((old_txns, new_txns, timing)),
-                Err(err) => Err(err),
-            },
-            Err(err) => Err(err),
+/// Send automatic refresh events to the pool thread until stopped
+fn _spawn_refresh_timer(
+    interval: Duration,
+    sender: UnboundedSender<PoolEvent>,
+) -> (Sender<()>, thread::JoinHandle<()>) {
+    let (stop, stop_receiver) = channel::<()>();
+    let timer = thread::spawn(move || {
+        while let Err(RecvTimeoutError::Timeout) = stop_receiver.recv_timeout(interval) {
+            if sender.unbounded_send(PoolEvent::AutoRefresh).is_err() {
+                break;
+            }
+        }
+        debug!("Pool refresh timer ended")
+    });
+    (stop, timer)
+}
+
+/// Fetch any new pool transactions, which are applied to the pool by the pool thread
+async fn _perform_refresh(
+    pool: LocalPool,
+    callback: Option<Callback<RefreshResponse>>,
+) -> TaskResult {
+    let result = perform_refresh(&pool).await;
+    TaskResult::Refreshed(result, callback)
+}
+
+/// Apply the transactions found by a refresh, returning the previous transactions
+/// and the resulting verifier changes
+fn _apply_refresh(
+    pool: &mut LocalPool,
+    new_txns: Option<&Vec<String>>,
+    store: Option<&(PoolTransactionsStore, PoolTransactions)>,
+) -> VdrResult<(Vec<String>, Option<PoolChanges>)> {
+    let old_txns = pool.get_json_transactions()?;
+    let changes = if let Some(new_txns) = new_txns {
+        let prev_verifiers = pool.get_verifier_info().unwrap_or_default();
+        pool.apply_transactions(new_txns)?;
+        if let Some((store, genesis)) = store {
+            let txns = PoolTransactions::from(pool.get_merkle_tree());
+            if let Err(err) = store.store(genesis, &txns) {
+                warn!("Error storing refreshed pool transactions: {}", err);
+            }
         }
+        let verifiers = pool.get_verifier_info().unwrap_or_default();
+        Some(PoolChanges::new(&prev_verifiers, &verifiers))
+    } else {
+        None
     };
-    callback(result);
+    Ok((old_txns, changes))
 }
 
 async fn _perform_ledger_request(
-    pool: &LocalPool,
+    pool: LocalPool,
     request: PreparedRequest,
     callback: Callback<SendReqResponse>,
-) {
-    let result = perform_ledger_request(pool, &request).await;
-    callback(result);
+) -> TaskResult {
+    let f = min_consensus(pool.get_node_aliases().len());
+    let result = perform_ledger_request_diagnostic(&pool, &request).await;
+    let timeout = matches!(
+        &result,
+        Ok((_, _, Some(report))) if report.timeouts.len() > f
+    );
+    callback(result.map(|(result, timing, _)| (result, timing)));
+    if timeout {
+        TaskResult::Timeout
+    } else {
+        TaskResult::Done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::Value as SJsonValue;
+
+    use crate::pool::networker::fixtures::*;
+    use crate::pool::networker::{
+        MakeLocal, SimulatedNetworkerFactory, SimulatedNode, SimulatedReply,
+    };
+
+    #[test]
+    fn test_runner_refreshes_after_timeout() {
+        let (node, _new_txn) = _catchup_node(SimulatedReply::Timeout);
+        let config = PoolConfig {
+            refresh_on_timeout: true,
+            ..PoolConfig::default()
+        };
+        let runner = PoolRunner::new(
+            config,
+            _merkle_tree(&_transactions()),
+            MakeLocal(SimulatedNetworkerFactory::new().default_node(node)),
+            None,
+            None,
+            None,
+        );
+        let (changes_send, changes_recv) = channel();
+        runner
+            .add_refresh_listener(Box::new(move |changes| {
+                changes_send.send(changes.clone()).unwrap();
+            }))
+            .unwrap();
+
+        let request = _nym_request();
+        let (result_send, result_recv) = channel();
+        runner
+            .send_request(
+                request,
+                Box::new(move |result| {
+                    result_send.send(result.unwrap().0).unwrap();
+                }),
+            )
+            .unwrap();
+        match result_recv.recv_timeout(Duration::from_secs(5)).unwrap() {
+            RequestResult::Failed(err) => {
+                assert!(matches!(err.kind(), VdrErrorKind::PoolTimeout))
+            }
+            RequestResult::Reply(reply) => panic!("Unexpected reply: {}", reply),
+        }
+
+        let changes = changes_recv.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(changes.added, vec!["Node5".to_string()]);
+        assert!(changes.removed.is_empty());
+    }
+
+    #[test]
+    fn test_runner_queues_concurrent_refresh() {
+        let (node, new_txn) = _catchup_node(SimulatedReply::Timeout);
+        let runner = PoolRunner::new(
+            PoolConfig::default(),
+            _merkle_tree(&_transactions()),
+            MakeLocal(SimulatedNetworkerFactory::new().default_node(node)),
+            None,
+            None,
+            None,
+        );
+        let (changes_send, changes_recv) = channel();
+        runner
+            .add_refresh_listener(Box::new(move |changes| {
+                changes_send.send(changes.clone()).unwrap();
+            }))
+            .unwrap();
+
+        let (result_send, result_recv) = channel();
+        for _ in 0..2 {
+            let result_send = result_send.clone();
+            runner
+                .refresh(Box::new(move |result| {
+                    result_send.send(result.unwrap().1).unwrap();
+                }))
+                .unwrap();
+        }
+        // the second refresh starts from the transactions applied by the first
+        let first = result_recv
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(
+            serde_json::from_str::<SJsonValue>(&first[0]).unwrap(),
+            new_txn
+        );
+        let second = result_recv.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(second, None);
+
+        let changes = changes_recv.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(changes.added, vec!["Node5".to_string()]);
+        assert!(changes_recv.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_runner_stores_refreshed_transactions() {
+        let (node, _new_txn) = _catchup_node(SimulatedReply::Timeout);
+        let path = std::env::temp_dir().join("indy_vdr_tests").join(format!(
+            "runner_stores_refreshed_transactions_{}",
+            std::process::id()
+        ));
+        let store = PoolTransactionsStore::new(&path);
+        let genesis = PoolTransactions::from_json_transactions(_transactions()).unwrap();
+        let runner = PoolRunner::new(
+            PoolConfig::default(),
+            _merkle_tree(&_transactions()),
+            MakeLocal(SimulatedNetworkerFactory::new().default_node(node)),
+            None,
+            Some((store.clone(), genesis.clone())),
+            None,
+        );
+
+        let (result_send, result_recv) = channel();
+        runner
+            .refresh(Box::new(move |result| {
+                result_send.send(result.unwrap().1).unwrap();
+            }))
+            .unwrap();
+        assert!(result_recv
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+            .is_some());
+        drop(runner);
+
+        let stored = store.load(&genesis).unwrap().unwrap();
+        assert_eq!(stored.len(), 5);
+        let _ = std::fs::remove_dir_all(path);
+    }
 }
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use rand::distributions::{Distribution, WeightedIndex};
+
+use super::types::Verifiers;
+
+/// The smoothing factor applied to new latency observations
+const DEFAULT_SMOOTHING: f32 = 0.3;
+/// The latency assumed for nodes when no observations have been recorded
+const DEFAULT_LATENCY: f32 = 1.0;
+/// The lower bound applied to latency estimates when computing selection weights
+const MIN_LATENCY: f32 = 0.01;
+
+/// The observed response time of a single node for a completed request
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NodeLatency {
+    /// A reply was received after the given number of seconds
+    Replied(f32),
+    /// No reply had been received after the given number of seconds
+    Unanswered(f32),
+}
+
+/// A strategy for ordering the verifier nodes contacted by a request
+pub trait NodeSelector: Send + Sync {
+    /// Get the verifier node aliases in the order they should be contacted
+    fn select_nodes(&self, verifiers: &Verifiers) -> Vec<String>;
+
+    /// Record the response time of a node for a completed request
+    fn record_latency(&self, _node_alias: &str, _latency: NodeLatency) {}
+}
+
+/// The default `NodeSelector`, performing a random selection using static node weights
+#[derive(Clone, Debug, Default)]
+pub struct WeightedNodeSelector {
+    weights: Option<HashMap<String, f32>>,
+}
+
+impl WeightedNodeSelector {
+    /// Create a new `WeightedNodeSelector` with optional static weights for each node alias
+    pub fn new(weights: Option<HashMap<String, f32>>) -> Self {
+        Self { weights }
+    }
+}
+
+impl NodeSelector for WeightedNodeSelector {
+    fn select_nodes(&self, verifiers: &Verifiers) -> Vec<String> {
+        weighted_order(
+            verifiers
+                .keys()
+                .map(|alias| (_static_weight(self.weights.as_ref(), alias), alias.as_str()))
+                .collect(),
+        )
+    }
+}
+
+/// A `NodeSelector` which learns the response time of each node and favours faster nodes
+#[derive(Debug)]
+pub struct AdaptiveNodeSelector {
+    weights: Option<HashMap<String, f32>>,
+    smoothing: f32,
+    estimates: Mutex<HashMap<String, f32>>,
+}
+
+impl AdaptiveNodeSelector {
+    /// Create a new `AdaptiveNodeSelector` with optional static weights for each node alias
+    pub fn new(weights: Option<HashMap<String, f32>>) -> Self {
+        Self {
+            weights,
+            smoothing: DEFAULT_SMOOTHING,
+            estimates: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set the smoothing factor (between 0 and 1) applied to new observations
+    pub fn smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Get the current response time estimate for each node, in seconds
+    pub fn latency_estimates(&self) -> HashMap<String, f32> {
+        self.estimates
+            .lock()
+            .map(|estimates| estimates.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for AdaptiveNodeSelector {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl NodeSelector for AdaptiveNodeSelector {
+    fn select_nodes(&self, verifiers: &Verifiers) -> Vec<String> {
+        let estimates = self.latency_estimates();
+        // optimistic estimate for unseen nodes, so that they are tried early
+        let unknown = estimates
+            .values()
+            .cloned()
+            .fold(None, |min: Option<f32>, val| {
+                Some(min.map_or(val, |min| min.min(val)))
+            })
+            .unwrap_or(DEFAULT_LATENCY);
+        weighted_order(
+            verifiers
+                .keys()
+                .map(|alias| {
+                    let latency = estimates
+                        .get(alias)
+                        .cloned()
+                        .unwrap_or(unknown)
+                        .max(MIN_LATENCY);
+                    let weight = _static_weight(self.weights.as_ref(), alias);
+                    (weight / (latency * latency), alias.as_str())
+                })
+                .collect(),
+        )
+    }
+
+    fn record_latency(&self, node_alias: &str, latency: NodeLatency) {
+        let mut estimates = match self.estimates.lock() {
+            Ok(estimates) => estimates,
+            Err(_) => return,
+        };
+        let (observed, lower_bound) = match latency {
+            NodeLatency::Replied(secs) => (secs, false),
+            NodeLatency::Unanswered(secs) => (secs, true),
+        };
+        match estimates.get_mut(node_alias) {
+            Some(estimate) => {
+                // a missing reply only indicates that the node is at least this slow
+                if !lower_bound || observed > *estimate {
+                    *estimate += (observed - *estimate) * self.smoothing;
+                }
+            }
+            None => {
+                estimates.insert(node_alias.to_owned(), observed);
+            }
+        }
+    }
+}
+
+/// Perform a weighted random ordering of node aliases
+pub(crate) fn weighted_order(mut weighted: Vec<(f32, &str)>) -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    let mut result = Vec::with_capacity(weighted.len());
+    while !weighted.is_empty() {
+        let index = match WeightedIndex::new(weighted.iter().map(|item| item.0)) {
+            Ok(dist) => dist.sample(&mut rng),
+            // only zero weights remain
+            Err(_) => break,
+        };
+        result.push(weighted.remove(index).1.to_string());
+    }
+    result.extend(weighted.into_iter().map(|item| item.1.to_string()));
+    result
+}
+
+fn _static_weight(weights: Option<&HashMap<String, f32>>, alias: &str) -> f32 {
+    weights.and_then(|w| w.get(alias)).cloned().unwrap_or(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::VerifierInfo;
+
+    fn _verifiers() -> Verifiers {
+        ["Node1", "Node2", "Node3", "Node4"]
+            .iter()
+            .map(|alias| {
+                (
+                    alias.to_string(),
+                    VerifierInfo {
+                        client_addr: String::new(),
+                        node_addr: String::new(),
+                        public_key: String::new(),
+                        enc_key: vec![],
+                        bls_key: None,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_weighted_selects_all_nodes() {
+        let mut weights = HashMap::new();
+        weights.insert("Node1".to_string(), 0.0);
+        let selector = WeightedNodeSelector::new(Some(weights));
+        let order = selector.select_nodes(&_verifiers());
+        assert_eq!(order.len(), 4);
+        assert_eq!(order[3], "Node1");
+    }
+
+    #[test]
+    fn test_adaptive_prefers_fast_nodes() {
+        let selector = AdaptiveNodeSelector::default();
+        selector.record_latency("Node1", NodeLatency::Unanswered(20.0));
+        for alias in &["Node2", "Node3", "Node4"] {
+            selector.record_latency(alias, NodeLatency::Replied(0.02));
+        }
+        for _ in 0..20 {
+            let order = selector.select_nodes(&_verifiers());
+            assert_eq!(order.len(), 4);
+            assert_eq!(order[3], "Node1");
+        }
+    }
+
+    #[test]
+    fn test_adaptive_latency_estimates() {
+        let selector = AdaptiveNodeSelector::default().smoothing(0.5);
+        selector.record_latency("Node1", NodeLatency::Replied(1.0));
+        selector.record_latency("Node1", NodeLatency::Replied(2.0));
+        assert_eq!(selector.latency_estimates()["Node1"], 1.5);
+
+        // an early unanswered request does not lower the estimate
+        selector.record_latency("Node1", NodeLatency::Unanswered(0.1));
+        assert_eq!(selector.latency_estimates()["Node1"], 1.5);
+        selector.record_latency("Node1", NodeLatency::Unanswered(3.5));
+        assert_eq!(selector.latency_estimates()["Node1"], 2.5);
+    }
+}
@@ -0,0 +1,211 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::genesis::PoolTransactions;
+use crate::common::error::prelude::*;
+use crate::common::merkle_tree::MerkleTree;
+use crate::utils::base58;
+
+/// A counter distinguishing the temporary files written by concurrent stores
+static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A directory of cached pool ledgers, each indexed by the merkle root of its genesis transactions
+///
+/// Cached transactions are only returned when they begin with the genesis transactions.
+/// The transactions appended to the genesis are not authenticated when they are loaded:
+/// they are only confirmed once a pool refresh verifies the ledger status with the
+/// validator nodes, which fails for a cache that has been tampered with.
+#[derive(Clone, Debug)]
+pub struct PoolTransactionsStore {
+    path: PathBuf,
+}
+
+impl PoolTransactionsStore {
+    /// Create a new `PoolTransactionsStore` using the given cache directory.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Get the cache directory.
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    /// Load the cached pool transactions for a set of genesis transactions.
+    ///
+    /// Returns `None` when no cached transactions exist, or when they do not begin with
+    /// the genesis transactions.
+    pub fn load(&self, genesis: &PoolTransactions) -> VdrResult<Option<PoolTransactions>> {
+        let genesis_tree = genesis.merkle_tree()?;
+        let file_path = self.file_path(&genesis_tree);
+        let txns = match fs::read_to_string(&file_path) {
+            Ok(txns) => txns,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(err_msg(
+                    VdrErrorKind::FileSystem(err),
+                    format!("Error reading cached pool transactions: {:?}", file_path),
+                ))
+            }
+        };
+        let txns = match PoolTransactions::from_json(&txns) {
+            Ok(txns) => txns,
+            Err(err) => {
+                warn!(
+                    "Ignoring invalid cached pool transactions: {:?}, {}",
+                    file_path, err
+                );
+                return Ok(None);
+            }
+        };
+        if let Err(err) = check_extends(&genesis_tree, &txns) {
+            warn!(
+                "Ignoring inconsistent cached pool transactions: {:?}, {}",
+                file_path, err
+            );
+            return Ok(None);
+        }
+        debug!(
+            "Loaded {} cached pool transactions from {:?}",
+            txns.len(),
+            file_path
+        );
+        Ok(Some(txns))
+    }
+
+    /// Load the cached pool transactions if present and valid, otherwise the genesis transactions.
+    pub fn load_or_genesis(&self, genesis: PoolTransactions) -> VdrResult<PoolTransactions> {
+        Ok(self.load(&genesis)?.unwrap_or(genesis))
+    }
+
+    /// Store a refreshed set of pool transactions, indexed by the genesis transactions.
+    ///
+    /// The refreshed transactions must begin with the genesis transactions.
+    pub fn store(&self, genesis: &PoolTransactions, txns: &PoolTransactions) -> VdrResult<()> {
+        let genesis_tree = genesis.merkle_tree()?;
+        check_extends(&genesis_tree, txns)?;
+        fs::create_dir_all(&self.path).map_err(|err| {
+            err_msg(
+                VdrErrorKind::FileSystem(err),
+                format!("Error creating pool transactions cache: {:?}", self.path),
+            )
+        })?;
+        let file_path = self.file_path(&genesis_tree);
+        let mut json = txns.encode_json()?.join("\n");
+        json.push('\n');
+        // write to a temporary file first so that concurrent readers never see a partial ledger
+        let tmp_path = file_path.with_extension(format!(
+            "tmp{}_{}",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&tmp_path, json)
+            .and_then(|_| fs::rename(&tmp_path, &file_path))
+            .map_err(|err| {
+                let _ = fs::remove_file(&tmp_path);
+                err_msg(
+                    VdrErrorKind::FileSystem(err),
+                    format!("Error writing cached pool transactions: {:?}", file_path),
+                )
+            })?;
+        debug!("Stored {} pool transactions to {:?}", txns.len(), file_path);
+        Ok(())
+    }
+
+    /// Remove the cached pool transactions for a set of genesis transactions, if any.
+    pub fn remove(&self, genesis: &PoolTransactions) -> VdrResult<()> {
+        let file_path = self.file_path(&genesis.merkle_tree()?);
+        match fs::remove_file(&file_path) {
+            Ok(_) => Ok(()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err_msg(
+                VdrErrorKind::FileSystem(err),
+                format!("Error removing cached pool transactions: {:?}", file_path),
+            )),
+        }
+    }
+
+    fn file_path(&self, genesis_tree: &MerkleTree) -> PathBuf {
+        self.path
+            .join(format!("{}.json", base58::encode(genesis_tree.root_hash())))
+    }
+}
+
+/// Check that a set of pool transactions begins with the genesis transactions
+fn check_extends(genesis_tree: &MerkleTree, txns: &PoolTransactions) -> VdrResult<()> {
+    let size = genesis_tree.count();
+    let prefix = txns.iter().take(size).cloned().collect::<Vec<Vec<u8>>>();
+    if prefix.len() < size {
+        return Err(input_err(
+            "Pool transactions are shorter than the genesis transactions",
+        ));
+    }
+    if MerkleTree::from_vec(prefix)?.root_hash() != genesis_tree.root_hash() {
+        return Err(input_err(
+            "Pool transactions do not begin with the genesis transactions",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indy_test_utils::genesis::GenesisTransactions;
+
+    fn _store(name: &str) -> PoolTransactionsStore {
+        let path = std::env::temp_dir().join("indy_vdr_tests").join(format!(
+            "{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&path);
+        PoolTransactionsStore::new(path)
+    }
+
+    fn _transactions(count: usize) -> PoolTransactions {
+        PoolTransactions::from_json_transactions(
+            &GenesisTransactions::new(Some(count)).transactions,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_store_and_load_extended_transactions() {
+        let store = _store("store_and_load_extended_transactions");
+        let genesis = _transactions(3);
+        assert_eq!(store.load(&genesis).unwrap(), None);
+
+        let refreshed = _transactions(4);
+        store.store(&genesis, &refreshed).unwrap();
+        assert_eq!(store.load(&genesis).unwrap(), Some(refreshed.clone()));
+        assert_eq!(store.load_or_genesis(genesis.clone()).unwrap(), refreshed);
+
+        // cached transactions are indexed by the genesis root
+        assert_eq!(store.load(&_transactions(2)).unwrap(), None);
+
+        store.remove(&genesis).unwrap();
+        assert_eq!(store.load_or_genesis(genesis.clone()).unwrap(), genesis);
+        let _ = fs::remove_dir_all(store.path());
+    }
+
+    #[test]
+    fn test_store_rejects_inconsistent_transactions() {
+        let store = _store("store_rejects_inconsistent_transactions");
+        let genesis = _transactions(2);
+        let mut txns = _transactions(4).iter().cloned().collect::<Vec<_>>();
+        txns.swap(0, 1);
+        let reordered = PoolTransactions::from_transactions(&txns);
+        store.store(&genesis, &reordered).unwrap_err();
+        store.store(&_transactions(4), &genesis).unwrap_err();
+
+        // a modified cache file is not trusted
+        let file_path = store.file_path(&genesis.merkle_tree().unwrap());
+        fs::create_dir_all(store.path()).unwrap();
+        fs::write(&file_path, reordered.encode_json().unwrap().join("\n")).unwrap();
+        assert_eq!(store.load(&genesis).unwrap(), None);
+        let _ = fs::remove_dir_all(store.path());
+    }
+}
@@ -1,6 +1,7 @@
 use std::cmp::Eq;
 use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
+use std::sync::Arc;
 
 use serde::{ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{self, Value as SJsonValue};
@@ -10,8 +11,11 @@ use crate::common::error::prelude::*;
 use crate::common::merkle_tree::MerkleTree;
 use crate::config::constants::DEFAULT_PROTOCOL_VERSION;
 use crate::config::PoolConfig;
+use crate::state_proof::{SignatureCache, StateProofParsers};
 use crate::utils::{base58, keys::build_full_verkey};
 
+use super::selector::{NodeSelector, WeightedNodeSelector};
+
 /// The Indy Node communication protocol version
 #[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
 pub enum ProtocolVersion {
@@ -612,26 +616,88 @@ impl Serialize for VerifierInfo {
 /// A standard collection of verifier information, indexed by node alias
 pub type Verifiers = HashMap<String, VerifierInfo>;
 
+/// The changes to the verifier pool resulting from a refresh
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct PoolChanges {
+    /// The aliases of newly added verifier nodes
+    pub added: Vec<String>,
+    /// The aliases of removed verifier nodes
+    pub removed: Vec<String>,
+    /// The aliases of verifier nodes with a changed client or node address
+    pub changed_addresses: Vec<String>,
+    /// The aliases of verifier nodes with a rotated BLS key
+    pub rotated_bls_keys: Vec<String>,
+}
+
+impl PoolChanges {
+    /// Compare two sets of verifiers, collecting the differences
+    pub fn new(prev: &Verifiers, next: &Verifiers) -> Self {
+        let mut changes = Self::default();
+        for (alias, info) in next {
+            match prev.get(alias) {
+                Some(prev_info) => {
+                    if prev_info.client_addr != info.client_addr
+                        || prev_info.node_addr != info.node_addr
+                    {
+                        changes.changed_addresses.push(alias.clone());
+                    }
+                    if prev_info.bls_key.as_ref().map(VerifierKey::as_bytes)
+                        != info.bls_key.as_ref().map(VerifierKey::as_bytes)
+                    {
+                        changes.rotated_bls_keys.push(alias.clone());
+                    }
+                }
+                None => changes.added.push(alias.clone()),
+            }
+        }
+        changes.removed.extend(
+            prev.keys()
+                .filter(|alias| !next.contains_key(*alias))
+                .cloned(),
+        );
+        changes.added.sort();
+        changes.removed.sort();
+        changes.changed_addresses.sort();
+        changes.rotated_bls_keys.sort();
+        changes
+    }
+
+    /// Check whether no changes were found
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed_addresses.is_empty()
+            && self.rotated_bls_keys.is_empty()
+    }
+}
+
 /// Setup parameters for `PoolImpl`, shared with cloned instances and derived pool requests
 pub struct PoolSetup {
     pub config: PoolConfig,
     pub merkle_tree: MerkleTree,
-    pub node_weights: Option<HashMap<String, f32>>,
+    pub node_selector: Arc<dyn NodeSelector>,
     pub verifiers: Verifiers,
+    pub signature_cache: SignatureCache,
+    pub state_proof_parsers: StateProofParsers,
 }
 
 impl PoolSetup {
     pub fn new(
         config: PoolConfig,
         merkle_tree: MerkleTree,
-        node_weights: Option<HashMap<String, f32>>,
+        node_selector: Option<Arc<dyn NodeSelector>>,
         verifiers: Verifiers,
+        state_proof_parsers: Option<StateProofParsers>,
     ) -> Self {
+        let signature_cache = SignatureCache::new(config.signature_cache_size);
         Self {
             config,
             merkle_tree,
-            node_weights,
+            node_selector: node_selector
+                .unwrap_or_else(|| Arc::new(WeightedNodeSelector::default())),
             verifiers,
+            signature_cache,
+            state_proof_parsers: state_proof_parsers.unwrap_or_default(),
         }
     }
 }
@@ -660,6 +726,37 @@ impl<T> RequestResult<T> {
 /// Type representing timing information collected for ledger transaction request
 pub type TimingResult = HashMap<String, f32>;
 
+/// A summary of the replies received from each node for a consensus request
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ConsensusReport {
+    /// The distinct reply results (excluding state proofs), most common first
+    pub replies: Vec<ConsensusReplyGroup>,
+    /// The distinct reasons given by nodes refusing or rejecting the request
+    pub rejections: Vec<ConsensusRejectGroup>,
+    /// The nodes which sent a malformed or unexpected message
+    pub failed: Vec<String>,
+    /// The nodes which did not reply before the request timeout
+    pub timeouts: Vec<String>,
+}
+
+/// A reply result shared by one or more nodes
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ConsensusReplyGroup {
+    /// The reply result, excluding any state proof
+    pub result: SJsonValue,
+    /// The aliases of the nodes which sent this result
+    pub nodes: Vec<String>,
+}
+
+/// A rejection reason shared by one or more nodes
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ConsensusRejectGroup {
+    /// The reason given for the rejection
+    pub reason: String,
+    /// The aliases of the nodes which gave this reason
+    pub nodes: Vec<String>,
+}
+
 /// The result of a request to a single validator node
 #[derive(Debug)]
 pub enum SingleReply<T> {
@@ -0,0 +1,219 @@
+use std::fmt::{self, Display, Formatter};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::common::error::prelude::*;
+use crate::utils::did::DidValue;
+
+static DID_INDY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new("^did:indy:([a-z0-9_-]+(?::[a-z0-9_-]+)*):([1-9A-HJ-NP-Za-km-z]{21,22})$").unwrap()
+});
+
+static DID_SOV: Lazy<Regex> =
+    Lazy::new(|| Regex::new("^did:sov:(?:([a-z0-9_-]+):)?([1-9A-HJ-NP-Za-km-z]{21,22})$").unwrap());
+
+static DID_UNQUALIFIED: Lazy<Regex> =
+    Lazy::new(|| Regex::new("^[1-9A-HJ-NP-Za-km-z]{21,22}$").unwrap());
+
+/// The DID method of a DID resolved against an Indy ledger
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DidMethod {
+    /// `did:indy:<namespace>:<id>`
+    Indy,
+    /// `did:sov:<id>`, optionally with a sub-namespace, or an unqualified DID
+    Sov,
+}
+
+/// A `did:indy` or `did:sov` DID registered on an Indy ledger
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LedgerDid {
+    /// The DID method
+    pub method: DidMethod,
+    /// The ledger namespace, if any
+    pub namespace: Option<String>,
+    /// The unqualified identifier used in ledger requests
+    pub id: String,
+}
+
+impl LedgerDid {
+    /// Parse a `did:indy`, `did:sov` or unqualified DID
+    pub fn parse(did: &str) -> VdrResult<Self> {
+        if let Some(caps) = DID_INDY.captures(did) {
+            Ok(Self {
+                method: DidMethod::Indy,
+                namespace: Some(caps[1].to_string()),
+                id: caps[2].to_string(),
+            })
+        } else if let Some(caps) = DID_SOV.captures(did) {
+            Ok(Self {
+                method: DidMethod::Sov,
+                namespace: caps.get(1).map(|ns| ns.as_str().to_string()),
+                id: caps[2].to_string(),
+            })
+        } else if DID_UNQUALIFIED.is_match(did) {
+            Ok(Self {
+                method: DidMethod::Sov,
+                namespace: None,
+                id: did.to_string(),
+            })
+        } else {
+            Err(input_err(format!("Invalid DID: {}", did)))
+        }
+    }
+
+    /// Get the DID value to use in ledger requests
+    pub fn ledger_did(&self) -> DidValue {
+        DidValue(self.id.clone())
+    }
+}
+
+impl Display for LedgerDid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let method = match self.method {
+            DidMethod::Indy => "indy",
+            DidMethod::Sov => "sov",
+        };
+        match self.namespace.as_ref() {
+            Some(namespace) => write!(f, "did:{}:{}:{}", method, namespace, self.id),
+            None => write!(f, "did:{}:{}", method, self.id),
+        }
+    }
+}
+
+/// A DID URL referencing a DID document or an object on the ledger
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DidUrl {
+    /// The DID of the URL
+    pub did: LedgerDid,
+    /// The path following the DID, if any
+    pub path: Option<String>,
+    /// The query parameters in order of appearance
+    pub query: Vec<(String, String)>,
+    /// The fragment, if any
+    pub fragment: Option<String>,
+}
+
+impl DidUrl {
+    /// Parse a DID URL
+    pub fn parse(url: &str) -> VdrResult<Self> {
+        let (url, fragment) = _split_at(url, '#');
+        let (url, query) = _split_at(url, '?');
+        let (did, path) = match url.find('/') {
+            Some(pos) => (&url[..pos], Some(&url[pos..])),
+            None => (url, None),
+        };
+        let query = query
+            .map(|query| {
+                query
+                    .split('&')
+                    .filter(|param| !param.is_empty())
+                    .map(|param| {
+                        let (name, value) = _split_at(param, '=');
+                        Ok((
+                            percent_decode(name)?,
+                            percent_decode(value.unwrap_or_default())?,
+                        ))
+                    })
+                    .collect::<VdrResult<Vec<_>>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+        Ok(Self {
+            did: LedgerDid::parse(did)?,
+            path: path.filter(|path| *path != "/").map(str::to_string),
+            query,
+            fragment: fragment.map(str::to_string),
+        })
+    }
+
+    /// Get the value of a query parameter
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn _split_at(val: &str, delim: char) -> (&str, Option<&str>) {
+    match val.find(delim) {
+        Some(pos) => (&val[..pos], Some(&val[pos + 1..])),
+        None => (val, None),
+    }
+}
+
+/// Decode a percent-encoded DID URL component
+pub(crate) fn percent_decode(val: &str) -> VdrResult<String> {
+    let bytes = val.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%' {
+            let byte = val
+                .get(idx + 1..idx + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| input_err(format!("Invalid percent encoding: {}", val)))?;
+            decoded.push(byte);
+            idx += 3;
+        } else {
+            decoded.push(bytes[idx]);
+            idx += 1;
+        }
+    }
+    String::from_utf8(decoded).with_input_err("Invalid percent encoding")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "V4SGRU86Z58d6TV7PBUe6f";
+
+    #[test]
+    fn test_parse_did() {
+        let did = LedgerDid::parse(&format!("did:indy:sovrin:staging:{}", ID)).unwrap();
+        assert_eq!(did.method, DidMethod::Indy);
+        assert_eq!(did.namespace.as_deref(), Some("sovrin:staging"));
+        assert_eq!(did.id, ID);
+        assert_eq!(did.to_string(), format!("did:indy:sovrin:staging:{}", ID));
+
+        let did = LedgerDid::parse(&format!("did:sov:{}", ID)).unwrap();
+        assert_eq!(did.method, DidMethod::Sov);
+        assert_eq!(did.namespace, None);
+        assert_eq!(did.ledger_did(), DidValue(ID.to_string()));
+
+        let did = LedgerDid::parse(ID).unwrap();
+        assert_eq!(did.to_string(), format!("did:sov:{}", ID));
+    }
+
+    #[test]
+    fn test_parse_invalid_did() {
+        assert!(LedgerDid::parse(&format!("did:indy:{}", ID)).is_err());
+        assert!(LedgerDid::parse(&format!("did:key:{}", ID)).is_err());
+        assert!(LedgerDid::parse("did:sov:0OIl").is_err());
+    }
+
+    #[test]
+    fn test_parse_did_url() {
+        let url = DidUrl::parse(&format!(
+            "did:indy:sovrin:{}/anoncreds/v0/SCHEMA/Degree%20Schema/1.0?versionTime=10#key",
+            ID
+        ))
+        .unwrap();
+        assert_eq!(url.did.id, ID);
+        assert_eq!(
+            url.path.as_deref(),
+            Some("/anoncreds/v0/SCHEMA/Degree%20Schema/1.0")
+        );
+        assert_eq!(url.query_param("versionTime"), Some("10"));
+        assert_eq!(url.fragment.as_deref(), Some("key"));
+
+        let url = DidUrl::parse(&format!("did:sov:{}", ID)).unwrap();
+        assert_eq!(url.path, None);
+        assert!(url.query.is_empty());
+
+        assert_eq!(percent_decode("a%2Fb").unwrap(), "a/b");
+        assert!(percent_decode("a%2").is_err());
+    }
+}
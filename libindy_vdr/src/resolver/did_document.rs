@@ -0,0 +1,305 @@
+use serde_json::Value as SJsonValue;
+
+use crate::common::error::prelude::*;
+use crate::utils::base58;
+use crate::utils::keys::{build_full_verkey, EncodedVerKey, KeyEncoding, KeyType};
+use crate::utils::ConversionError;
+
+pub const DID_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+pub const ED25519_CONTEXT: &str = "https://w3id.org/security/suites/ed25519-2018/v1";
+pub const X25519_CONTEXT: &str = "https://w3id.org/security/suites/x25519-2019/v1";
+
+const ED25519_TYPE: &str = "Ed25519VerificationKey2018";
+const X25519_TYPE: &str = "X25519KeyAgreementKey2019";
+const DEFAULT_SERVICE_TYPES: [&str; 2] = ["endpoint", "did-communication"];
+
+/// A legacy `endpoint` attribute registered for a DID
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Endpoint {
+    /// The service endpoint URL
+    pub endpoint: String,
+    /// The routing keys for DIDComm messaging
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routing_keys: Vec<String>,
+    /// The service types offered at the endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub types: Option<Vec<String>>,
+}
+
+/// The ledger data describing a DID, used to construct its DID document
+#[derive(Clone, Debug, PartialEq)]
+pub struct DidDocument {
+    /// The fully-qualified DID
+    pub id: String,
+    /// The full verification key of the DID, if it has not been deactivated
+    pub verkey: Option<String>,
+    /// The legacy endpoint attribute of the DID
+    pub endpoint: Option<Endpoint>,
+    /// Additional DID document content registered with a `did:indy` NYM
+    pub diddoc_content: Option<SJsonValue>,
+}
+
+impl DidDocument {
+    /// Create a new `DidDocument` from the NYM data, expanding an abbreviated verkey
+    pub fn new(
+        id: String,
+        dest: &str,
+        verkey: Option<&str>,
+        endpoint: Option<Endpoint>,
+        diddoc_content: Option<SJsonValue>,
+    ) -> VdrResult<Self> {
+        let verkey = match verkey {
+            Some(verkey) => Some(
+                full_verkey(dest, verkey)
+                    .map_input_err(|| format!("Invalid verkey for DID: {}", id))?,
+            ),
+            None => None,
+        };
+        if let Some(content) = diddoc_content.as_ref() {
+            if !content.is_object() {
+                return Err(input_err("DID document content must be a JSON object"));
+            }
+            if content.get("id").is_some() {
+                return Err(input_err("DID document content must not define an id"));
+            }
+        }
+        Ok(Self {
+            id,
+            verkey,
+            endpoint,
+            diddoc_content,
+        })
+    }
+
+    /// Get the identifier of the default Ed25519 verification method
+    pub fn verkey_id(&self) -> String {
+        format!("{}#verkey", self.id)
+    }
+
+    /// Get the identifier of the derived X25519 key agreement method
+    pub fn key_agreement_id(&self) -> String {
+        format!("{}#key-agreement-1", self.id)
+    }
+
+    /// Convert to a W3C DID document
+    ///
+    /// The X25519 key agreement method and the services derived from the legacy endpoint
+    /// are only added when no DID document content is registered for the DID.
+    pub fn to_value(&self) -> VdrResult<SJsonValue> {
+        let mut context = vec![DID_CONTEXT.to_string()];
+        let mut doc = json!({ "id": self.id });
+
+        if let Some(verkey) = self.verkey.as_ref() {
+            let verkey_id = self.verkey_id();
+            context.push(ED25519_CONTEXT.to_string());
+            let mut methods = vec![json!({
+                "id": verkey_id,
+                "type": ED25519_TYPE,
+                "controller": self.id,
+                "publicKeyBase58": verkey,
+            })];
+            doc["authentication"] = json!([verkey_id]);
+            doc["assertionMethod"] = json!([verkey_id]);
+
+            if self.diddoc_content.is_none() {
+                let key_agreement_id = self.key_agreement_id();
+                context.push(X25519_CONTEXT.to_string());
+                methods.push(json!({
+                    "id": key_agreement_id,
+                    "type": X25519_TYPE,
+                    "controller": self.id,
+                    "publicKeyBase58": key_agreement_key(verkey)?,
+                }));
+                doc["keyAgreement"] = json!([key_agreement_id]);
+            }
+            doc["verificationMethod"] = SJsonValue::from(methods);
+        }
+
+        match (self.diddoc_content.as_ref(), self.endpoint.as_ref()) {
+            (Some(content), _) => {
+                if let Some(content) = content.as_object() {
+                    for (key, value) in content {
+                        if key == "@context" {
+                            _merge_context(&mut context, value);
+                        } else {
+                            _merge_property(&mut doc, key, value);
+                        }
+                    }
+                }
+            }
+            (None, Some(endpoint)) => {
+                doc["service"] = self.endpoint_services(endpoint);
+            }
+            (None, None) => (),
+        }
+
+        doc["@context"] = SJsonValue::from(context);
+        Ok(doc)
+    }
+
+    fn endpoint_services(&self, endpoint: &Endpoint) -> SJsonValue {
+        let types = match endpoint.types.as_ref() {
+            Some(types) => types.iter().map(String::as_str).collect::<Vec<_>>(),
+            None => DEFAULT_SERVICE_TYPES.to_vec(),
+        };
+        let services = types
+            .into_iter()
+            .map(|service_type| match service_type {
+                "did-communication" => json!({
+                    "id": format!("{}#did-communication", self.id),
+                    "type": service_type,
+                    "serviceEndpoint": endpoint.endpoint,
+                    "recipientKeys": [self.verkey_id()],
+                    "routingKeys": endpoint.routing_keys,
+                    "priority": 0,
+                    "accept": ["didcomm/aip2;env=rfc19"],
+                }),
+                "DIDComm" => json!({
+                    "id": format!("{}#didcomm-1", self.id),
+                    "type": service_type,
+                    "serviceEndpoint": endpoint.endpoint,
+                    "routingKeys": endpoint.routing_keys,
+                    "accept": ["didcomm/v2"],
+                }),
+                _ => json!({
+                    "id": format!("{}#{}", self.id, service_type),
+                    "type": service_type,
+                    "serviceEndpoint": endpoint.endpoint,
+                }),
+            })
+            .collect::<Vec<_>>();
+        SJsonValue::from(services)
+    }
+}
+
+/// Expand a possibly abbreviated verkey to the base58-encoded Ed25519 key
+pub fn full_verkey(dest: &str, verkey: &str) -> Result<String, ConversionError> {
+    let key = build_full_verkey(dest, verkey)?.decode()?;
+    Ok(base58::encode(key.key_bytes()))
+}
+
+/// Derive the base58-encoded X25519 key agreement key for an Ed25519 verkey
+pub fn key_agreement_key(verkey: &str) -> VdrResult<String> {
+    let key = EncodedVerKey::new(verkey, Some(KeyType::ED25519), Some(KeyEncoding::BASE58))
+        .decode()
+        .map_input_err(|| "Invalid Ed25519 verkey")?
+        .key_exchange()
+        .map_input_err(|| "Error deriving X25519 key from verkey")?;
+    Ok(base58::encode(key.key_bytes()))
+}
+
+fn _merge_context(context: &mut Vec<String>, value: &SJsonValue) {
+    let values: Vec<&SJsonValue> = match value {
+        SJsonValue::Array(values) => values.iter().collect(),
+        value => vec![value],
+    };
+    for value in values.into_iter().filter_map(SJsonValue::as_str) {
+        if !context.iter().any(|ctx| ctx == value) {
+            context.push(value.to_string());
+        }
+    }
+}
+
+fn _merge_property(doc: &mut SJsonValue, key: &str, value: &SJsonValue) {
+    match (doc.get_mut(key), value) {
+        (Some(SJsonValue::Array(existing)), SJsonValue::Array(values)) => {
+            existing.extend(values.iter().cloned());
+        }
+        _ => {
+            doc[key] = value.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DID: &str = "did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f";
+    const DEST: &str = "V4SGRU86Z58d6TV7PBUe6f";
+    const VERKEY: &str = "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL";
+
+    #[test]
+    fn test_did_document_for_endpoint() {
+        let endpoint = Endpoint {
+            endpoint: "https://agent.example.com".to_string(),
+            routing_keys: vec![],
+            types: None,
+        };
+        let doc = DidDocument::new(DID.to_string(), DEST, Some(VERKEY), Some(endpoint), None)
+            .unwrap()
+            .to_value()
+            .unwrap();
+
+        assert_eq!(
+            doc["@context"],
+            json!([DID_CONTEXT, ED25519_CONTEXT, X25519_CONTEXT])
+        );
+        assert_eq!(doc["id"], DID);
+        assert_eq!(doc["verificationMethod"][0]["publicKeyBase58"], VERKEY);
+        assert_eq!(doc["verificationMethod"][1]["type"], X25519_TYPE);
+        assert_eq!(doc["authentication"], json!([format!("{}#verkey", DID)]));
+        assert_eq!(
+            doc["keyAgreement"],
+            json!([format!("{}#key-agreement-1", DID)])
+        );
+        assert_eq!(doc["service"][0]["type"], "endpoint");
+        assert_eq!(doc["service"][1]["type"], "did-communication");
+        assert_eq!(
+            doc["service"][1]["serviceEndpoint"],
+            "https://agent.example.com"
+        );
+    }
+
+    #[test]
+    fn test_did_document_for_diddoc_content() {
+        let content = json!({
+            "@context": ["https://identity.foundation/didcomm-messaging/service-endpoint/v1"],
+            "authentication": ["did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f#key-2"],
+            "service": [{
+                "id": "did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f#didcomm",
+                "type": "DIDCommMessaging",
+                "serviceEndpoint": "https://agent.example.com",
+            }],
+        });
+        let doc = DidDocument::new(
+            DID.to_string(),
+            DEST,
+            Some(VERKEY),
+            None,
+            Some(content.clone()),
+        )
+        .unwrap()
+        .to_value()
+        .unwrap();
+
+        assert_eq!(doc["@context"].as_array().unwrap().len(), 3);
+        assert_eq!(doc["authentication"].as_array().unwrap().len(), 2);
+        assert_eq!(doc["verificationMethod"].as_array().unwrap().len(), 1);
+        assert_eq!(doc["service"], content["service"]);
+        assert!(doc.get("keyAgreement").is_none());
+
+        let content = json!({"id": DID});
+        assert!(
+            DidDocument::new(DID.to_string(), DEST, Some(VERKEY), None, Some(content)).is_err()
+        );
+    }
+
+    #[test]
+    fn test_did_document_for_abbreviated_verkey() {
+        let doc = DidDocument::new(
+            DID.to_string(),
+            DEST,
+            Some("~HYwqs2vrTc8Tn4uBV7NBTe"),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            doc.verkey,
+            Some(full_verkey(DEST, "~HYwqs2vrTc8Tn4uBV7NBTe").unwrap())
+        );
+        assert!(doc.verkey.unwrap().len() > 40);
+    }
+}
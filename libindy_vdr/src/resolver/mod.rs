@@ -0,0 +1,13 @@
+mod did;
+mod did_document;
+mod object;
+mod pool;
+mod types;
+
+pub use self::did::{DidMethod, DidUrl, LedgerDid};
+pub use self::did_document::{key_agreement_key, DidDocument, Endpoint};
+pub use self::object::LedgerObject;
+pub use self::pool::PoolResolver;
+pub use self::types::{
+    ContentMetadata, DereferencingResult, DocumentMetadata, ResolutionMetadata, ResolutionResult,
+};
@@ -0,0 +1,146 @@
+use crate::common::error::prelude::*;
+use crate::ledger::identifiers::{CredentialDefinitionId, RevocationRegistryId, SchemaId};
+
+use super::did::{percent_decode, LedgerDid};
+
+const ANONCREDS_PATH: &str = "/anoncreds/v0/";
+const ATTRIB_PATH: &str = "/ATTRIB/";
+const ATTRIB: &str = "ATTRIB";
+
+/// A ledger object referenced by the path of a `did:indy` DID URL
+///
+/// Anoncreds objects use the paths of the `did:indy` method specification, for example
+/// `/anoncreds/v0/SCHEMA/<name>/<version>` or `/anoncreds/v0/CLAIM_DEF/<schema seq no>/<tag>`.
+/// Attributes are referenced as `/ATTRIB/<name>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LedgerObject {
+    /// A schema, referenced by name and version
+    Schema(SchemaId),
+    /// A credential definition, referenced by schema sequence number and tag
+    ClaimDef(CredentialDefinitionId),
+    /// A revocation registry definition, referenced by schema sequence number,
+    /// credential definition tag and registry tag
+    RevRegDef(RevocationRegistryId),
+    /// The state of a revocation registry at a point in time
+    RevRegEntry(RevocationRegistryId),
+    /// The changes to a revocation registry over an interval
+    RevRegDelta(RevocationRegistryId),
+    /// A raw attribute of the DID
+    Attrib(String),
+}
+
+impl LedgerObject {
+    /// Parse the path of a DID URL
+    pub fn parse(did: &LedgerDid, path: &str) -> VdrResult<Self> {
+        let (object_type, parts) = if let Some(rest) = path.strip_prefix(ANONCREDS_PATH) {
+            let mut parts = rest.split('/');
+            let object_type = parts.next().unwrap_or_default();
+            let parts = parts.map(percent_decode).collect::<VdrResult<Vec<_>>>()?;
+            (object_type, parts)
+        } else if let Some(name) = path.strip_prefix(ATTRIB_PATH) {
+            (ATTRIB, vec![percent_decode(name)?])
+        } else {
+            return Err(input_err(format!("Unsupported DID URL path: {}", path)));
+        };
+        let parts = parts.iter().map(String::as_str).collect::<Vec<_>>();
+        let did = &did.id;
+
+        match (object_type, parts.as_slice()) {
+            ("SCHEMA", [name, version]) => Ok(Self::Schema(SchemaId(format!(
+                "{}:2:{}:{}",
+                did, name, version
+            )))),
+            ("CLAIM_DEF", [schema_seq_no, tag]) => {
+                Ok(Self::ClaimDef(_cred_def_id(did, schema_seq_no, tag)?))
+            }
+            ("REV_REG_DEF", [schema_seq_no, cred_def_tag, tag]) => Ok(Self::RevRegDef(
+                _rev_reg_id(did, schema_seq_no, cred_def_tag, tag)?,
+            )),
+            ("REV_REG_ENTRY", [schema_seq_no, cred_def_tag, tag]) => Ok(Self::RevRegEntry(
+                _rev_reg_id(did, schema_seq_no, cred_def_tag, tag)?,
+            )),
+            ("REV_REG_DELTA", [schema_seq_no, cred_def_tag, tag]) => Ok(Self::RevRegDelta(
+                _rev_reg_id(did, schema_seq_no, cred_def_tag, tag)?,
+            )),
+            (ATTRIB, [name]) if !name.is_empty() => Ok(Self::Attrib(name.to_string())),
+            _ => Err(input_err(format!("Invalid ledger object path: {}", path))),
+        }
+    }
+
+    /// Get the name of the ledger object type, as used in DID URL paths
+    pub fn object_type(&self) -> &'static str {
+        match self {
+            Self::Schema(_) => "SCHEMA",
+            Self::ClaimDef(_) => "CLAIM_DEF",
+            Self::RevRegDef(_) => "REV_REG_DEF",
+            Self::RevRegEntry(_) => "REV_REG_ENTRY",
+            Self::RevRegDelta(_) => "REV_REG_DELTA",
+            Self::Attrib(_) => ATTRIB,
+        }
+    }
+}
+
+fn _cred_def_id(did: &str, schema_seq_no: &str, tag: &str) -> VdrResult<CredentialDefinitionId> {
+    schema_seq_no
+        .parse::<u32>()
+        .with_input_err("Invalid schema sequence number in DID URL")?;
+    Ok(CredentialDefinitionId(format!(
+        "{}:3:CL:{}:{}",
+        did, schema_seq_no, tag
+    )))
+}
+
+fn _rev_reg_id(
+    did: &str,
+    schema_seq_no: &str,
+    cred_def_tag: &str,
+    tag: &str,
+) -> VdrResult<RevocationRegistryId> {
+    let cred_def_id = _cred_def_id(did, schema_seq_no, cred_def_tag)?;
+    Ok(RevocationRegistryId(format!(
+        "{}:4:{}:CL_ACCUM:{}",
+        did, cred_def_id.0, tag
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn _did() -> LedgerDid {
+        LedgerDid::parse("did:indy:sovrin:F72i3Y3Q4i466efjYJYCHM").unwrap()
+    }
+
+    #[test]
+    fn test_parse_object_paths() {
+        assert_eq!(
+            LedgerObject::parse(&_did(), "/anoncreds/v0/SCHEMA/npdb/4.3.4").unwrap(),
+            LedgerObject::Schema(SchemaId("F72i3Y3Q4i466efjYJYCHM:2:npdb:4.3.4".to_string()))
+        );
+        assert_eq!(
+            LedgerObject::parse(&_did(), "/anoncreds/v0/CLAIM_DEF/160971/1.0.0").unwrap(),
+            LedgerObject::ClaimDef(CredentialDefinitionId(
+                "F72i3Y3Q4i466efjYJYCHM:3:CL:160971:1.0.0".to_string()
+            ))
+        );
+        assert_eq!(
+            LedgerObject::parse(&_did(), "/anoncreds/v0/REV_REG_DELTA/160971/1.0.0/tag").unwrap(),
+            LedgerObject::RevRegDelta(RevocationRegistryId(
+                "F72i3Y3Q4i466efjYJYCHM:4:F72i3Y3Q4i466efjYJYCHM:3:CL:160971:1.0.0:CL_ACCUM:tag"
+                    .to_string()
+            ))
+        );
+        assert_eq!(
+            LedgerObject::parse(&_did(), "/ATTRIB/endpoint").unwrap(),
+            LedgerObject::Attrib("endpoint".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_object_paths() {
+        assert!(LedgerObject::parse(&_did(), "/anoncreds/v0/SCHEMA/npdb").is_err());
+        assert!(LedgerObject::parse(&_did(), "/anoncreds/v0/CLAIM_DEF/abc/tag").is_err());
+        assert!(LedgerObject::parse(&_did(), "/anoncreds/v1/SCHEMA/npdb/4.3.4").is_err());
+        assert!(LedgerObject::parse(&_did(), "/ATTRIB/").is_err());
+    }
+}
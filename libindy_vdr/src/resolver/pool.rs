@@ -0,0 +1,284 @@
+use serde::Serialize;
+use serde_json::Value as SJsonValue;
+
+use crate::common::error::prelude::*;
+use crate::ledger::responses::attrib::AttribValue;
+use crate::ledger::responses::{
+    parse_get_attrib_response, parse_get_cred_def_response, parse_get_nym_response,
+    parse_get_revoc_reg_def_response, parse_get_revoc_reg_delta_response,
+    parse_get_revoc_reg_response, parse_get_schema_response, LedgerResponse, ResponseMetadata,
+};
+use crate::pool::helpers::perform_ledger_request;
+use crate::pool::{Pool, PreparedRequest, RequestResult};
+use crate::state_proof::{verify_reply, VerifyReplyOptions};
+
+use super::did::{DidMethod, DidUrl, LedgerDid};
+use super::did_document::{DidDocument, Endpoint};
+use super::object::LedgerObject;
+use super::types::{
+    parse_timestamp, ContentMetadata, DereferencingResult, DocumentMetadata, ResolutionResult,
+};
+
+/// A resolver for `did:indy` and `did:sov` DIDs registered on a single ledger
+#[derive(Clone, Debug)]
+pub struct PoolResolver<T: Pool> {
+    pool: T,
+    namespace: Option<String>,
+}
+
+impl<T: Pool> PoolResolver<T> {
+    /// Create a new `PoolResolver` reading from a verifier pool
+    pub fn new(pool: T) -> Self {
+        Self {
+            pool,
+            namespace: None,
+        }
+    }
+
+    /// Set the `did:indy` namespace of the ledger, rejecting DIDs in other namespaces
+    pub fn namespace(mut self, namespace: Option<String>) -> Self {
+        self.namespace = namespace;
+        self
+    }
+
+    /// Get a reference to the verifier pool
+    pub fn pool(&self) -> &T {
+        &self.pool
+    }
+
+    /// Resolve a `did:indy`, `did:sov` or unqualified DID to a DID document
+    pub async fn resolve(&self, did: &str) -> VdrResult<ResolutionResult> {
+        let did = LedgerDid::parse(did)?;
+        self.resolve_did(&did, None, None).await
+    }
+
+    /// Resolve a DID, optionally selecting a previous version by sequence number or time
+    pub async fn resolve_did(
+        &self,
+        did: &LedgerDid,
+        seq_no: Option<i32>,
+        timestamp: Option<u64>,
+    ) -> VdrResult<ResolutionResult> {
+        let (result, _metadata) = self.resolve_nym(did, seq_no, timestamp).await?;
+        Ok(result)
+    }
+
+    /// Dereference a DID URL to a DID document, a part of it or a ledger object
+    ///
+    /// A previous version of a DID document may be selected using the `versionId` or
+    /// `versionTime` query parameters. `REV_REG_ENTRY` objects are read as of `versionTime`
+    /// and `REV_REG_DELTA` objects over the interval from `from` until `to` or `versionTime`,
+    /// each defaulting to the current time.
+    pub async fn dereference(&self, did_url: &str) -> VdrResult<DereferencingResult> {
+        let url = DidUrl::parse(did_url)?;
+        let version_time = _query_timestamp(&url, "versionTime")?;
+        let object = match url.path.as_ref() {
+            Some(path) => LedgerObject::parse(&url.did, path)?,
+            None => {
+                let seq_no = url
+                    .query_param("versionId")
+                    .map(|seq_no| seq_no.parse::<i32>())
+                    .transpose()
+                    .with_input_err("Invalid versionId in DID URL")?;
+                let (result, metadata) = self.resolve_nym(&url.did, seq_no, version_time).await?;
+                let content = match (result.did_document, url.fragment.as_ref()) {
+                    (Some(doc), Some(fragment)) => _find_fragment(&doc, &url.did, fragment),
+                    (doc, _) => doc,
+                };
+                return Ok(match content {
+                    Some(content) => DereferencingResult::new(content, metadata),
+                    None => DereferencingResult::not_found(metadata),
+                });
+            }
+        };
+
+        self.check_namespace(&url.did)?;
+        let builder = self.pool.get_request_builder();
+        let request = match &object {
+            LedgerObject::Schema(id) => builder.build_get_schema_request(None, id)?,
+            LedgerObject::ClaimDef(id) => builder.build_get_cred_def_request(None, id)?,
+            LedgerObject::RevRegDef(id) => builder.build_get_revoc_reg_def_request(None, id)?,
+            LedgerObject::RevRegEntry(id) => {
+                let timestamp = version_time.unwrap_or_else(_now);
+                builder.build_get_revoc_reg_request(None, id, timestamp as i64)?
+            }
+            LedgerObject::RevRegDelta(id) => {
+                let from = _query_timestamp(&url, "from")?;
+                let to = _query_timestamp(&url, "to")?
+                    .or(version_time)
+                    .unwrap_or_else(_now);
+                builder.build_get_revoc_reg_delta_request(
+                    None,
+                    id,
+                    from.map(|from| from as i64),
+                    to as i64,
+                )?
+            }
+            LedgerObject::Attrib(name) => builder.build_get_attrib_request(
+                None,
+                &url.did.ledger_did(),
+                Some(name.clone()),
+                None,
+                None,
+            )?,
+        };
+        let raw_reply = _submit_request(&self.pool, &request).await?;
+        let (data, response_metadata) = match object {
+            LedgerObject::Schema(_) => _content(parse_get_schema_response(&raw_reply)?)?,
+            LedgerObject::ClaimDef(_) => _content(parse_get_cred_def_response(&raw_reply)?)?,
+            LedgerObject::RevRegDef(_) => _content(parse_get_revoc_reg_def_response(&raw_reply)?)?,
+            LedgerObject::RevRegEntry(_) => _content(parse_get_revoc_reg_response(&raw_reply)?)?,
+            LedgerObject::RevRegDelta(_) => {
+                _content(parse_get_revoc_reg_delta_response(&raw_reply)?)?
+            }
+            LedgerObject::Attrib(_) => {
+                let response = parse_get_attrib_response(&raw_reply)?;
+                let data = response.data.map(|attrib| match attrib.value {
+                    AttribValue::Raw(value) => value,
+                    AttribValue::Hash(value) | AttribValue::Enc(value) => SJsonValue::String(value),
+                });
+                (data, response.metadata)
+            }
+        };
+        let metadata = ContentMetadata {
+            object_type: object.object_type().to_string(),
+            seq_no: response_metadata.seq_no,
+            txn_time: response_metadata.txn_time,
+            state_proof: self.verify_state_proof(&request, &raw_reply),
+        };
+        Ok(match data {
+            Some(data) => DereferencingResult::new(data, metadata),
+            None => DereferencingResult::not_found(metadata),
+        })
+    }
+
+    fn check_namespace(&self, did: &LedgerDid) -> VdrResult<()> {
+        if did.method == DidMethod::Indy
+            && self.namespace.is_some()
+            && did.namespace != self.namespace
+        {
+            return Err(input_err(format!(
+                "DID namespace is not served by this ledger: {}",
+                did
+            )));
+        }
+        Ok(())
+    }
+
+    async fn resolve_nym(
+        &self,
+        did: &LedgerDid,
+        seq_no: Option<i32>,
+        timestamp: Option<u64>,
+    ) -> VdrResult<(ResolutionResult, ContentMetadata)> {
+        self.check_namespace(did)?;
+
+        let builder = self.pool.get_request_builder();
+        let request = builder.build_get_nym_request(None, &did.ledger_did(), seq_no, timestamp)?;
+        let raw_reply = _submit_request(&self.pool, &request).await?;
+        let response = parse_get_nym_response(&raw_reply)?;
+        let metadata = ContentMetadata {
+            object_type: "NYM".to_string(),
+            seq_no: response.metadata.seq_no,
+            txn_time: response.metadata.txn_time,
+            state_proof: self.verify_state_proof(&request, &raw_reply),
+        };
+        let nym = match response.data {
+            Some(nym) => nym,
+            None => return Ok((ResolutionResult::not_found(), metadata)),
+        };
+
+        let verkey = nym.verkey.as_deref();
+        let diddoc_content = nym.diddoc_content;
+        let endpoint = if verkey.is_some() && diddoc_content.is_none() {
+            self.fetch_endpoint(did).await?
+        } else {
+            None
+        };
+
+        let doc = DidDocument::new(did.to_string(), &did.id, verkey, endpoint, diddoc_content)?;
+        let doc_metadata =
+            DocumentMetadata::new(metadata.seq_no, metadata.txn_time, verkey.is_none());
+        Ok((
+            ResolutionResult::new(doc.to_value()?, doc_metadata),
+            metadata,
+        ))
+    }
+
+    /// Fetch the legacy `endpoint` attribute of a DID
+    async fn fetch_endpoint(&self, did: &LedgerDid) -> VdrResult<Option<Endpoint>> {
+        let request = self.pool.get_request_builder().build_get_attrib_request(
+            None,
+            &did.ledger_did(),
+            Some("endpoint".to_string()),
+            None,
+            None,
+        )?;
+        let raw_reply = _submit_request(&self.pool, &request).await?;
+        // malformed endpoint attributes are ignored rather than failing the resolution
+        let attrib = parse_get_attrib_response(&raw_reply)
+            .ok()
+            .and_then(|response| response.data);
+        Ok(match attrib.map(|attrib| attrib.value) {
+            Some(AttribValue::Raw(value)) => serde_json::from_value(value["endpoint"].clone()).ok(),
+            _ => None,
+        })
+    }
+
+    /// Check whether a reply carries a valid state proof signed by the verifier pool
+    fn verify_state_proof(&self, request: &PreparedRequest, raw_reply: &str) -> bool {
+        let options = VerifyReplyOptions {
+            freshness_threshold: self.pool.get_config().freshness_threshold,
+            check_time: None,
+        };
+        self.pool
+            .get_verifier_info()
+            .and_then(|verifiers| verify_reply(request, raw_reply, &verifiers, &options))
+            .map(|verification| verification.is_valid())
+            .unwrap_or(false)
+    }
+}
+
+async fn _submit_request<T: Pool>(pool: &T, request: &PreparedRequest) -> VdrResult<String> {
+    let (result, _timing) = perform_ledger_request(pool, request).await?;
+    match result {
+        RequestResult::Reply(reply) => Ok(reply),
+        RequestResult::Failed(err) => Err(err),
+    }
+}
+
+/// Convert a parsed ledger object to the content of a dereferencing result
+fn _content<T: Serialize>(
+    response: LedgerResponse<T>,
+) -> VdrResult<(Option<SJsonValue>, ResponseMetadata)> {
+    let data = response
+        .data
+        .map(serde_json::to_value)
+        .transpose()
+        .with_input_err("Error serializing ledger object")?;
+    Ok((data, response.metadata))
+}
+
+fn _query_timestamp(url: &DidUrl, name: &str) -> VdrResult<Option<u64>> {
+    url.query_param(name)
+        .map(|value| {
+            parse_timestamp(value)
+                .ok_or_else(|| input_err(format!("Invalid {} in DID URL: {}", name, value)))
+        })
+        .transpose()
+}
+
+fn _find_fragment(doc: &SJsonValue, did: &LedgerDid, fragment: &str) -> Option<SJsonValue> {
+    let full_id = format!("{}#{}", did, fragment);
+    let rel_id = format!("#{}", fragment);
+    ["verificationMethod", "service"]
+        .iter()
+        .filter_map(|section| doc[*section].as_array())
+        .flatten()
+        .find(|item| item["id"] == full_id.as_str() || item["id"] == rel_id.as_str())
+        .cloned()
+}
+
+fn _now() -> u64 {
+    crate::state_proof::get_cur_time()
+}
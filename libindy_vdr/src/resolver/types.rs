@@ -0,0 +1,238 @@
+use serde_json::Value as SJsonValue;
+
+/// The media type of a resolved DID document
+pub const DID_DOCUMENT_CONTENT_TYPE: &str = "application/did+ld+json";
+
+/// The resolution error reported when a DID is not registered on the ledger
+pub const ERROR_NOT_FOUND: &str = "notFound";
+
+/// The result of resolving a DID, following the W3C DID resolution data model
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolutionResult {
+    /// The resolved DID document, if the DID was found
+    pub did_document: Option<SJsonValue>,
+    /// Metadata describing the DID document
+    pub did_document_metadata: DocumentMetadata,
+    /// Metadata describing the resolution process
+    pub did_resolution_metadata: ResolutionMetadata,
+}
+
+impl ResolutionResult {
+    /// Create a result for a resolved DID document
+    pub fn new(did_document: SJsonValue, did_document_metadata: DocumentMetadata) -> Self {
+        Self {
+            did_document: Some(did_document),
+            did_document_metadata,
+            did_resolution_metadata: ResolutionMetadata {
+                content_type: Some(DID_DOCUMENT_CONTENT_TYPE.to_string()),
+                error: None,
+            },
+        }
+    }
+
+    /// Create a result for a DID which is not registered on the ledger
+    pub fn not_found() -> Self {
+        Self {
+            did_document: None,
+            did_document_metadata: DocumentMetadata::default(),
+            did_resolution_metadata: ResolutionMetadata {
+                content_type: None,
+                error: Some(ERROR_NOT_FOUND.to_string()),
+            },
+        }
+    }
+}
+
+/// Metadata describing a resolved DID document
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentMetadata {
+    /// The sequence number of the NYM transaction defining this version of the DID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+    /// The time of the NYM transaction, in XML datetime format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated: Option<String>,
+    /// Whether the DID has been deactivated by removing its verkey
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deactivated: bool,
+}
+
+impl DocumentMetadata {
+    /// Create the document metadata for a NYM transaction
+    pub fn new(seq_no: Option<u64>, txn_time: Option<u64>, deactivated: bool) -> Self {
+        Self {
+            version_id: seq_no.map(|seq_no| seq_no.to_string()),
+            updated: txn_time.map(timestamp_to_datetime),
+            deactivated,
+        }
+    }
+}
+
+/// Metadata describing the resolution of a DID
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolutionMetadata {
+    /// The media type of the DID document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// The resolution error, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The result of dereferencing a DID URL, following the W3C DID resolution data model
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DereferencingResult {
+    /// The referenced content, if it was found
+    pub content_stream: Option<SJsonValue>,
+    /// Metadata describing the ledger object
+    pub content_metadata: ContentMetadata,
+    /// Metadata describing the dereferencing process
+    pub dereferencing_metadata: ResolutionMetadata,
+}
+
+impl DereferencingResult {
+    /// Create a result for a dereferenced ledger object
+    pub fn new(content: SJsonValue, content_metadata: ContentMetadata) -> Self {
+        Self {
+            content_stream: Some(content),
+            content_metadata,
+            dereferencing_metadata: ResolutionMetadata {
+                content_type: Some("application/json".to_string()),
+                error: None,
+            },
+        }
+    }
+
+    /// Create a result for an object which is not found on the ledger
+    pub fn not_found(content_metadata: ContentMetadata) -> Self {
+        Self {
+            content_stream: None,
+            content_metadata,
+            dereferencing_metadata: ResolutionMetadata {
+                content_type: None,
+                error: Some(ERROR_NOT_FOUND.to_string()),
+            },
+        }
+    }
+}
+
+/// Ledger metadata for a dereferenced object
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentMetadata {
+    /// The type of the ledger object, such as `NYM` or `SCHEMA`
+    pub object_type: String,
+    /// The sequence number of the transaction defining the object
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq_no: Option<u64>,
+    /// The time of the transaction defining the object in seconds since the epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub txn_time: Option<u64>,
+    /// Whether the reply was verified using a state proof signed by the verifier pool
+    pub state_proof: bool,
+}
+
+/// Format a time in seconds since the epoch as a UTC XML datetime
+pub(crate) fn timestamp_to_datetime(timestamp: u64) -> String {
+    const SEC_IN_DAY: u64 = 86400;
+    let (days, secs) = (timestamp / SEC_IN_DAY, timestamp % SEC_IN_DAY);
+    // convert days since the epoch to a civil date
+    let z = days as i64 + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// Parse a UTC XML datetime, such as `2020-09-13T12:26:40Z`, or a time in seconds since the epoch
+pub(crate) fn parse_timestamp(value: &str) -> Option<u64> {
+    if let Ok(timestamp) = value.parse::<u64>() {
+        return Some(timestamp);
+    }
+    let value = value.strip_suffix('Z')?;
+    let (date, time) = (value.get(..10)?, value.get(11..)?);
+    if value.get(10..11)? != "T" {
+        return None;
+    }
+    let date = date
+        .split('-')
+        .map(|part| part.parse::<i64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    // fractional seconds are ignored
+    let time = time
+        .split('.')
+        .next()?
+        .split(':')
+        .map(|part| part.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let (year, month, day, hour, min, sec) = match (date.as_slice(), time.as_slice()) {
+        ([year, month, day], [hour, min, sec]) => (*year, *month, *day, *hour, *min, *sec),
+        _ => return None,
+    };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || min > 59 || sec > 60 {
+        return None;
+    }
+    // convert the civil date to days since the epoch
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    if days < 0 {
+        return None;
+    }
+    Some(days as u64 * 86400 + hour * 3600 + min * 60 + sec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamp_to_datetime() {
+        assert_eq!(timestamp_to_datetime(0), "1970-01-01T00:00:00Z");
+        assert_eq!(timestamp_to_datetime(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(timestamp_to_datetime(1_600_000_000), "2020-09-13T12:26:40Z");
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("1600000000"), Some(1_600_000_000));
+        assert_eq!(parse_timestamp("2020-09-13T12:26:40Z"), Some(1_600_000_000));
+        assert_eq!(parse_timestamp("2000-02-29T00:00:00.5Z"), Some(951_782_400));
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_timestamp("2020-13-01T00:00:00Z"), None);
+        assert_eq!(parse_timestamp("2020-09-13 12:26:40"), None);
+    }
+
+    #[test]
+    fn test_serialize_not_found() {
+        assert_eq!(
+            serde_json::to_value(ResolutionResult::not_found()).unwrap(),
+            json!({
+                "didDocument": null,
+                "didDocumentMetadata": {},
+                "didResolutionMetadata": {"error": "notFound"},
+            })
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::{HashSet, VecDeque};
+use std::sync::Mutex;
+
+use ursa::bls::VerKey;
+
+use crate::utils::hash::SHA256;
+
+/// A bounded cache of verified BLS multi-signatures
+///
+/// Replies to reads against the same ledger state commonly carry the same multi-signature.
+/// Entries are keyed on the signed value, the signature and the BLS keys of the participating
+/// nodes, so that the pairing check is only skipped for an identical verification.
+/// The oldest entry is evicted once the capacity is reached.
+#[derive(Debug)]
+pub struct SignatureCache {
+    capacity: usize,
+    entries: Mutex<CacheEntries>,
+}
+
+#[derive(Debug, Default)]
+struct CacheEntries {
+    keys: HashSet<Vec<u8>>,
+    order: VecDeque<Vec<u8>>,
+}
+
+impl SignatureCache {
+    /// Create a new cache holding up to `capacity` verified signatures (0 to disable)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(CacheEntries::default()),
+        }
+    }
+
+    /// Get the maximum number of cached signatures
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get the number of cached signatures
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .map(|entries| entries.keys.len())
+            .unwrap_or(0)
+    }
+
+    /// Check whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all cached signatures
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.keys.clear();
+            entries.order.clear();
+        }
+    }
+
+    /// Check whether a signature has been verified
+    pub(crate) fn contains(&self, key: &[u8]) -> bool {
+        self.capacity > 0
+            && self
+                .entries
+                .lock()
+                .map(|entries| entries.keys.contains(key))
+                .unwrap_or(false)
+    }
+
+    /// Record a verified signature, evicting the oldest entry when full
+    pub(crate) fn insert(&self, key: Vec<u8>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if let Ok(mut entries) = self.entries.lock() {
+            if entries.keys.contains(&key) {
+                return;
+            }
+            while entries.order.len() >= self.capacity {
+                if let Some(evicted) = entries.order.pop_front() {
+                    entries.keys.remove(&evicted);
+                }
+            }
+            entries.keys.insert(key.clone());
+            entries.order.push_back(key);
+        }
+    }
+
+    /// Derive the cache key for a multi-signature verification
+    pub(crate) fn signature_key(
+        signature: &str,
+        participants: &[String],
+        ver_keys: &[&VerKey],
+        value: &[u8],
+    ) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut push = |part: &[u8]| {
+            data.extend_from_slice(&(part.len() as u64).to_be_bytes());
+            data.extend_from_slice(part);
+        };
+        push(signature.as_bytes());
+        push(value);
+        for (alias, ver_key) in participants.iter().zip(ver_keys) {
+            push(alias.as_bytes());
+            push(ver_key.as_bytes());
+        }
+        SHA256::digest(data.as_slice())
+    }
+}
+
+impl Default for SignatureCache {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_cache_evicts_oldest() {
+        let cache = SignatureCache::new(2);
+        cache.insert(vec![1]);
+        cache.insert(vec![2]);
+        cache.insert(vec![1]);
+        assert_eq!(cache.len(), 2);
+        cache.insert(vec![3]);
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains(&[1]));
+        assert!(cache.contains(&[2]));
+        assert!(cache.contains(&[3]));
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_signature_cache_disabled() {
+        let cache = SignatureCache::new(0);
+        cache.insert(vec![1]);
+        assert!(cache.is_empty());
+        assert!(!cache.contains(&[1]));
+    }
+}
@@ -16,7 +16,8 @@ pub const REQUESTS_FOR_STATE_PROOFS: [&str; 11] = [
 
 pub const REQUEST_FOR_FULL: [&str; 2] = [POOL_RESTART, GET_VALIDATOR_INFO];
 
-pub const REQUESTS_FOR_STATE_PROOFS_IN_THE_PAST: [&str; 5] = [
+pub const REQUESTS_FOR_STATE_PROOFS_IN_THE_PAST: [&str; 6] = [
+    GET_NYM,
     GET_REVOC_REG,
     GET_REVOC_REG_DELTA,
     GET_TXN_AUTHR_AGRMT,
@@ -1,10 +1,16 @@
 extern crate rlp;
 
+/// Caching of verified BLS multi-signatures
+mod cache;
 pub(crate) mod constants;
 mod node;
+/// Custom state proof parsers registered by transaction type
+mod registry;
 pub(crate) mod types;
+mod verify;
 
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use rlp::UntrustedRlp;
@@ -12,7 +18,7 @@ use serde_json::Value as SJsonValue;
 use ursa::bls::{Bls, Generator, MultiSignature, VerKey};
 
 use crate::common::error::prelude::*;
-use crate::pool::{ProtocolVersion, VerifierKeys};
+use crate::pool::{LedgerType, ProtocolVersion, VerifierKeys};
 use crate::utils::base58;
 use crate::utils::base64;
 use crate::utils::hash::{
@@ -27,10 +33,17 @@ use self::constants::{
 use self::node::{Node, TrieDB};
 use self::types::*;
 
+pub use cache::SignatureCache;
+pub use registry::StateProofParsers;
 pub use types::ParsedSP;
+pub use verify::{
+    get_reply_signed_time, verify_bundle, verify_multi_signature, verify_receipt, verify_reply,
+    verify_write_reply, InclusionReceipt, ReplyBundle, ReplyVerification, VerifyReplyOptions,
+};
 
 /// A `StateProofParser` appropriate for attaching to a `PreparedRequest`
-pub struct BoxedSPParser(Box<dyn StateProofParser + Send + Sync>);
+#[derive(Clone)]
+pub struct BoxedSPParser(Arc<dyn StateProofParser + Send + Sync>);
 
 impl std::ops::Deref for BoxedSPParser {
     type Target = dyn StateProofParser;
@@ -61,7 +74,7 @@ pub trait StateProofParser {
     where
         Self: Send + Sync + Sized + 'static,
     {
-        BoxedSPParser(Box::new(self))
+        BoxedSPParser(Arc::new(self))
     }
 
     /// Parse a node message into a sequence of `ParsedSP` instances
@@ -81,48 +94,88 @@ where
     }
 }
 
+/// The outcome of verifying the state proof of a reply
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum StateProofResult {
+    /// The reply carries no state proof
+    Missing,
+    /// The state proof or its multi-signature could not be verified
+    Invalid,
+    /// The state proof is valid, but the state was signed before the freshness threshold
+    Expired,
+    /// The state proof is valid and fresh
+    Verified,
+}
+
+/// The verifier state and freshness requirements for checking the state proof of a reply
+pub(crate) struct StateProofContext<'a> {
+    /// The number of faulty nodes tolerated by the pool
+    pub f: usize,
+    /// The generator used in BLS signature verification
+    pub gen: &'a Generator,
+    /// The BLS keys of the pool verifiers
+    pub bls_keys: &'a VerifierKeys,
+    /// A cache of previously verified multi-signatures
+    pub signature_cache: Option<&'a SignatureCache>,
+    /// The state proof key of the request, if any
+    pub sp_key: Option<&'a [u8]>,
+    /// The timestamps of the request, used in the freshness check
+    pub requested_timestamps: (Option<u64>, Option<u64>),
+    /// The freshness threshold in seconds, or `None` to skip the freshness check
+    pub threshold: Option<u64>,
+    /// A custom parser for the state proofs of the reply
+    pub custom_state_proof_parser: Option<&'a BoxedSPParser>,
+}
+
 pub(crate) fn check_state_proof(
     msg_result: &SJsonValue,
-    f: usize,
-    gen: &Generator,
-    bls_keys: &VerifierKeys,
     raw_msg: &str,
-    sp_key: Option<&[u8]>,
-    requested_timestamps: (Option<u64>, Option<u64>),
     last_write_time: u64,
-    threshold: u64,
-    custom_state_proof_parser: Option<&BoxedSPParser>,
-) -> bool {
+    context: &StateProofContext,
+) -> StateProofResult {
     trace!("process_reply: Try to verify proof and signature >>");
 
     let res = match parse_generic_reply_for_proof_checking(
         &msg_result,
         raw_msg,
-        sp_key,
-        custom_state_proof_parser,
+        context.sp_key,
+        context.custom_state_proof_parser,
     ) {
         Some(parsed_sps) => {
             trace!("process_reply: Proof and signature are present");
-            if verify_parsed_sp(parsed_sps, bls_keys, f, gen) {
-                if check_freshness(msg_result, requested_timestamps, last_write_time, threshold) {
-                    true
+            if verify_parsed_sp(
+                parsed_sps,
+                context.bls_keys,
+                context.f,
+                context.gen,
+                context.signature_cache,
+            ) {
+                if context.threshold.map_or(true, |threshold| {
+                    check_freshness(
+                        msg_result,
+                        context.requested_timestamps,
+                        last_write_time,
+                        threshold,
+                    )
+                }) {
+                    StateProofResult::Verified
                 } else {
                     debug!("Freshness check failed");
-                    false
+                    StateProofResult::Expired
                 }
             } else {
                 debug!("Verification of parsed state proof failed");
-                false
+                StateProofResult::Invalid
             }
         }
         None => {
             debug!("No state proof found");
-            false
+            StateProofResult::Missing
         }
     };
 
     trace!(
-        "process_reply: Try to verify proof and signature << {}",
+        "process_reply: Try to verify proof and signature << {:?}",
         res
     );
     res
@@ -133,6 +186,23 @@ pub(crate) fn check_freshness(
     requested_timestamps: (Option<u64>, Option<u64>),
     last_write_time: u64,
     threshold: u64,
+) -> bool {
+    check_freshness_at(
+        msg_result,
+        requested_timestamps,
+        last_write_time,
+        threshold,
+        get_cur_time(),
+    )
+}
+
+/// Check the freshness of a reply relative to a given time in seconds since the epoch
+pub(crate) fn check_freshness_at(
+    msg_result: &SJsonValue,
+    requested_timestamps: (Option<u64>, Option<u64>),
+    last_write_time: u64,
+    threshold: u64,
+    cur_time: u64,
 ) -> bool {
     trace!(
         "check_freshness: requested_timestamps: {:?} >>",
@@ -175,7 +245,7 @@ pub(crate) fn check_freshness(
             trace!("Last right signed time: {}", last_write_time);
 
             let left_time_for_freshness_check = from;
-            let time_for_freshness_check = get_cur_time();
+            let time_for_freshness_check = cur_time;
 
             trace!(
                 "Left time for freshness check: {}",
@@ -187,7 +257,7 @@ pub(crate) fn check_freshness(
                 && time_for_freshness_check <= threshold + last_write_time
         }
         (None, None) => {
-            let time_for_freshness_check = get_cur_time();
+            let time_for_freshness_check = cur_time;
 
             trace!("Last signed time: {}", last_write_time);
             trace!("Time for freshness check: {}", time_for_freshness_check);
@@ -283,92 +353,143 @@ pub(crate) fn verify_parsed_sp(
     nodes: &VerifierKeys,
     f: usize,
     gen: &Generator,
+    signature_cache: Option<&SignatureCache>,
 ) -> bool {
     for parsed_sp in parsed_sps {
-        if parsed_sp.multi_signature["value"]["state_root_hash"]
-            .as_str()
-            .ne(&Some(&parsed_sp.root_hash))
-            && parsed_sp.multi_signature["value"]["txn_root_hash"]
-                .as_str()
-                .ne(&Some(&parsed_sp.root_hash))
-        {
-            debug!("Given signature is not for current root hash, aborting");
+        if _verify_parsed_sp_signature(&parsed_sp, nodes, f, gen, signature_cache).is_none() {
             return false;
         }
+        if !_verify_parsed_sp_proof(parsed_sp) {
+            return false;
+        }
+    }
 
-        let (signature, participants, value) = unwrap_opt_or_return!(
-            _parse_reply_for_proof_signature_checking(&parsed_sp.multi_signature),
-            {
-                debug!("Reply parsing failed");
-                false
-            }
-        );
-        if !_verify_proof_signature(signature, participants.as_slice(), &value, nodes, f, gen)
-            .map_err(|err| debug!("Proof signature verification failed: {}", err))
-            .unwrap_or(false)
+    true
+}
+
+/// Verify the multi-signature of a parsed state proof, returning the participating nodes
+fn _verify_parsed_sp_signature(
+    parsed_sp: &ParsedSP,
+    nodes: &VerifierKeys,
+    f: usize,
+    gen: &Generator,
+    signature_cache: Option<&SignatureCache>,
+) -> Option<Vec<String>> {
+    _verify_multi_signature(
+        &parsed_sp.multi_signature,
+        &parsed_sp.root_hash,
+        nodes,
+        f,
+        gen,
+        signature_cache,
+    )
+}
+
+/// Verify a multi-signature over a state or ledger root hash, returning the participating nodes
+fn _verify_multi_signature(
+    multi_signature: &SJsonValue,
+    root_hash: &str,
+    nodes: &VerifierKeys,
+    f: usize,
+    gen: &Generator,
+    signature_cache: Option<&SignatureCache>,
+) -> Option<Vec<String>> {
+    if multi_signature["value"]["state_root_hash"]
+        .as_str()
+        .ne(&Some(root_hash))
+        && multi_signature["value"]["txn_root_hash"]
+            .as_str()
+            .ne(&Some(root_hash))
+    {
+        debug!("Given signature is not for current root hash, aborting");
+        return None;
+    }
+
+    let (signature, participants, value) = unwrap_opt_or_return!(
+        _parse_reply_for_proof_signature_checking(multi_signature),
         {
-            return false;
+            debug!("Reply parsing failed");
+            None
         }
+    );
+    if !_verify_proof_signature(
+        signature,
+        participants.as_slice(),
+        &value,
+        nodes,
+        f,
+        gen,
+        signature_cache,
+    )
+    .map_err(|err| debug!("Proof signature verification failed: {}", err))
+    .unwrap_or(false)
+    {
+        return None;
+    }
 
-        let proof_nodes = unwrap_or_return!(base64::decode(&parsed_sp.proof_nodes), {
-            debug!("Error decoding proof nodes from state proof");
-            false
-        });
-        let root_hash = unwrap_or_return!(base58::decode(parsed_sp.root_hash), {
-            debug!("Error decoding root hash from state proof");
-            false
-        });
-        match parsed_sp.kvs_to_verify {
-            KeyValuesInSP::Simple(kvs) => match kvs.verification_type {
-                KeyValueSimpleDataVerificationType::Simple => {
-                    for (k, v) in kvs.kvs {
-                        let key = unwrap_or_return!(base64::decode(&k), {
-                            debug!("Error decoding proof key");
-                            false
-                        });
-                        if !_verify_proof(
-                            proof_nodes.as_slice(),
-                            root_hash.as_slice(),
-                            &key,
-                            v.as_ref().map(String::as_str),
-                        ) {
-                            debug!("Simple verification failed");
-                            return false;
-                        }
-                    }
-                }
-                KeyValueSimpleDataVerificationType::NumericalSuffixAscendingNoGaps(data) => {
-                    if !_verify_proof_range(
+    Some(participants)
+}
+
+/// Verify the key-value pairs of a parsed state proof against its root hash
+fn _verify_parsed_sp_proof(parsed_sp: ParsedSP) -> bool {
+    let proof_nodes = unwrap_or_return!(base64::decode(&parsed_sp.proof_nodes), {
+        debug!("Error decoding proof nodes from state proof");
+        false
+    });
+    let root_hash = unwrap_or_return!(base58::decode(parsed_sp.root_hash), {
+        debug!("Error decoding root hash from state proof");
+        false
+    });
+    match parsed_sp.kvs_to_verify {
+        KeyValuesInSP::Simple(kvs) => match kvs.verification_type {
+            KeyValueSimpleDataVerificationType::Simple => {
+                for (k, v) in kvs.kvs {
+                    let key = unwrap_or_return!(base64::decode(&k), {
+                        debug!("Error decoding proof key");
+                        false
+                    });
+                    if !_verify_proof(
                         proof_nodes.as_slice(),
                         root_hash.as_slice(),
-                        data.prefix.as_str(),
-                        data.from,
-                        data.next,
-                        &kvs.kvs,
+                        &key,
+                        v.as_ref().map(String::as_str),
                     ) {
-                        debug!("Range verification failed");
+                        debug!("Simple verification failed");
                         return false;
                     }
                 }
-                KeyValueSimpleDataVerificationType::MerkleTree(length) => {
-                    if !_verify_merkle_tree(
-                        proof_nodes.as_slice(),
-                        root_hash.as_slice(),
-                        &kvs.kvs,
-                        length,
-                    ) {
-                        return false;
-                    }
+            }
+            KeyValueSimpleDataVerificationType::NumericalSuffixAscendingNoGaps(data) => {
+                if !_verify_proof_range(
+                    proof_nodes.as_slice(),
+                    root_hash.as_slice(),
+                    data.prefix.as_str(),
+                    data.from,
+                    data.next,
+                    &kvs.kvs,
+                ) {
+                    debug!("Range verification failed");
+                    return false;
                 }
-            },
-            //TODO IS-713 support KeyValuesInSP::SubTrie
-            kvs => {
-                debug!(
-                    "Unsupported parsed state proof format for key-values {:?} ",
-                    kvs
-                );
-                return false;
             }
+            KeyValueSimpleDataVerificationType::MerkleTree { ledger_length } => {
+                if !_verify_merkle_tree(
+                    proof_nodes.as_slice(),
+                    root_hash.as_slice(),
+                    &kvs.kvs,
+                    ledger_length,
+                ) {
+                    return false;
+                }
+            }
+        },
+        //TODO IS-713 support KeyValuesInSP::SubTrie
+        kvs => {
+            debug!(
+                "Unsupported parsed state proof format for key-values {:?} ",
+                kvs
+            );
+            return false;
         }
     }
 
@@ -545,7 +666,10 @@ pub(crate) fn parse_key_from_request_for_builtin_sp(
         }
         constants::GET_TXN => {
             if let Some(seq_no) = json_msg["data"].as_u64() {
-                format!("{}", seq_no)
+                let ledger_id = json_msg["ledgerId"]
+                    .as_u64()
+                    .unwrap_or(LedgerType::DOMAIN as u64);
+                format!("{}:{}", ledger_id, seq_no)
             } else {
                 debug!("parse_key_from_request_for_builtin_sp: <<< GET_TXN has no seq_no, skip AuditProof logic");
                 return None;
@@ -613,6 +737,15 @@ pub(crate) fn parse_timestamp_from_req_for_builtin_sp(
     }
 
     match op {
+        constants::GET_NYM => {
+            if let Some(timestamp) = req["operation"]["timestamp"].as_u64() {
+                (None, Some(timestamp))
+            } else if req["operation"]["seqNo"].is_u64() {
+                (None, Some(0))
+            } else {
+                (None, None)
+            }
+        }
         constants::GET_REVOC_REG
         | constants::GET_TXN_AUTHR_AGRMT
         | constants::GET_TXN_AUTHR_AGRMT_AML => (None, req["operation"]["timestamp"].as_u64()),
@@ -717,7 +850,7 @@ fn _parse_reply_for_sp(
     data: Option<&str>,
     parsed_data: &SJsonValue,
     xtype: &str,
-    sp_key: &[u8],
+    mut sp_key: &[u8],
 ) -> VdrResult<ParsedSP> {
     let (proof, root_hash, ver_type, multi_sig) = if xtype != constants::GET_TXN {
         let proof = if let Some(proof) = json_msg["state_proof"]["proof_nodes"].as_str() {
@@ -763,14 +896,24 @@ fn _parse_reply_for_sp(
             return Err(input_err("No ledger length for this proof"));
         };
 
+        let multi_sig = json_msg["state_proof"]
+            .get("multi_signature")
+            .unwrap_or(&parsed_data["multi_signature"])
+            .clone();
+
+        // the key may be prefixed by the requested ledger, which must match the signed state
+        if let Some((ledger_id, seq_no)) = _split_txn_key(sp_key) {
+            if multi_sig["value"]["ledger_id"].as_u64() != Some(ledger_id) {
+                return Err(input_err("Multi-signature is not for the requested ledger"));
+            }
+            sp_key = seq_no;
+        }
+
         (
             proof,
             root_hash,
-            KeyValueSimpleDataVerificationType::MerkleTree(len),
-            json_msg["state_proof"]
-                .get("multi_signature")
-                .unwrap_or(&parsed_data["multi_signature"])
-                .clone(),
+            KeyValueSimpleDataVerificationType::MerkleTree { ledger_length: len },
+            multi_sig,
         )
     };
 
@@ -948,6 +1091,13 @@ fn _verify_merkle_tree(
         debug!("Error while parsing merkle tree seq_no: {}", err);
         false
     });
+    if seq_no == 0 || seq_no > length {
+        debug!(
+            "Merkle tree seq_no {} is outside of the ledger length {}",
+            seq_no, length
+        );
+        return false;
+    }
 
     let turns = _calculate_turns(length, seq_no - 1);
     trace!(
@@ -1006,6 +1156,16 @@ fn _verify_merkle_tree(
     result
 }
 
+/// Split a `GET_TXN` key of the form `<ledger_id>:<seq_no>`
+fn _split_txn_key(sp_key: &[u8]) -> Option<(u64, &[u8])> {
+    let pos = sp_key.iter().position(|c| *c == b':')?;
+    let ledger_id = std::str::from_utf8(&sp_key[..pos])
+        .ok()?
+        .parse::<u64>()
+        .ok()?;
+    Some((ledger_id, &sp_key[pos + 1..]))
+}
+
 // true is right
 // false is left
 fn _calculate_turns(length: u64, idx: u64) -> Vec<bool> {
@@ -1049,6 +1209,9 @@ fn _verify_proof(
         .unwrap_or(false)
 }
 
+/// A key-value pair from a state proof range, with the numerical suffix of its key
+type NumberedValue = (u64, (String, Option<String>));
+
 fn _verify_proof_range(
     proofs_rlp: &[u8],
     root_hash: &[u8],
@@ -1079,7 +1242,7 @@ fn _verify_proof_range(
         };
         // Preparation of data for verification
         // Fetch numerical suffixes
-        let vals_for_sort_check: Vec<Option<(u64, (String, Option<String>))>> = vals.into_iter()
+        let vals_for_sort_check: Vec<Option<NumberedValue>> = vals.into_iter()
             .filter(|(key, _)| key.starts_with(prefix))
             .map(|(key, value)| {
                 let no = key.replacen(prefix, "", 1).parse::<u64>();
@@ -1089,7 +1252,7 @@ fn _verify_proof_range(
             debug!("Some values in state proof are not correlating with state proof rule, aborting.");
             return false;
         }
-        let mut vals_for_sort: Vec<(u64, (String, Option<String>))> = vals_for_sort_check.into_iter().flat_map(|a| a).collect();
+        let mut vals_for_sort: Vec<NumberedValue> = vals_for_sort_check.into_iter().flat_map(|a| a).collect();
         // Sort by numerical suffixes in ascending order
         vals_for_sort.sort_by_key(|&(a, _)| a);
         trace!("Sorted trie values: {:?}", vals_for_sort);
@@ -1127,6 +1290,7 @@ fn _verify_proof_signature(
     nodes: &VerifierKeys,
     f: usize,
     gen: &Generator,
+    signature_cache: Option<&SignatureCache>,
 ) -> VdrResult<bool> {
     let mut ver_keys: Vec<&VerKey> = Vec::with_capacity(nodes.len());
 
@@ -1147,6 +1311,15 @@ fn _verify_proof_signature(
         return Ok(false);
     }
 
+    let cache_key = signature_cache
+        .map(|_| SignatureCache::signature_key(signature, participants, &ver_keys, value));
+    if let (Some(cache), Some(key)) = (signature_cache, cache_key.as_ref()) {
+        if cache.contains(key) {
+            trace!("verify_proof_signature: <<< cached");
+            return Ok(true);
+        }
+    }
+
     let signature = if let Ok(signature) = base58::decode(signature) {
         signature
     } else {
@@ -1160,6 +1333,11 @@ fn _verify_proof_signature(
     };
 
     let res = Bls::verify_multi_sig(&signature, value, ver_keys.as_slice(), gen).unwrap_or(false);
+    if res {
+        if let (Some(cache), Some(key)) = (signature_cache, cache_key) {
+            cache.insert(key);
+        }
+    }
 
     trace!("verify_proof_signature: <<< res: {:?}", res);
     Ok(res)
@@ -1196,50 +1374,20 @@ fn _parse_reply_for_proof_value(
         match xtype {
             //TODO constants::GET_DDO => support DDO
             constants::GET_TXN => {
-                value = json!({});
-                if parsed_data["txn"].is_null()
-                    && parsed_data["txnMetadata"].is_null()
-                    && parsed_data["ver"].is_null()
-                    && parsed_data["reqSignature"].is_null()
-                {
-                    return Ok(None);
-                }
-                if !parsed_data["txn"].is_null() {
-                    value["txn"] = parsed_data["txn"].clone();
-                }
-                if !parsed_data["txnMetadata"].is_null() {
-                    value["txnMetadata"] = parsed_data["txnMetadata"].clone();
-                }
-                if !parsed_data["ver"].is_null() {
-                    value["ver"] = parsed_data["ver"].clone();
-                }
-                if !parsed_data["reqSignature"].is_null() {
-                    value["reqSignature"] = parsed_data["reqSignature"].clone();
-                }
-
-                // Adjust attrib transaction to match stored state
-                if value["txn"]["type"].as_str() == Some("100") {
-                    if let Some(raw) = value["txn"]["data"]["raw"].as_str() {
-                        if raw.is_empty() {
-                            value["txn"]["data"]["raw"] = SJsonValue::from("");
-                        } else {
-                            value["txn"]["data"]["raw"] =
-                                SJsonValue::from(hex::encode(SHA256::digest(raw.as_bytes())));
-                        }
-                    } else if let Some(enc) = value["txn"]["data"]["enc"].as_str() {
-                        if enc.is_empty() {
-                            value["txn"]["data"]["enc"] = SJsonValue::from("");
-                        } else {
-                            value["txn"]["data"]["enc"] =
-                                SJsonValue::from(hex::encode(SHA256::digest(enc.as_bytes())));
-                        }
-                    }
-                }
+                value = match _parse_ledger_txn(parsed_data) {
+                    Some(value) => value,
+                    None => return Ok(None),
+                };
             }
             constants::GET_NYM => {
                 value["identifier"] = parsed_data["identifier"].clone();
                 value["role"] = parsed_data["role"].clone();
                 value["verkey"] = parsed_data["verkey"].clone();
+                for field in ["diddocContent", "version"].iter() {
+                    if !parsed_data[field].is_null() {
+                        value[field] = parsed_data[field].clone();
+                    }
+                }
             }
             constants::GET_ATTR => {
                 value["val"] = SJsonValue::String(hex::encode(SHA256::digest(data.as_bytes())));
@@ -1307,6 +1455,50 @@ fn _parse_reply_for_proof_value(
     }
 }
 
+/// Extract the ledger representation of a transaction from a `GET_TXN` or write result
+fn _parse_ledger_txn(parsed_data: &SJsonValue) -> Option<SJsonValue> {
+    if parsed_data["txn"].is_null()
+        && parsed_data["txnMetadata"].is_null()
+        && parsed_data["ver"].is_null()
+        && parsed_data["reqSignature"].is_null()
+    {
+        return None;
+    }
+    let mut value = json!({});
+    if !parsed_data["txn"].is_null() {
+        value["txn"] = parsed_data["txn"].clone();
+    }
+    if !parsed_data["txnMetadata"].is_null() {
+        value["txnMetadata"] = parsed_data["txnMetadata"].clone();
+    }
+    if !parsed_data["ver"].is_null() {
+        value["ver"] = parsed_data["ver"].clone();
+    }
+    if !parsed_data["reqSignature"].is_null() {
+        value["reqSignature"] = parsed_data["reqSignature"].clone();
+    }
+
+    // Adjust attrib transaction to match stored state
+    if value["txn"]["type"].as_str() == Some("100") {
+        if let Some(raw) = value["txn"]["data"]["raw"].as_str() {
+            if raw.is_empty() {
+                value["txn"]["data"]["raw"] = SJsonValue::from("");
+            } else {
+                value["txn"]["data"]["raw"] =
+                    SJsonValue::from(hex::encode(SHA256::digest(raw.as_bytes())));
+            }
+        } else if let Some(enc) = value["txn"]["data"]["enc"].as_str() {
+            if enc.is_empty() {
+                value["txn"]["data"]["enc"] = SJsonValue::from("");
+            } else {
+                value["txn"]["data"]["enc"] =
+                    SJsonValue::from(hex::encode(SHA256::digest(enc.as_bytes())));
+            }
+        }
+    }
+    Some(value)
+}
+
 fn _calculate_taa_digest(text: &str, version: &str) -> VdrResult<Vec<u8>> {
     let content: String = version.to_string() + text;
     Ok(SHA256::digest(content.as_bytes()))
@@ -1356,6 +1548,26 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn audit_proof_verify_works_for_out_of_range_seq_no() {
+        let nodes = json!([
+            "Gf9aBhHCtBpTYbJXQWnt1DU8q33hwi6nN4f3NhnsBgMZ",
+            "68TGAdRjeQ29eNcuFYhsX5uLakGQLgKMKp5wSyPzt9Nq",
+            "25KLEkkyCEPSBj4qMFE3AcH87mFocyJEuPJ5xzPGwDgz"
+        ])
+        .to_string();
+        let root_hash = base58::decode("CrA5sqYe3ruf2uY7d8re7ePmyHqptHqANtMZcfZd4BvK").unwrap();
+        for (seq_no, length) in [("0", 5), ("6", 5), ("1", 0)].iter() {
+            let kvs = vec![(base64::encode(seq_no), Some(r#"{"3":"3"}"#.to_string()))];
+            assert!(!_verify_merkle_tree(
+                nodes.as_bytes(),
+                root_hash.as_slice(),
+                kvs.as_slice(),
+                *length
+            ));
+        }
+    }
+
     #[test]
     fn audit_proof_verify_works_for_invalid_proof() {
         let nodes = json!([
@@ -2036,11 +2248,51 @@ mod tests {
                     base64::encode("2"),
                     Some(json!({"txn":{"test1": "test2", "seqNo": 2}}).to_string())
                 )],
-                verification_type: KeyValueSimpleDataVerificationType::MerkleTree(2),
+                verification_type: KeyValueSimpleDataVerificationType::MerkleTree {
+                    ledger_length: 2,
+                },
             })
         );
     }
 
+    #[test]
+    fn transaction_handler_parse_generic_reply_for_proof_checking_works_for_get_txn_ledger_id() {
+        let json_msg = &json!({
+            "type": constants::GET_TXN,
+            "data": {
+                "auditPath": ["1", "2"],
+                "ledgerSize": 2,
+                "rootHash": "123",
+                "txn": {"test1": "test2", "seqNo": 2},
+            },
+            "state_proof": {
+                "multi_signature": {"value": {"ledger_id": 1}}
+            }
+        });
+
+        let mut parsed_sps = super::parse_generic_reply_for_proof_checking(
+            json_msg,
+            "",
+            Some("1:2".as_bytes()),
+            None,
+        )
+        .unwrap();
+        assert_eq!(parsed_sps.len(), 1);
+        match parsed_sps.remove(0).kvs_to_verify {
+            KeyValuesInSP::Simple(kvs) => assert_eq!(kvs.kvs[0].0, base64::encode("2")),
+            kvs => panic!("Unexpected key-values: {:?}", kvs),
+        }
+
+        // the multi-signature must be for the requested ledger
+        assert!(super::parse_generic_reply_for_proof_checking(
+            json_msg,
+            "",
+            Some("0:2".as_bytes()),
+            None
+        )
+        .is_none());
+    }
+
     #[test]
     fn transaction_handler_parse_generic_reply_for_proof_checking_works_for_get_txn_no_multi_signature(
     ) {
@@ -2073,7 +2325,9 @@ mod tests {
                     base64::encode("2"),
                     Some(json!({"txn":{"test1": "test2", "seqNo": 2}}).to_string())
                 )],
-                verification_type: KeyValueSimpleDataVerificationType::MerkleTree(2),
+                verification_type: KeyValueSimpleDataVerificationType::MerkleTree {
+                    ledger_length: 2,
+                },
             })
         );
     }
@@ -2133,7 +2387,9 @@ mod tests {
             parsed_sp.kvs_to_verify,
             KeyValuesInSP::Simple(KeyValueSimpleData {
                 kvs: vec![(base64::encode("2"), None)],
-                verification_type: KeyValueSimpleDataVerificationType::MerkleTree(2),
+                verification_type: KeyValueSimpleDataVerificationType::MerkleTree {
+                    ledger_length: 2,
+                },
             })
         );
     }
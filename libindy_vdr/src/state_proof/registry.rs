@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use super::BoxedSPParser;
+
+/// A set of custom state proof parsers for transaction types, such as plugin ledger reads
+///
+/// A pool built with these parsers verifies replies to requests of a registered type using
+/// the parser, unless a built-in state proof is supported or the request was prepared
+/// with its own state proof parser.
+#[derive(Clone, Default)]
+pub struct StateProofParsers {
+    parsers: HashMap<String, BoxedSPParser>,
+}
+
+impl StateProofParsers {
+    /// Create a new, empty set of parsers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a parser for a transaction type, returning any parser it replaces
+    pub fn register<T: Into<String>>(
+        &mut self,
+        txn_type: T,
+        parser: BoxedSPParser,
+    ) -> Option<BoxedSPParser> {
+        self.parsers.insert(txn_type.into(), parser)
+    }
+
+    /// Remove the parser registered for a transaction type
+    pub fn unregister(&mut self, txn_type: &str) -> Option<BoxedSPParser> {
+        self.parsers.remove(txn_type)
+    }
+
+    /// Get the parser registered for a transaction type
+    pub fn get(&self, txn_type: &str) -> Option<&BoxedSPParser> {
+        self.parsers.get(txn_type)
+    }
+
+    /// Check whether no parsers are registered
+    pub fn is_empty(&self) -> bool {
+        self.parsers.is_empty()
+    }
+}
+
+impl fmt::Debug for StateProofParsers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.parsers.keys()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::state_proof::{state_proof_parser_fn, StateProofParser};
+
+    #[test]
+    fn test_register_parser() {
+        let mut parsers = StateProofParsers::new();
+        assert!(parsers.is_empty());
+
+        let parser = state_proof_parser_fn(|_, _| None).boxed();
+        assert!(parsers.register("10001", parser.clone()).is_none());
+        assert!(parsers.register("10001", parser).is_some());
+        assert!(parsers.get("10001").is_some());
+        assert!(parsers.get("10002").is_none());
+
+        assert!(parsers.unregister("10001").is_some());
+        assert!(parsers.get("10001").is_none());
+        assert!(parsers.is_empty());
+    }
+}
@@ -43,7 +43,7 @@ pub enum KeyValueSimpleDataVerificationType {
     /* key should be plain string */
     NumericalSuffixAscendingNoGaps(NumericalSuffixAscendingNoGapsData),
     /* nodes are from a simple merkle tree */
-    MerkleTree(u64),
+    MerkleTree { ledger_length: u64 },
 }
 
 impl Default for KeyValueSimpleDataVerificationType {
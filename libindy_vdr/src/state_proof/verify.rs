@@ -0,0 +1,817 @@
+use serde_json::Value as SJsonValue;
+use ursa::bls::Generator;
+
+use crate::common::error::prelude::*;
+use crate::config::constants::{DEFAULT_FRESHNESS_TIMEOUT, DEFAULT_GENERATOR};
+use crate::pool::handlers::{get_last_signed_time, min_consensus};
+use crate::pool::{
+    ConsensusPolicy, PoolTransactions, PreparedRequest, RequestMethod, VerifierKeys, Verifiers,
+};
+use crate::utils::{base58, base64};
+
+use super::{
+    _parse_ledger_txn, _verify_merkle_tree, _verify_multi_signature, _verify_parsed_sp_proof,
+    _verify_parsed_sp_signature, check_freshness_at, get_cur_time,
+    parse_generic_reply_for_proof_checking, ParsedSP, SignatureCache,
+};
+
+/// Options for verifying a ledger reply outside of a pool request
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyReplyOptions {
+    /// The freshness threshold to accept in seconds, unless overridden by the request policy
+    #[serde(default = "VerifyReplyOptions::default_freshness_threshold")]
+    pub freshness_threshold: u64,
+    /// The time to check freshness against in seconds since the epoch, defaulting to the current time
+    #[serde(default)]
+    pub check_time: Option<u64>,
+}
+
+impl VerifyReplyOptions {
+    /// The default freshness threshold to accept
+    pub fn default_freshness_threshold() -> u64 {
+        DEFAULT_FRESHNESS_TIMEOUT
+    }
+}
+
+impl Default for VerifyReplyOptions {
+    fn default() -> Self {
+        Self {
+            freshness_threshold: Self::default_freshness_threshold(),
+            check_time: None,
+        }
+    }
+}
+
+/// The result of verifying the state proof of a ledger reply
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ReplyVerification {
+    /// Whether the reply contains a state proof for the request
+    pub has_state_proof: bool,
+    /// Whether the state proof matches the reply data
+    pub proof_valid: bool,
+    /// Whether the state proof is signed by a sufficient number of verifier nodes
+    pub multi_signature_valid: bool,
+    /// Whether the signed state is within the freshness threshold
+    pub fresh: bool,
+    /// The aliases of the nodes participating in a valid multi-signature
+    pub signing_nodes: Vec<String>,
+    /// The timestamp of the signed state, if any
+    pub last_signed_time: Option<u64>,
+}
+
+impl ReplyVerification {
+    /// Check whether the reply passed all verification steps
+    pub fn is_valid(&self) -> bool {
+        self.has_state_proof && self.proof_valid && self.multi_signature_valid && self.fresh
+    }
+}
+
+/// Verify the state proof of a node reply to a prepared request, without contacting the pool
+///
+/// The verifier nodes are only used to check the BLS multi-signature, so a client holding
+/// the genesis transactions may verify replies which were cached or relayed by another service.
+pub fn verify_reply(
+    prepared: &PreparedRequest,
+    raw_reply: &str,
+    verifiers: &Verifiers,
+    options: &VerifyReplyOptions,
+) -> VdrResult<ReplyVerification> {
+    let (sp_key, sp_timestamps, sp_parser) = match &prepared.method {
+        RequestMethod::BuiltinStateProof {
+            sp_key,
+            sp_timestamps,
+        } => (Some(sp_key.as_slice()), *sp_timestamps, None),
+        RequestMethod::CustomStateProof {
+            sp_parser,
+            sp_timestamps,
+        } => (None, *sp_timestamps, Some(sp_parser)),
+        _ => {
+            return Err(input_err(
+                "Request does not support state proof verification",
+            ))
+        }
+    };
+
+    let result = parse_reply_result(raw_reply)?;
+    let (node_keys, f, generator) = signature_context(verifiers)?;
+
+    let mut verification = ReplyVerification {
+        last_signed_time: get_last_signed_time(&result),
+        ..Default::default()
+    };
+    let parsed_sps =
+        match parse_generic_reply_for_proof_checking(&result, raw_reply, sp_key, sp_parser) {
+            Some(parsed_sps) if !parsed_sps.is_empty() => parsed_sps,
+            _ => return Ok(verification),
+        };
+    verification.has_state_proof = true;
+    verification.proof_valid = true;
+    verification.multi_signature_valid = true;
+    for parsed_sp in parsed_sps {
+        match _verify_parsed_sp_signature(&parsed_sp, &node_keys, f, &generator, None) {
+            Some(participants) => {
+                for alias in participants {
+                    if !verification.signing_nodes.contains(&alias) {
+                        verification.signing_nodes.push(alias);
+                    }
+                }
+            }
+            None => verification.multi_signature_valid = false,
+        }
+        if !_verify_parsed_sp_proof(parsed_sp) {
+            verification.proof_valid = false;
+        }
+    }
+    verification.signing_nodes.sort();
+    verification.fresh = ConsensusPolicy::freshness_threshold(
+        prepared.consensus_policy.as_ref(),
+        options.freshness_threshold,
+    )
+    .map_or(true, |threshold| {
+        check_freshness_at(
+            &result,
+            sp_timestamps,
+            verification.last_signed_time.unwrap_or(0),
+            threshold,
+            options.check_time.unwrap_or_else(get_cur_time),
+        )
+    });
+
+    Ok(verification)
+}
+
+/// A ledger reply packaged with the information needed to verify its state proof later
+///
+/// Replies are verified against the pool transactions recorded by the bundle, so that
+/// a bundle remains verifiable after the verifier nodes or their BLS keys are changed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReplyBundle {
+    /// The ledger request
+    pub request: SJsonValue,
+    /// The raw ledger reply
+    pub reply: String,
+    /// The state proofs contained in the reply
+    pub state_proofs: Vec<ParsedSP>,
+    /// The aliases of the nodes participating in the multi-signature
+    pub signing_nodes: Vec<String>,
+    /// The base58-encoded merkle root of the pool transactions
+    pub pool_root: String,
+    /// The number of pool transactions
+    pub pool_size: usize,
+    /// The timestamp of the signed state, if any
+    pub timestamp: Option<u64>,
+    /// The time the reply was bundled in seconds since the epoch
+    ///
+    /// This time is not covered by the multi-signature, so it is not used for verification.
+    #[serde(default)]
+    pub received_at: Option<u64>,
+}
+
+impl ReplyBundle {
+    /// Package a ledger reply to a request with a built-in state proof
+    pub fn new(
+        prepared: &PreparedRequest,
+        raw_reply: &str,
+        pool_txns: &PoolTransactions,
+    ) -> VdrResult<Self> {
+        let sp_key = match &prepared.method {
+            RequestMethod::BuiltinStateProof { sp_key, .. } => sp_key.as_slice(),
+            _ => {
+                return Err(input_err(
+                    "Only replies to requests with a built-in state proof may be bundled",
+                ))
+            }
+        };
+        let result = parse_reply_result(raw_reply)?;
+        let state_proofs =
+            parse_generic_reply_for_proof_checking(&result, raw_reply, Some(sp_key), None)
+                .ok_or_else(|| input_err("Ledger reply has no state proof"))?;
+        let signing_nodes = multi_signature_participants(&state_proofs);
+        let pool_tree = pool_txns.merkle_tree()?;
+        Ok(Self {
+            request: prepared.req_json.clone(),
+            reply: raw_reply.to_owned(),
+            state_proofs,
+            signing_nodes,
+            pool_root: base58::encode(pool_tree.root_hash()),
+            pool_size: pool_tree.count(),
+            timestamp: get_last_signed_time(&result),
+            received_at: Some(get_cur_time()),
+        })
+    }
+
+    /// Load a bundle from its JSON representation
+    pub fn from_json(bundle: &str) -> VdrResult<Self> {
+        serde_json::from_str(bundle).with_input_err("Invalid reply bundle")
+    }
+
+    /// Serialize the bundle to JSON
+    pub fn to_json(&self) -> VdrResult<String> {
+        serde_json::to_string(self).with_input_err("Error serializing reply bundle")
+    }
+}
+
+/// Verify a reply bundle against a snapshot of the pool transactions
+///
+/// The snapshot must begin with the pool transactions recorded by the bundle. Later
+/// transactions are ignored, so the current pool ledger may be used. Freshness is checked
+/// against the check time given by the options, or else the current time.
+pub fn verify_bundle(
+    bundle: &ReplyBundle,
+    pool_txns: &PoolTransactions,
+    options: &VerifyReplyOptions,
+) -> VdrResult<ReplyVerification> {
+    if bundle.pool_size == 0 || pool_txns.len() < bundle.pool_size {
+        return Err(input_err(
+            "Pool transactions do not include the bundled pool state",
+        ));
+    }
+    let pool_txns = PoolTransactions::from_transactions(pool_txns.iter().take(bundle.pool_size));
+    if base58::encode(pool_txns.merkle_tree()?.root_hash()) != bundle.pool_root {
+        return Err(input_err(
+            "Pool transactions are not consistent with the bundled pool state",
+        ));
+    }
+
+    let prepared = PreparedRequest::from_request_json_ext(bundle.request.clone(), false, None)?;
+    let sp_key = match &prepared.method {
+        RequestMethod::BuiltinStateProof { sp_key, .. } => sp_key.as_slice(),
+        _ => return Err(input_err("Bundled request has no built-in state proof")),
+    };
+    let result = parse_reply_result(&bundle.reply)?;
+    let state_proofs =
+        parse_generic_reply_for_proof_checking(&result, &bundle.reply, Some(sp_key), None);
+    if serde_json::to_value(&state_proofs).ok() != serde_json::to_value(&bundle.state_proofs).ok() {
+        return Err(input_err(
+            "Bundled state proofs do not match the ledger reply",
+        ));
+    }
+    let state_proofs = state_proofs.unwrap_or_default();
+    if bundle.signing_nodes != multi_signature_participants(&state_proofs) {
+        return Err(input_err(
+            "Bundled signing nodes do not match the ledger reply",
+        ));
+    }
+    if bundle.timestamp != get_last_signed_time(&result) {
+        return Err(input_err(
+            "Bundled timestamp does not match the ledger reply",
+        ));
+    }
+
+    let verifiers = pool_txns.verifiers(prepared.protocol_version)?;
+    verify_reply(&prepared, &bundle.reply, &verifiers, options)
+}
+
+/// Collect the aliases of the nodes listed as participants in the state proof multi-signatures
+fn multi_signature_participants(state_proofs: &[ParsedSP]) -> Vec<String> {
+    let mut participants = vec![];
+    for parsed_sp in state_proofs {
+        if let Some(aliases) = parsed_sp.multi_signature["participants"].as_array() {
+            for alias in aliases.iter().filter_map(SJsonValue::as_str) {
+                if !participants.iter().any(|node| node == alias) {
+                    participants.push(alias.to_owned());
+                }
+            }
+        }
+    }
+    participants.sort();
+    participants
+}
+
+/// Get the timestamp of the signed state in a ledger reply, if any
+pub fn get_reply_signed_time(raw_reply: &str) -> VdrResult<Option<u64>> {
+    Ok(get_last_signed_time(&parse_reply_result(raw_reply)?))
+}
+
+/// Evidence that a write transaction was committed to the ledger
+///
+/// The audit path proves the inclusion of the transaction under the ledger root hash.
+/// When the nodes include a multi-signature over the ledger root, the receipt may be
+/// verified later by any party holding the verifier BLS keys.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InclusionReceipt {
+    /// The committed ledger transaction
+    pub txn: SJsonValue,
+    /// The sequence number of the transaction
+    pub seq_no: u64,
+    /// The number of ledger transactions covered by the root hash
+    pub ledger_size: u64,
+    /// The base58-encoded merkle root of the ledger
+    pub root_hash: String,
+    /// The base58-encoded merkle audit path of the transaction
+    pub audit_path: Vec<String>,
+    /// The multi-signature over the ledger root, if provided by the reply
+    pub multi_signature: Option<SJsonValue>,
+    /// The aliases of the nodes participating in a valid multi-signature
+    pub signing_nodes: Vec<String>,
+}
+
+impl InclusionReceipt {
+    /// Extract the receipt from the result of a write reply
+    pub(crate) fn from_result(result: &SJsonValue) -> VdrResult<Self> {
+        let txn = _parse_ledger_txn(result)
+            .ok_or_else(|| input_err("Write reply has no ledger transaction"))?;
+        let seq_no = txn["txnMetadata"]["seqNo"]
+            .as_u64()
+            .ok_or_else(|| input_err("Write reply has no transaction sequence number"))?;
+        let root_hash = result["rootHash"]
+            .as_str()
+            .ok_or_else(|| input_err("Write reply has no ledger root hash"))?;
+        let audit_path = serde_json::from_value(result["auditPath"].clone())
+            .with_input_err("Write reply has no valid audit path")?;
+        Ok(Self {
+            txn,
+            seq_no,
+            ledger_size: result["ledgerSize"].as_u64().unwrap_or(seq_no),
+            root_hash: root_hash.to_owned(),
+            audit_path,
+            multi_signature: result["state_proof"]
+                .get("multi_signature")
+                .or_else(|| result.get("multi_signature"))
+                .cloned(),
+            signing_nodes: vec![],
+        })
+    }
+
+    /// Load a receipt from its JSON representation
+    pub fn from_json(receipt: &str) -> VdrResult<Self> {
+        serde_json::from_str(receipt).with_input_err("Invalid inclusion receipt")
+    }
+
+    /// Serialize the receipt to JSON
+    pub fn to_json(&self) -> VdrResult<String> {
+        serde_json::to_string(self).with_input_err("Error serializing inclusion receipt")
+    }
+
+    /// Check whether the receipt records the verifier nodes which signed the ledger root
+    ///
+    /// The signing nodes are recorded by `verify_write_reply`. A receipt loaded from JSON
+    /// should be checked again with `verify_receipt`.
+    pub fn has_signing_nodes(&self) -> bool {
+        !self.signing_nodes.is_empty()
+    }
+
+    /// Verify the audit path of the transaction and any multi-signature over the ledger
+    /// root, returning the nodes participating in the multi-signature
+    pub(crate) fn verify_signed(
+        &self,
+        node_keys: &VerifierKeys,
+        f: usize,
+        gen: &Generator,
+        signature_cache: Option<&SignatureCache>,
+    ) -> VdrResult<Vec<String>> {
+        self.verify_audit_path()?;
+        let multi_signature = match self.multi_signature.as_ref() {
+            Some(multi_signature) => multi_signature,
+            None => return Ok(vec![]),
+        };
+        let mut signing_nodes = _verify_multi_signature(
+            multi_signature,
+            &self.root_hash,
+            node_keys,
+            f,
+            gen,
+            signature_cache,
+        )
+        .ok_or_else(|| input_err("Ledger root multi-signature verification failed"))?;
+        signing_nodes.sort();
+        Ok(signing_nodes)
+    }
+
+    /// Verify the inclusion of the transaction under the ledger root hash
+    pub(crate) fn verify_audit_path(&self) -> VdrResult<()> {
+        let root_hash = base58::decode(&self.root_hash)?;
+        let audit_path = serde_json::to_string(&self.audit_path)
+            .with_input_err("Error serializing audit path")?;
+        let kvs = [(
+            base64::encode(self.seq_no.to_string()),
+            Some(self.txn.to_string()),
+        )];
+        if _verify_merkle_tree(audit_path.as_bytes(), &root_hash, &kvs, self.ledger_size) {
+            Ok(())
+        } else {
+            Err(input_err("Transaction audit path verification failed"))
+        }
+    }
+}
+
+/// Verify that the transaction in a write reply was committed to the ledger
+///
+/// The reply must contain the audit path of the transaction. A multi-signature over the
+/// ledger root is checked when present, failing verification if it is invalid, and the
+/// signing nodes are recorded by the receipt.
+pub fn verify_write_reply(raw_reply: &str, verifiers: &Verifiers) -> VdrResult<InclusionReceipt> {
+    let result = parse_reply_result(raw_reply)?;
+    let mut receipt = InclusionReceipt::from_result(&result)?;
+    receipt.signing_nodes = verify_receipt(&receipt, verifiers)?;
+    Ok(receipt)
+}
+
+/// Verify an inclusion receipt, returning the nodes participating in the multi-signature
+///
+/// A receipt without a multi-signature is verified by its audit path alone, returning
+/// no signing nodes. A receipt with an invalid multi-signature fails verification.
+pub fn verify_receipt(receipt: &InclusionReceipt, verifiers: &Verifiers) -> VdrResult<Vec<String>> {
+    let (node_keys, f, generator) = signature_context(verifiers)?;
+    receipt.verify_signed(&node_keys, f, &generator, None)
+}
+
+/// Verify a BLS multi-signature over a ledger or state root hash
+///
+/// Returns the participating nodes when the signature is valid and signed by a sufficient
+/// number of verifiers. Signatures found in the cache skip the pairing check.
+pub fn verify_multi_signature(
+    multi_signature: &SJsonValue,
+    root_hash: &str,
+    verifiers: &Verifiers,
+    signature_cache: Option<&SignatureCache>,
+) -> VdrResult<Option<Vec<String>>> {
+    let (node_keys, f, generator) = signature_context(verifiers)?;
+    Ok(_verify_multi_signature(
+        multi_signature,
+        root_hash,
+        &node_keys,
+        f,
+        &generator,
+        signature_cache,
+    ))
+}
+
+/// Collect the BLS keys and fault tolerance of a set of verifiers
+fn signature_context(verifiers: &Verifiers) -> VdrResult<(VerifierKeys, usize, Generator)> {
+    let node_keys: VerifierKeys = verifiers
+        .iter()
+        .filter_map(|(alias, info)| {
+            info.bls_key
+                .as_ref()
+                .map(|bls_key| (alias.clone(), bls_key.clone()))
+        })
+        .collect();
+    let f = min_consensus(verifiers.len());
+    let generator: Generator =
+        Generator::from_bytes(&base58::decode(DEFAULT_GENERATOR)?).map_err(|err| {
+            err_msg(
+                VdrErrorKind::Resource,
+                format!("Error loading generator: {}", err),
+            )
+        })?;
+    Ok((node_keys, f, generator))
+}
+
+/// Extract the result of a ledger reply
+fn parse_reply_result(raw_reply: &str) -> VdrResult<SJsonValue> {
+    let reply: SJsonValue =
+        serde_json::from_str(raw_reply).with_input_err("Malformed reply json")?;
+    if reply["op"].as_str() != Some("REPLY") {
+        return Err(input_err("Message is not a ledger reply"));
+    }
+    reply
+        .get("result") // V0
+        .or_else(|| reply["data"]["result"][0].get("result")) // V1
+        .cloned()
+        .ok_or_else(|| input_err("Ledger reply has no result"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use indy_test_utils::genesis::GenesisTransactions;
+    use ursa::bls::{Bls, MultiSignature, SignKey, VerKey};
+
+    use crate::common::merkle_tree::MerkleTree;
+    use crate::ledger::RequestBuilder;
+    use crate::pool::{VerifierInfo, VerifierKey};
+    use crate::utils::hash::{TreeHash, SHA256::DigestType as Sha256};
+
+    const NODES: [&str; 4] = ["Node1", "Node2", "Node3", "Node4"];
+
+    fn _txn(seq_no: u64) -> SJsonValue {
+        json!({
+            "txn": {"type": "1", "data": {"dest": format!("Dest{}", seq_no)}},
+            "txnMetadata": {"seqNo": seq_no},
+            "ver": "1",
+        })
+    }
+
+    fn _sign_keys() -> Vec<SignKey> {
+        (0..NODES.len())
+            .map(|idx| SignKey::new(Some(&[idx as u8 + 1; 32][..])).unwrap())
+            .collect()
+    }
+
+    fn _verifiers(gen: &Generator) -> Verifiers {
+        NODES
+            .iter()
+            .zip(_sign_keys())
+            .map(|(alias, sign_key)| {
+                let ver_key = VerKey::new(gen, &sign_key).unwrap();
+                (
+                    alias.to_string(),
+                    VerifierInfo {
+                        client_addr: String::new(),
+                        node_addr: String::new(),
+                        public_key: String::new(),
+                        enc_key: vec![],
+                        bls_key: Some(VerifierKey::from_bytes(ver_key.as_bytes()).unwrap()),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn _signed_pool_txns(gen: &Generator) -> PoolTransactions {
+        let txns = GenesisTransactions::new(Some(NODES.len()))
+            .transactions
+            .iter()
+            .zip(_sign_keys())
+            .map(|(txn, sign_key)| {
+                let mut txn: SJsonValue = serde_json::from_str(txn).unwrap();
+                let ver_key = VerKey::new(gen, &sign_key).unwrap();
+                txn["txn"]["data"]["data"]["blskey"] = json!(base58::encode(ver_key.as_bytes()));
+                txn.to_string()
+            })
+            .collect::<Vec<_>>();
+        PoolTransactions::from_json_transactions(txns).unwrap()
+    }
+
+    fn _pool_txns(count: usize) -> PoolTransactions {
+        PoolTransactions::from_json_transactions(
+            &GenesisTransactions::new(Some(count)).transactions,
+        )
+        .unwrap()
+    }
+
+    fn _ledger_leaves() -> (Vec<Vec<u8>>, String) {
+        let leaves = vec![
+            rmp_serde::to_vec(&_txn(1)).unwrap(),
+            rmp_serde::to_vec(&_txn(2)).unwrap(),
+        ];
+        let tree = MerkleTree::from_vec(leaves.clone()).unwrap();
+        let root_hash = base58::encode(tree.root_hash());
+        (leaves, root_hash)
+    }
+
+    fn _multi_signature(signers: usize, root_hash: &str) -> SJsonValue {
+        let value = json!({
+            "ledger_id": 1,
+            "pool_state_root_hash": "",
+            "state_root_hash": "",
+            "timestamp": 1000,
+            "txn_root_hash": root_hash,
+        });
+        let message = rmp_serde::to_vec_named(&value).unwrap();
+        let signatures = _sign_keys()
+            .iter()
+            .take(signers)
+            .map(|sign_key| Bls::sign(&message, sign_key).unwrap())
+            .collect::<Vec<_>>();
+        let multi_sig = MultiSignature::new(&signatures.iter().collect::<Vec<_>>()).unwrap();
+        json!({
+            "participants": &NODES[..signers],
+            "signature": base58::encode(multi_sig.as_bytes()),
+            "value": value,
+        })
+    }
+
+    fn _get_txn_reply(signers: usize, txn: SJsonValue) -> String {
+        let (leaves, root_hash) = _ledger_leaves();
+        let mut data = txn;
+        data["auditPath"] = json!([base58::encode(Sha256::hash_leaf(&leaves[1]).unwrap())]);
+        data["ledgerSize"] = json!(2);
+        data["rootHash"] = json!(root_hash);
+        json!({
+            "op": "REPLY",
+            "result": {
+                "type": "3",
+                "identifier": "LibindyDid111111111111",
+                "reqId": 1,
+                "seqNo": 1,
+                "data": data,
+                "state_proof": {
+                    "multi_signature": _multi_signature(signers, &root_hash),
+                }
+            }
+        })
+        .to_string()
+    }
+
+    fn _write_reply(signers: Option<usize>, txn: SJsonValue) -> String {
+        let (leaves, root_hash) = _ledger_leaves();
+        let mut result = txn;
+        result["auditPath"] = json!([base58::encode(Sha256::hash_leaf(&leaves[0]).unwrap())]);
+        result["rootHash"] = json!(root_hash);
+        if let Some(signers) = signers {
+            result["state_proof"] = json!({
+                "multi_signature": _multi_signature(signers, &root_hash),
+            });
+        }
+        json!({"op": "REPLY", "result": result}).to_string()
+    }
+
+    #[test]
+    fn test_verify_reply_for_get_txn() {
+        let gen = Generator::from_bytes(&base58::decode(DEFAULT_GENERATOR).unwrap()).unwrap();
+        let verifiers = _verifiers(&gen);
+        let request = RequestBuilder::default()
+            .build_get_txn_request(None, 1, 1)
+            .unwrap();
+        let options = VerifyReplyOptions::default();
+
+        let result =
+            verify_reply(&request, &_get_txn_reply(3, _txn(1)), &verifiers, &options).unwrap();
+        assert!(result.is_valid());
+        assert_eq!(result.signing_nodes, &NODES[..3]);
+
+        // the transaction does not match the audit path
+        let result =
+            verify_reply(&request, &_get_txn_reply(3, _txn(2)), &verifiers, &options).unwrap();
+        assert!(result.multi_signature_valid);
+        assert!(!result.proof_valid);
+
+        // too few nodes participating in the multi-signature
+        let result =
+            verify_reply(&request, &_get_txn_reply(2, _txn(1)), &verifiers, &options).unwrap();
+        assert!(result.proof_valid);
+        assert!(!result.multi_signature_valid);
+        assert!(result.signing_nodes.is_empty());
+    }
+
+    #[test]
+    fn test_verify_bundle() {
+        let request = RequestBuilder::default()
+            .build_get_txn_request(None, 1, 1)
+            .unwrap();
+        let bundle =
+            ReplyBundle::new(&request, &_get_txn_reply(3, _txn(1)), &_pool_txns(3)).unwrap();
+        assert_eq!(bundle.signing_nodes, &NODES[..3]);
+        assert_eq!(bundle.timestamp, Some(1000));
+        let bundle = ReplyBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+
+        // later pool transactions are ignored
+        let result =
+            verify_bundle(&bundle, &_pool_txns(4), &VerifyReplyOptions::default()).unwrap();
+        assert!(result.has_state_proof);
+        assert!(result.proof_valid);
+        // the multi-signature is not produced by the genesis BLS keys
+        assert!(!result.multi_signature_valid);
+
+        verify_bundle(&bundle, &_pool_txns(2), &VerifyReplyOptions::default()).unwrap_err();
+
+        let mut tampered = ReplyBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+        tampered.reply = _get_txn_reply(3, _txn(2));
+        verify_bundle(&tampered, &_pool_txns(3), &VerifyReplyOptions::default()).unwrap_err();
+
+        let mut tampered = ReplyBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+        tampered.signing_nodes = NODES.iter().map(|alias| alias.to_string()).collect();
+        verify_bundle(&tampered, &_pool_txns(3), &VerifyReplyOptions::default()).unwrap_err();
+
+        let mut tampered = ReplyBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+        tampered.timestamp = Some(2000);
+        verify_bundle(&tampered, &_pool_txns(3), &VerifyReplyOptions::default()).unwrap_err();
+    }
+
+    #[test]
+    fn test_verify_bundle_with_multi_signature() {
+        let gen = Generator::from_bytes(&base58::decode(DEFAULT_GENERATOR).unwrap()).unwrap();
+        let pool_txns = _signed_pool_txns(&gen);
+        let request = RequestBuilder::default()
+            .build_get_txn_request(None, 1, 1)
+            .unwrap();
+        let bundle = ReplyBundle::new(&request, &_get_txn_reply(3, _txn(1)), &pool_txns).unwrap();
+        let bundle = ReplyBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+        let options = VerifyReplyOptions {
+            check_time: Some(1010),
+            ..VerifyReplyOptions::default()
+        };
+
+        let result = verify_bundle(&bundle, &pool_txns, &options).unwrap();
+        assert!(result.is_valid());
+        assert_eq!(result.signing_nodes, &NODES[..3]);
+        assert_eq!(result.last_signed_time, Some(1000));
+    }
+
+    #[test]
+    fn test_verify_bundle_freshness() {
+        let request = RequestBuilder::default()
+            .build_get_txn_request(None, 1, 1)
+            .unwrap();
+        let pool_txns = _pool_txns(3);
+        let is_fresh = |bundle: &ReplyBundle, options: &VerifyReplyOptions| {
+            verify_bundle(bundle, &pool_txns, options).unwrap().fresh
+        };
+        let options = VerifyReplyOptions::default();
+
+        // the signed state is checked against the current time by default
+        let bundle = ReplyBundle::new(&request, &_get_txn_reply(3, _txn(1)), &pool_txns).unwrap();
+        assert!(!is_fresh(&bundle, &options));
+
+        // the unsigned receipt time of the bundle is not trusted
+        let mut bundle = ReplyBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+        bundle.received_at = Some(1010);
+        assert!(!is_fresh(&bundle, &options));
+
+        let check_time = Some(1010);
+        assert!(is_fresh(
+            &bundle,
+            &VerifyReplyOptions {
+                check_time,
+                ..options.clone()
+            }
+        ));
+        let check_time = Some(1000 + options.freshness_threshold + 1);
+        assert!(!is_fresh(
+            &bundle,
+            &VerifyReplyOptions {
+                check_time,
+                ..options.clone()
+            }
+        ));
+    }
+
+    #[test]
+    fn test_verify_write_reply() {
+        let gen = Generator::from_bytes(&base58::decode(DEFAULT_GENERATOR).unwrap()).unwrap();
+        let verifiers = _verifiers(&gen);
+
+        let receipt = verify_write_reply(&_write_reply(Some(3), _txn(2)), &verifiers).unwrap();
+        assert_eq!(receipt.seq_no, 2);
+        assert_eq!(receipt.ledger_size, 2);
+        assert!(receipt.has_signing_nodes());
+        assert_eq!(receipt.signing_nodes, &NODES[..3]);
+        let receipt = InclusionReceipt::from_json(&receipt.to_json().unwrap()).unwrap();
+        assert_eq!(verify_receipt(&receipt, &verifiers).unwrap(), &NODES[..3]);
+
+        // the audit path is verified without a multi-signature
+        let receipt = verify_write_reply(&_write_reply(None, _txn(2)), &verifiers).unwrap();
+        assert!(!receipt.has_signing_nodes());
+
+        // too few nodes participating in the multi-signature
+        verify_write_reply(&_write_reply(Some(2), _txn(2)), &verifiers).unwrap_err();
+        let mut receipt = InclusionReceipt::from_json(&receipt.to_json().unwrap()).unwrap();
+        receipt.multi_signature = Some(_multi_signature(2, &receipt.root_hash));
+        verify_receipt(&receipt, &verifiers).unwrap_err();
+
+        // the transaction does not match the audit path
+        verify_write_reply(&_write_reply(Some(3), _txn(1)), &verifiers).unwrap_err();
+
+        // no audit path
+        verify_write_reply(
+            &json!({"op": "REPLY", "result": _txn(2)}).to_string(),
+            &verifiers,
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_verify_multi_signature_uses_cache() {
+        let gen = Generator::from_bytes(&base58::decode(DEFAULT_GENERATOR).unwrap()).unwrap();
+        let verifiers = _verifiers(&gen);
+        let (_, root_hash) = _ledger_leaves();
+        let cache = SignatureCache::new(4);
+
+        let multi_sig = _multi_signature(3, &root_hash);
+        let participants =
+            verify_multi_signature(&multi_sig, &root_hash, &verifiers, Some(&cache)).unwrap();
+        assert_eq!(participants.unwrap(), &NODES[..3]);
+        assert_eq!(cache.len(), 1);
+        verify_multi_signature(&multi_sig, &root_hash, &verifiers, Some(&cache))
+            .unwrap()
+            .unwrap();
+        assert_eq!(cache.len(), 1);
+
+        // a cached signature is not accepted for other verifier keys
+        let mut other_verifiers = verifiers.clone();
+        other_verifiers.get_mut(NODES[0]).unwrap().bls_key = verifiers[NODES[1]].bls_key.clone();
+        assert!(
+            verify_multi_signature(&multi_sig, &root_hash, &other_verifiers, Some(&cache))
+                .unwrap()
+                .is_none()
+        );
+
+        // failed verifications are not cached
+        let multi_sig = _multi_signature(2, &root_hash);
+        assert!(
+            verify_multi_signature(&multi_sig, &root_hash, &verifiers, Some(&cache))
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_verify_reply_requires_state_proof_request() {
+        let request = RequestBuilder::default()
+            .build_get_validator_info_request(&crate::utils::did::DidValue(
+                "V4SGRU86Z58d6TV7PBUe6f".to_owned(),
+            ))
+            .unwrap();
+        verify_reply(
+            &request,
+            r#"{"op": "REPLY", "result": {}}"#,
+            &Verifiers::new(),
+            &VerifyReplyOptions::default(),
+        )
+        .unwrap_err();
+    }
+}
@@ -0,0 +1,138 @@
+use std::fmt::Write;
+
+use serde_json::Value as SJsonValue;
+
+/// Encode a JSON value in canonical form, following the JSON Canonicalization Scheme (RFC 8785).
+///
+/// Object keys are sorted by their UTF-16 code units and floating point numbers are
+/// normalized, so that semantically equal values produce the same encoding. Integers
+/// are encoded exactly, without conversion to a double.
+pub(crate) fn canonical_json(value: &SJsonValue) -> String {
+    let mut out = String::new();
+    _write_canonical(value, &mut out);
+    out
+}
+
+fn _write_canonical(value: &SJsonValue, out: &mut String) {
+    match value {
+        SJsonValue::Null => out.push_str("null"),
+        SJsonValue::Bool(val) => out.push_str(if *val { "true" } else { "false" }),
+        SJsonValue::Number(num) => {
+            if let Some(val) = num.as_u64() {
+                write!(out, "{}", val).unwrap();
+            } else if let Some(val) = num.as_i64() {
+                write!(out, "{}", val).unwrap();
+            } else {
+                _write_number(num.as_f64().unwrap_or(0.0), out);
+            }
+        }
+        SJsonValue::String(val) => _write_string(val, out),
+        SJsonValue::Array(vals) => {
+            out.push('[');
+            for (idx, val) in vals.iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                _write_canonical(val, out);
+            }
+            out.push(']');
+        }
+        SJsonValue::Object(map) => {
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (idx, (key, val)) in entries.into_iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                _write_string(key, out);
+                out.push(':');
+                _write_canonical(val, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn _write_string(val: &str, out: &mut String) {
+    // serde_json applies the same minimal escaping as ECMAScript
+    out.push_str(&serde_json::to_string(val).unwrap());
+}
+
+/// Format a number following the ECMAScript `Number.prototype.toString` rules
+fn _write_number(val: f64, out: &mut String) {
+    if val == 0.0 || !val.is_finite() {
+        // non-finite values cannot be represented in JSON
+        out.push('0');
+        return;
+    }
+    if val < 0.0 {
+        out.push('-');
+    }
+    // the shortest round-trip representation, as `d.ddde[-]x`
+    let sci = format!("{:e}", val.abs());
+    let (mantissa, exp) = sci.split_at(sci.find('e').unwrap());
+    let digits = mantissa.replace('.', "");
+    let len = digits.len() as i32;
+    let point = exp[1..].parse::<i32>().unwrap() + 1;
+    if len <= point && point <= 21 {
+        out.push_str(&digits);
+        out.extend(std::iter::repeat('0').take((point - len) as usize));
+    } else if 0 < point && point <= 21 {
+        out.push_str(&digits[..point as usize]);
+        out.push('.');
+        out.push_str(&digits[point as usize..]);
+    } else if -6 < point && point <= 0 {
+        out.push_str("0.");
+        out.extend(std::iter::repeat('0').take(-point as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if len > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        let exp = point - 1;
+        write!(out, "e{}{}", if exp < 0 { '-' } else { '+' }, exp.abs()).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn _canonical(json: &str) -> String {
+        canonical_json(&serde_json::from_str(json).unwrap())
+    }
+
+    #[test]
+    fn test_canonical_json_sorts_keys() {
+        assert_eq!(
+            _canonical(r#"{"b": [1, {"y": null, "x": true}], "a": "text"}"#),
+            r#"{"a":"text","b":[1,{"x":true,"y":null}]}"#
+        );
+        // sorted by UTF-16 code units rather than code points
+        assert_eq!(
+            _canonical(r#"{"😀": 1, "ﬁ": 2}"#),
+            "{\"\u{1f600}\":1,\"\u{fb01}\":2}"
+        );
+    }
+
+    #[test]
+    fn test_canonical_json_numbers() {
+        assert_eq!(
+            _canonical("[1.0, -2.50, 1e3, 0.000001, 1e-7]"),
+            "[1,-2.5,1000,0.000001,1e-7]"
+        );
+        assert_eq!(_canonical("[1e21, 1.5e300, -0.0]"), "[1e+21,1.5e+300,0]");
+        assert_eq!(
+            _canonical("[18446744073709551615, -9007199254740993]"),
+            "[18446744073709551615,-9007199254740993]"
+        );
+    }
+
+    #[test]
+    fn test_canonical_json_strings() {
+        assert_eq!(_canonical(r#""A\n\u001f\/é""#), "\"A\\n\\u001f/\u{e9}\"");
+    }
+}
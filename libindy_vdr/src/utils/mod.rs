@@ -1,6 +1,9 @@
 #[macro_use]
 mod macros;
 
+/// Canonical JSON encoding
+pub(crate) mod json;
+
 // re-exports
 pub use indy_utils::{qualifiable, ConversionError, Qualifiable, Validatable, ValidationError};
 
@@ -34,7 +34,7 @@ mod builder {
             my_did: DidValue,
         ) {
             let nym_request = request_builder
-                .build_nym_request(&trustee_did, &my_did, None, None, None)
+                .build_nym_request(&trustee_did, &my_did, None, None, None, None, None)
                 .unwrap();
 
             let expected_result = json!({
@@ -58,6 +58,8 @@ mod builder {
                     Some(identity.verkey.clone()),
                     Some(ALIAS.to_string()),
                     Some(ROLE.to_string()),
+                    None,
+                    None,
                 )
                 .unwrap();
 
@@ -79,7 +81,15 @@ mod builder {
             my_did: DidValue,
         ) {
             let nym_request = request_builder
-                .build_nym_request(&trustee_did, &my_did, None, None, Some(String::from("")))
+                .build_nym_request(
+                    &trustee_did,
+                    &my_did,
+                    None,
+                    None,
+                    Some(String::from("")),
+                    None,
+                    None,
+                )
                 .unwrap();
 
             let expected_result = json!({
@@ -99,7 +109,7 @@ mod builder {
             my_did: DidValue,
         ) {
             let nym_request = request_builder
-                .build_nym_request(&fq_trustee_did, &fq_my_did, None, None, None)
+                .build_nym_request(&fq_trustee_did, &fq_my_did, None, None, None, None, None)
                 .unwrap();
 
             let expected_result = json!({
@@ -125,8 +135,65 @@ mod builder {
                     Some(identity.verkey),
                     None,
                     Some(role.to_string()),
+                    None,
+                    None,
+                )
+                .unwrap_err();
+        }
+
+        #[rstest]
+        fn test_build_nym_request_for_diddoc_content_and_version(
+            request_builder: RequestBuilder,
+            trustee_did: DidValue,
+            my_did: DidValue,
+        ) {
+            let diddoc_content = json!({
+                "service": [{"id": "#agent", "type": "DIDCommMessaging"}]
+            });
+
+            let nym_request = request_builder
+                .build_nym_request(
+                    &trustee_did,
+                    &my_did,
+                    None,
+                    None,
+                    None,
+                    Some(&diddoc_content),
+                    Some(2),
+                )
+                .unwrap();
+
+            let expected_result = json!({
+                "type": constants::NYM,
+                "dest": my_did,
+                "diddocContent": diddoc_content.to_string(),
+                "version": 2,
+            });
+
+            helpers::check_request_operation(&nym_request, expected_result);
+        }
+
+        #[rstest]
+        fn test_build_nym_request_works_for_invalid_diddoc_content_and_version(
+            request_builder: RequestBuilder,
+            trustee_did: DidValue,
+            my_did: DidValue,
+        ) {
+            let _err = request_builder
+                .build_nym_request(
+                    &trustee_did,
+                    &my_did,
+                    None,
+                    None,
+                    None,
+                    Some(&json!(["service"])),
+                    None,
                 )
                 .unwrap_err();
+
+            let _err = request_builder
+                .build_nym_request(&trustee_did, &my_did, None, None, None, None, Some(3))
+                .unwrap_err();
         }
     }
 
@@ -140,7 +207,7 @@ mod builder {
             my_did: DidValue,
         ) {
             let nym_request = request_builder
-                .build_get_nym_request(Some(&trustee_did), &my_did)
+                .build_get_nym_request(Some(&trustee_did), &my_did, None, None)
                 .unwrap();
 
             let expected_result = json!({
@@ -159,15 +226,49 @@ mod builder {
             my_did: DidValue,
         ) {
             let nym_request = request_builder
-                .build_get_nym_request(Some(&fq_trustee_did), &fq_my_did)
+                .build_get_nym_request(Some(&fq_trustee_did), &fq_my_did, None, None)
+                .unwrap();
+
+            let expected_result = json!({
+                "type": constants::GET_NYM,
+                "dest": my_did,
+            });
+
+            helpers::check_request_operation(&nym_request, expected_result);
+        }
+
+        #[rstest]
+        fn test_pool_build_get_nym_request_for_seq_no_and_timestamp(
+            request_builder: RequestBuilder,
+            my_did: DidValue,
+        ) {
+            let nym_request = request_builder
+                .build_get_nym_request(None, &my_did, Some(10), None)
+                .unwrap();
+
+            let expected_result = json!({
+                "type": constants::GET_NYM,
+                "dest": my_did,
+                "seqNo": 10,
+            });
+
+            helpers::check_request_operation(&nym_request, expected_result);
+
+            let nym_request = request_builder
+                .build_get_nym_request(None, &my_did, None, Some(1600000000))
                 .unwrap();
 
             let expected_result = json!({
                 "type": constants::GET_NYM,
                 "dest": my_did,
+                "timestamp": 1600000000,
             });
 
             helpers::check_request_operation(&nym_request, expected_result);
+
+            let _err = request_builder
+                .build_get_nym_request(None, &my_did, Some(10), Some(1600000000))
+                .unwrap_err();
         }
     }
 }
@@ -190,6 +291,8 @@ mod send {
                 Some(identity.verkey.to_string()),
                 None,
                 None,
+                None,
+                None,
             )
             .unwrap();
 
@@ -199,7 +302,7 @@ mod send {
         // Get NYM
         let get_nym_request = pool
             .request_builder()
-            .build_get_nym_request(None, &identity.did)
+            .build_get_nym_request(None, &identity.did, None, None)
             .unwrap();
 
         let response = pool
@@ -229,6 +332,8 @@ mod send {
                 Some(identity.verkey.to_string()),
                 Some(ALIAS.to_string()),
                 Some(ROLE.to_string()),
+                None,
+                None,
             )
             .unwrap();
 
@@ -238,7 +343,7 @@ mod send {
         // Get NYM
         let get_nym_request = pool
             .request_builder()
-            .build_get_nym_request(None, &identity.did)
+            .build_get_nym_request(None, &identity.did, None, None)
             .unwrap();
 
         let response = pool
@@ -277,6 +382,8 @@ mod send {
                 Some(new_identity.verkey.to_string()),
                 None,
                 Some(role.to_string()),
+                None,
+                None,
             )
             .unwrap();
 
@@ -286,7 +393,7 @@ mod send {
         // Get NYM
         let get_nym_request = pool
             .request_builder()
-            .build_get_nym_request(None, &new_identity.did)
+            .build_get_nym_request(None, &new_identity.did, None, None)
             .unwrap();
 
         let response = pool
@@ -316,6 +423,8 @@ mod send {
                 Some(identity.verkey.to_string()),
                 None,
                 Some(ROLE.to_string()),
+                None,
+                None,
             )
             .unwrap();
 
@@ -325,7 +434,7 @@ mod send {
         // Get NYM to ensure role is TRUSTEE
         let get_nym_request = pool
             .request_builder()
-            .build_get_nym_request(None, &identity.did)
+            .build_get_nym_request(None, &identity.did, None, None)
             .unwrap();
 
         let response = pool
@@ -348,6 +457,8 @@ mod send {
                 None,
                 None,
                 Some(ROLE_REMOVE.to_string()),
+                None,
+                None,
             )
             .unwrap();
 
@@ -357,7 +468,7 @@ mod send {
         // Get NYM to ensure role was reset
         let get_nym_request = pool
             .request_builder()
-            .build_get_nym_request(None, &identity.did)
+            .build_get_nym_request(None, &identity.did, None, None)
             .unwrap();
 
         let response = pool
@@ -387,6 +498,8 @@ mod send {
                 Some(identity.verkey.to_string()),
                 None,
                 None,
+                None,
+                None,
             )
             .unwrap();
 
@@ -399,7 +512,7 @@ mod send {
         // Send NYM
         let mut nym_request = pool
             .request_builder()
-            .build_nym_request(&identity.did, &identity.did, None, None, None)
+            .build_nym_request(&identity.did, &identity.did, None, None, None, None, None)
             .unwrap();
 
         identity.sign_request(&mut nym_request);
@@ -422,6 +535,8 @@ mod send {
                 Some(new_identity.verkey),
                 None,
                 None,
+                None,
+                None,
             )
             .unwrap();
 
@@ -434,7 +549,7 @@ mod send {
         // Get NYM
         let get_nym_request = pool
             .request_builder()
-            .build_get_nym_request(None, &identity.did)
+            .build_get_nym_request(None, &identity.did, None, None)
             .unwrap();
 
         let response = pool.send_request(&get_nym_request).unwrap();
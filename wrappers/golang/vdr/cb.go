@@ -28,5 +28,11 @@ void status(ErrorCode err, const char *response) {
 	statusCb(err, response);
 }
 
+extern void refreshListenerCb(CallbackId cb_id, const char *changes);
+
+void refreshListener(CallbackId cb_id, const char *changes) {
+	refreshListenerCb(cb_id, changes);
+}
+
 */
 import "C"
@@ -20,6 +20,9 @@ typedef void (*refreshWrapper)(ErrorCode err);
 extern void status(ErrorCode err, const char* response);
 typedef void (*statusWrapper)(ErrorCode err, const char* response);
 
+extern void refreshListener(CallbackId cb_id, const char* changes);
+typedef void (*refreshListenerWrapper)(CallbackId cb_id, const char* changes);
+
 */
 import "C"
 
@@ -110,6 +113,12 @@ func submitRequestCb(cb_id C.CallbackId, err C.ErrorCode, response *C.char) {
 
 //Submit is used to send prepared read requests to the ledger where the request parameter is the JSON-formatted payload.
 func (r *Client) Submit(request []byte) (*ReadReply, error) {
+	return r.SubmitWithPolicy(request, nil)
+}
+
+//SubmitWithPolicy is used to send prepared read requests to the ledger, accepting the replies according to the
+//consensus policy. A nil policy applies the default rules.
+func (r *Client) SubmitWithPolicy(request []byte, policy *ConsensusPolicy) (*ReadReply, error) {
 
 	var cusreq C.ulong
 	cjson := C.CString(string(request))
@@ -123,6 +132,22 @@ func (r *Client) Submit(request []byte) (*ReadReply, error) {
 	}
 	defer C.indy_vdr_request_free(cusreq)
 
+	if policy != nil {
+		d, err := json.Marshal(policy)
+		if err != nil {
+			return nil, errors.Wrap(err, "unable to marshal consensus policy")
+		}
+		cpolicy := C.CString(string(d))
+		result = C.indy_vdr_request_set_consensus_policy(cusreq, cpolicy)
+		C.free(unsafe.Pointer(cpolicy))
+		if result != 0 {
+			var errMsg *C.char
+			C.indy_vdr_get_current_error(&errMsg)
+			defer C.free(unsafe.Pointer(errMsg))
+			return nil, fmt.Errorf("invalid consensus policy: (Indy error code: [%s])", C.GoString(errMsg))
+		}
+	}
+
 	return r.submitReadRequest(cusreq)
 }
 
@@ -131,7 +156,7 @@ func (r *Client) GetNym(did string) (*ReadReply, error) {
 	var nymreq C.ulong
 	var none *C.char
 	cdid := C.CString(did)
-	result := C.indy_vdr_build_get_nym_request(none, cdid, &nymreq)
+	result := C.indy_vdr_build_get_nym_request(none, cdid, -1, -1, &nymreq)
 	C.free(unsafe.Pointer(cdid))
 	if result != 0 {
 		return nil, fmt.Errorf("invalid get nym request: (Indy error code: [%v])", result)
@@ -210,6 +235,46 @@ func (r *Client) RefreshPool() error {
 	return nil
 }
 
+var refreshListeners = map[C.CallbackId]func(*PoolChanges){}
+var refreshListenersLock = sync.Mutex{}
+var nextRefreshListener C.CallbackId
+
+//export refreshListenerCb
+func refreshListenerCb(cb_id C.CallbackId, changes *C.char) {
+	refreshListenersLock.Lock()
+	listener, ok := refreshListeners[cb_id]
+	refreshListenersLock.Unlock()
+	if !ok {
+		return
+	}
+
+	pc := &PoolChanges{}
+	err := json.Unmarshal([]byte(C.GoString(changes)), pc)
+	if err != nil {
+		return
+	}
+	listener(pc)
+}
+
+//AddRefreshListener registers a function called with the verifier changes applied by each pool refresh
+func (r *Client) AddRefreshListener(listener func(*PoolChanges)) error {
+	refreshListenersLock.Lock()
+	defer refreshListenersLock.Unlock()
+
+	nextRefreshListener++
+	cbID := nextRefreshListener
+	result := C.indy_vdr_pool_add_refresh_listener(C.ulong(r.pool), C.refreshListenerWrapper(C.refreshListener), cbID)
+	if result != 0 {
+		var errMsg *C.char
+		C.indy_vdr_get_current_error(&errMsg)
+		defer C.free(unsafe.Pointer(errMsg))
+		return fmt.Errorf("add refresh listener failed: (Indy error code: [%v] %s)", result, C.GoString(errMsg))
+	}
+	refreshListeners[cbID] = listener
+
+	return nil
+}
+
 type StatusResponse struct {
 	ErrorCode int
 	Response  string
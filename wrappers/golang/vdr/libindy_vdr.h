@@ -61,7 +61,11 @@ ErrorCode indy_vdr_build_get_cred_def_request(FfiStr submitter_did,
                                               FfiStr cred_def_id,
                                               Handle *handle_p);
 
-ErrorCode indy_vdr_build_get_nym_request(FfiStr submitter_did, FfiStr dest, Handle *handle_p);
+ErrorCode indy_vdr_build_get_nym_request(FfiStr submitter_did,
+                                         FfiStr dest,
+                                         int32_t seq_no,
+                                         int64_t timestamp,
+                                         Handle *handle_p);
 
 ErrorCode indy_vdr_build_get_revoc_reg_def_request(FfiStr submitter_did,
                                                    FfiStr revoc_reg_id,
@@ -108,6 +112,8 @@ ErrorCode indy_vdr_build_nym_request(FfiStr submitter_did,
                                      FfiStr verkey,
                                      FfiStr alias,
                                      FfiStr role,
+                                     FfiStr diddoc_content,
+                                     int32_t version,
                                      Handle *handle_p);
 
 ErrorCode indy_vdr_build_revoc_reg_def_request(FfiStr submitter_did,
@@ -140,6 +146,10 @@ ErrorCode indy_vdr_build_txn_author_agreement_request(FfiStr submitter_did,
 
 ErrorCode indy_vdr_get_current_error(const char **error_json_p);
 
+ErrorCode indy_vdr_pool_add_refresh_listener(Handle pool_handle,
+                                             void (*cb)(CallbackId cb_id, const char *changes),
+                                             CallbackId cb_id);
+
 ErrorCode indy_vdr_pool_close(Handle pool_handle);
 
 ErrorCode indy_vdr_pool_create(FfiStr params, Handle *handle_p);
@@ -185,6 +195,8 @@ ErrorCode indy_vdr_request_get_body(Handle request_handle, const char **body_p);
 
 ErrorCode indy_vdr_request_get_signature_input(Handle request_handle, const char **input_p);
 
+ErrorCode indy_vdr_request_set_consensus_policy(Handle request_handle, FfiStr policy);
+
 ErrorCode indy_vdr_request_set_endorser(Handle request_handle, FfiStr endorser);
 
 ErrorCode indy_vdr_request_set_signature(Handle request_handle,
@@ -5,3 +5,28 @@ type PoolStatus struct {
 	Size  int      `json:"mt_size"`
 	Nodes []string `json:"nodes"`
 }
+
+//ConsensusPolicy defines the rules for accepting the replies to a request.
+//Quorum may be "weak", "strong" or map[string]int{"count": n}, and Freshness
+//may be "pool", "skip" or map[string]int{"within": seconds}.
+type ConsensusPolicy struct {
+	Quorum                interface{} `json:"quorum,omitempty"`
+	AcceptStateProof      *bool       `json:"accept_state_proof,omitempty"`
+	Freshness             interface{} `json:"freshness,omitempty"`
+	InitialNodes          int         `json:"initial_nodes,omitempty"`
+	RequiredNodes         *NodeQuorum `json:"required_nodes,omitempty"`
+	RequireInclusionProof bool        `json:"require_inclusion_proof,omitempty"`
+}
+
+//NodeQuorum is a minimum number of matching replies which must come from a set of nodes
+type NodeQuorum struct {
+	NodeAliases []string `json:"node_aliases"`
+	MinReplies  int      `json:"min_replies"`
+}
+
+type PoolChanges struct {
+	Added            []string `json:"added"`
+	Removed          []string `json:"removed"`
+	ChangedAddresses []string `json:"changed_addresses"`
+	RotatedBLSKeys   []string `json:"rotated_bls_keys"`
+}
@@ -6,6 +6,16 @@ import { Callback } from 'ffi-napi';
 import * as ref from 'ref-napi';
 import { LedgerRequest } from './ledger-requests/ledger-request';
 
+/**
+ * The verifier changes applied by a pool refresh
+ */
+export interface IPoolChanges {
+    added: string[];
+    removed: string[];
+    changed_addresses: string[];
+    rotated_bls_keys: string[];
+}
+
 /**
  * @class Class representing a Indy Pool
  */
@@ -13,6 +23,7 @@ export class IndyVdrPool {
     protected _handle: Buffer;
     protected _params: string;
     protected _name: string;
+    protected _listeners: Buffer[] = [];
 
     constructor(name: string, params: string) {
         this._handle = allocateHandleBuffer();
@@ -36,6 +47,23 @@ export class IndyVdrPool {
             if (rc) {
                 throw Error(`Failed to close pool!`);
             }
+            this._listeners = [];
+        } catch (err) {
+            throw new VDRInternalError(err);
+        }
+    }
+
+    public addRefreshListener(listener: (changes: IPoolChanges) => void) {
+        try {
+            const cb = Callback('void', ['uint32', 'pointer'], (id: number, changes: Buffer) => {
+                listener(JSON.parse(ref.readCString(changes, 0)));
+            });
+            const rc = rustAPI().indy_vdr_pool_add_refresh_listener(this.getHandle(), cb, 0);
+            if (rc) {
+                throw Error(`Failed to add refresh listener!`);
+            }
+            // keep a reference to the callback until the pool is closed
+            this._listeners.push(cb);
         } catch (err) {
             throw new VDRInternalError(err);
         }
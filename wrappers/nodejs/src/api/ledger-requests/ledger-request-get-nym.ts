@@ -11,11 +11,17 @@ export class LedgerRequestGetNym extends LedgerRequest {
         super();
     }
 
-    public static create(dest: string, submitterDid?: string): LedgerRequestGetNym {
+    public static create(dest: string, submitterDid?: string, seqNo?: number, timestamp?: number): LedgerRequestGetNym {
         try {
             const request = new LedgerRequestGetNym();
             const submitterDidFfi = submitterDid ? allocCString(submitterDid) : NULL;
-            rustAPI().indy_vdr_build_get_nym_request(submitterDidFfi, allocCString(dest), request._handle);
+            rustAPI().indy_vdr_build_get_nym_request(
+                submitterDidFfi,
+                allocCString(dest),
+                seqNo !== undefined ? seqNo : -1,
+                timestamp !== undefined ? timestamp : -1,
+                request._handle,
+            );
             return request;
         } catch (err) {
             throw new VDRInternalError(err);
@@ -18,18 +18,25 @@ export class LedgerRequestNym extends LedgerRequest {
         verkey?: string,
         alias?: string,
         role?: string,
+        diddocContent?: string | object,
+        version?: number,
     ): LedgerRequestNym {
         try {
             const request = new LedgerRequestNym();
             const verkeyFfi = verkey ? allocCString(verkey) : NULL;
             const aliasFfi = alias ? allocCString(alias) : NULL;
             const roleFfi = role ? allocCString(role) : NULL;
+            const diddocContentFfi = diddocContent
+                ? allocCString(typeof diddocContent === 'string' ? diddocContent : JSON.stringify(diddocContent))
+                : NULL;
             rustAPI().indy_vdr_build_nym_request(
                 allocCString(submitterDid),
                 allocCString(dest),
                 verkeyFfi,
                 aliasFfi,
                 roleFfi,
+                diddocContentFfi,
+                version !== undefined ? version : -1,
                 request._handle,
             );
             return request;
@@ -2,6 +2,19 @@ import { rustAPI } from '../../rustlib';
 import { VDRInternalError } from '../../errors';
 import { allocateHandleBuffer, handleBufferToNumber } from '../ffi-tools';
 import * as ref from 'ref-napi';
+import { allocCString, NULL } from 'ref-napi';
+
+/**
+ * Rules for accepting the replies to a request resolved by consensus
+ */
+export interface IConsensusPolicy {
+    quorum?: 'weak' | 'strong' | { count: number };
+    accept_state_proof?: boolean;
+    freshness?: 'pool' | 'skip' | { within: number };
+    initial_nodes?: number;
+    required_nodes?: { node_aliases: string[]; min_replies: number };
+    require_inclusion_proof?: boolean;
+}
 
 export class LedgerRequest {
     protected _handle: Buffer;
@@ -29,4 +42,16 @@ export class LedgerRequest {
             throw new VDRInternalError(err);
         }
     }
+
+    /**
+     * Sets the rules for accepting the replies to this request, or restores the default rules
+     */
+    public setConsensusPolicy(policy?: IConsensusPolicy): void {
+        try {
+            const policyFfi = policy ? allocCString(JSON.stringify(policy)) : NULL;
+            rustAPI().indy_vdr_request_set_consensus_policy(this.getHandle(), policyFfi);
+        } catch (err) {
+            throw new VDRInternalError(err);
+        }
+    }
 }
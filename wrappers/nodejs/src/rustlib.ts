@@ -18,6 +18,8 @@ export const FFI_HANDLE = 'uint32';
 export const FFI_REQUEST_HANDLE = 'uint32';
 export const FFI_UNSIGNED_INT = 'uint32';
 export const FFI_UNSIGNED_LONG = 'uint64';
+export const FFI_INT32 = 'int32';
+export const FFI_INT64 = 'int64';
 export const FFI_UNSIGNED_INT_PTR = FFI_USIZE;
 export const FFI_STRING = 'string';
 export const FFI_CONFIG_PATH = FFI_STRING;
@@ -53,17 +55,27 @@ export interface IFFIEntryPoint {
         verkey: Buffer,
         alias: Buffer,
         role: Buffer,
+        diddocContent: Buffer,
+        version: number,
+        requestHandle: Buffer,
+    ) => rust_err_code;
+    indy_vdr_build_get_nym_request: (
+        submitterDid: Buffer,
+        dest: Buffer,
+        seqNo: number,
+        timestamp: number,
         requestHandle: Buffer,
     ) => rust_err_code;
-    indy_vdr_build_get_nym_request: (submitterDid: Buffer, dest: Buffer, requestHandle: Buffer) => rust_err_code;
     indy_vdr_build_get_validator_info_request: (submitterDid: Buffer, requestHandle: Buffer) => rust_err_code;
     indy_vdr_request_get_body: (requestHandle: number, bodyReturnPtr: number) => rust_err_code;
+    indy_vdr_request_set_consensus_policy: (requestHandle: number, policy: Buffer) => rust_err_code;
     // pool
     indy_vdr_pool_create: (params: rust_string, pool_handle: Buffer) => rust_err_code;
     indy_vdr_pool_close: (poolHandle: number) => rust_err_code;
     indy_vdr_pool_submit_request: (poolHandle: number, requestHandle: number, cb: any, cbId: number) => rust_err_code;
     indy_vdr_pool_get_status: (poolHandle: number, cb: any, cbId: number) => rust_err_code;
     indy_vdr_pool_get_transactions: (poolHandle: number, cb: any, cbId: number) => rust_err_code;
+    indy_vdr_pool_add_refresh_listener: (poolHandle: number, cb: any, cbId: number) => rust_err_code;
 }
 
 /**
@@ -79,18 +91,32 @@ export const FFIConfiguration: { [Key in keyof IFFIEntryPoint]: any } = {
     indy_vdr_build_get_txn_request: [FFI_ERROR_CODE, [FFI_USIZE_PTR, FFI_USIZE, FFI_USIZE, FFI_USIZE_PTR]],
     indy_vdr_build_nym_request: [
         FFI_ERROR_CODE,
-        [FFI_USIZE_PTR, FFI_USIZE_PTR, FFI_USIZE_PTR, FFI_USIZE_PTR, FFI_USIZE_PTR, FFI_USIZE_PTR],
+        [
+            FFI_USIZE_PTR,
+            FFI_USIZE_PTR,
+            FFI_USIZE_PTR,
+            FFI_USIZE_PTR,
+            FFI_USIZE_PTR,
+            FFI_USIZE_PTR,
+            FFI_INT32,
+            FFI_USIZE_PTR,
+        ],
     ],
     indy_vdr_build_get_schema_request: [FFI_ERROR_CODE, [FFI_USIZE_PTR, FFI_USIZE_PTR, FFI_USIZE_PTR]],
-    indy_vdr_build_get_nym_request: [FFI_ERROR_CODE, [FFI_USIZE_PTR, FFI_USIZE_PTR, FFI_USIZE_PTR]],
+    indy_vdr_build_get_nym_request: [
+        FFI_ERROR_CODE,
+        [FFI_USIZE_PTR, FFI_USIZE_PTR, FFI_INT32, FFI_INT64, FFI_USIZE_PTR],
+    ],
     indy_vdr_build_get_validator_info_request: [FFI_ERROR_CODE, [FFI_USIZE_PTR, FFI_USIZE_PTR]],
     indy_vdr_request_get_body: [FFI_ERROR_CODE, [FFI_USIZE, FFI_USIZE]],
+    indy_vdr_request_set_consensus_policy: [FFI_ERROR_CODE, [FFI_USIZE, FFI_USIZE_PTR]],
     // pool
     indy_vdr_pool_create: [FFI_ERROR_CODE, [FFI_STRING_DATA, FFI_USIZE_PTR]],
     indy_vdr_pool_close: [FFI_ERROR_CODE, [FFI_USIZE]],
     indy_vdr_pool_submit_request: [FFI_ERROR_CODE, [FFI_USIZE, FFI_USIZE, FFI_CALLBACK_PTR, FFI_USIZE]],
     indy_vdr_pool_get_status: [FFI_ERROR_CODE, [FFI_USIZE, FFI_CALLBACK_PTR, FFI_USIZE]],
     indy_vdr_pool_get_transactions: [FFI_ERROR_CODE, [FFI_USIZE, FFI_CALLBACK_PTR, FFI_USIZE]],
+    indy_vdr_pool_add_refresh_listener: [FFI_ERROR_CODE, [FFI_USIZE, FFI_CALLBACK_PTR, FFI_USIZE]],
 };
 
 let _rustAPI: IFFIEntryPoint;
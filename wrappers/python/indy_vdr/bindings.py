@@ -7,12 +7,13 @@ import os
 import sys
 from ctypes import CDLL, CFUNCTYPE, byref, c_char_p, c_size_t, c_void_p, c_int32
 from ctypes.util import find_library
-from typing import Optional, Sequence, Union
+from typing import Callable, Optional, Sequence, Union
 
 from .error import VdrError, VdrErrorCode
 
 
 CALLBACKS = {}
+LISTENERS = {}
 LIB: CDLL = None
 LOGGER = logging.getLogger(__name__)
 
@@ -204,6 +205,26 @@ def pool_create(params: Union[str, bytes, dict]) -> PoolHandle:
     return handle
 
 
+def pool_add_refresh_listener(
+    pool_handle: PoolHandle, listener: Callable[[dict], None]
+):
+    """Register a listener for the verifier changes applied by each pool refresh.
+
+    The listener is invoked on the current event loop.
+    """
+    loop = asyncio.get_event_loop()
+
+    def _cb(id: int, changes: lib_string):
+        """Callback function passed to the CFUNCTYPE for invocation."""
+        changes = json.loads(str(changes))
+        loop.call_soon_threadsafe(lambda: listener(changes))
+
+    cb_res = CFUNCTYPE(None, c_size_t, lib_string)(_cb)
+    do_call("indy_vdr_pool_add_refresh_listener", pool_handle, cb_res, c_size_t(0))
+    # keep a reference to the callback function until the pool is closed
+    LISTENERS.setdefault(pool_handle.value, []).append(cb_res)
+
+
 def pool_get_status(pool_handle: PoolHandle) -> asyncio.Future:
     """Get the status of an opened pool instance."""
     return do_call_async(
@@ -255,6 +276,7 @@ def pool_submit_request(
 def pool_close(pool_handle: PoolHandle):
     """Close and free a pool instance."""
     do_call("indy_vdr_pool_close", pool_handle)
+    LISTENERS.pop(pool_handle.value, None)
 
 
 def pool_get_transactions(pool_handle: PoolHandle) -> asyncio.Future:
@@ -296,6 +318,14 @@ def request_get_signature_input(handle: RequestHandle) -> bytes:
     return sig_input.value
 
 
+def request_set_consensus_policy(
+    handle: RequestHandle, policy: Optional[Union[str, dict]]
+):
+    """Set the consensus policy on a prepared request."""
+    policy_p = encode_json(policy) if isinstance(policy, dict) else encode_str(policy)
+    do_call("indy_vdr_request_set_consensus_policy", handle, policy_p)
+
+
 def request_set_endorser(handle: RequestHandle, endorser_did: str):
     """Set the endorser on a prepared request."""
     endorser_p = encode_str(endorser_did)
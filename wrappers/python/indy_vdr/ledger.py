@@ -268,7 +268,12 @@ def build_get_cred_def_request(
     return Request(handle)
 
 
-def build_get_nym_request(submitter_did: Optional[str], dest: str) -> Request:
+def build_get_nym_request(
+    submitter_did: Optional[str],
+    dest: str,
+    seq_no: int = None,
+    timestamp: int = None,
+) -> Request:
     """
     Builds a GET_NYM request to get information about a DID (NYM).
 
@@ -276,11 +281,22 @@ def build_get_nym_request(submitter_did: Optional[str], dest: str) -> Request:
         submitter_did: (Optional) DID of the read request sender. If not provided
             then the default Libindy DID will be use)
         target_did: Target DID as base58-encoded string for 16 or 32 bit DID value
+        seq_no: (Optional) Sequence number of the NYM transaction to fetch
+        timestamp: (Optional) Fetch the NYM as of this time (seconds since the epoch)
     """
     handle = RequestHandle()
     did_p = encode_str(submitter_did)
     dest_p = encode_str(dest)
-    do_call("indy_vdr_build_get_nym_request", did_p, dest_p, byref(handle))
+    seq_no_c = c_int32(seq_no if seq_no is not None else -1)
+    timestamp_c = c_int64(timestamp if timestamp is not None else -1)
+    do_call(
+        "indy_vdr_build_get_nym_request",
+        did_p,
+        dest_p,
+        seq_no_c,
+        timestamp_c,
+        byref(handle),
+    )
     return Request(handle)
 
 
@@ -471,6 +487,8 @@ def build_nym_request(
     verkey: str = None,
     alias: str = None,
     role: str = None,
+    diddoc_content: Union[str, dict] = None,
+    version: int = None,
 ) -> Request:
     """
     Builds a NYM request to create new DID on the ledger.
@@ -489,6 +507,11 @@ def build_nym_request(
             ENDORSER - equal to TRUST_ANCHOR that will be removed soon
             NETWORK_MONITOR
             empty string to reset role
+        diddoc_content: (Optional) Additional DID document content for did:indy
+        version: (Optional) The DID version of the NYM:
+            0 (no self-certification check)
+            1 (did:sov)
+            2 (did:indy)
     """
     handle = RequestHandle()
     did_p = encode_str(submitter_did)
@@ -496,6 +519,16 @@ def build_nym_request(
     verkey_p = encode_str(verkey) if verkey else None
     alias_p = encode_str(alias) if alias else None
     role_p = encode_str(role) if role else None
+    diddoc_content_p = (
+        (
+            encode_str(diddoc_content)
+            if isinstance(diddoc_content, (str, bytes))
+            else encode_json(diddoc_content)
+        )
+        if diddoc_content
+        else None
+    )
+    version_c = c_int32(version if version is not None else -1)
     do_call(
         "indy_vdr_build_nym_request",
         did_p,
@@ -503,6 +536,8 @@ def build_nym_request(
         verkey_p,
         alias_p,
         role_p,
+        diddoc_content_p,
+        version_c,
         byref(handle),
     )
     return Request(handle)
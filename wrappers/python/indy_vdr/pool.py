@@ -2,7 +2,7 @@
 
 import json
 from datetime import datetime
-from typing import Mapping, Sequence, Union
+from typing import Callable, Mapping, Sequence, Union
 
 from . import bindings
 from .error import VdrError, VdrErrorCode
@@ -18,6 +18,16 @@ class Pool:
         self.last_refresh: datetime = None
         self.last_status: dict = None
 
+    def add_refresh_listener(self, listener: Callable[[dict], None]):
+        """Register a listener for the verifier changes applied by each refresh.
+
+        The listener is called with a dict listing the `added` and `removed` nodes,
+        and the nodes with `changed_addresses` or `rotated_bls_keys`.
+        """
+        if not self.handle:
+            raise VdrError(VdrErrorCode.WRAPPER, "pool is closed")
+        bindings.pool_add_refresh_listener(self.handle, listener)
+
     def close(self):
         """Close and free the pool instance."""
         if hasattr(self, "handle") and self.handle:
@@ -142,6 +152,7 @@ async def open_pool(
     node_weights: Mapping[str, float] = None,
     no_refresh: bool = False,
     socks_proxy: str = None,
+    cache_path: str = None,
 ) -> Pool:
     """Create a new ledger pool instance.
 
@@ -156,6 +167,7 @@ async def open_pool(
             node will never be selected.
         no_refresh: Disable the initial verifier pool refresh
         socks_proxy: The socks proxy host name and port for ZMQ (example: proxy1.intranet.company.com:1080)
+        cache_path: A directory in which to cache the pool transactions found by each refresh
 
     Returns:
         A new `Pool` instance which may be used to submit ledger requests
@@ -171,6 +183,7 @@ async def open_pool(
         "transactions": transactions,
         "transactions_path": transactions_path,
         "node_weights": node_weights,
+        "cache_path": cache_path,
     }
     pool = Pool(bindings.pool_create(params))
     if not no_refresh:
@@ -1,6 +1,6 @@
 """Handling of prepared ledger requests."""
 
-from typing import Union
+from typing import Optional, Union
 
 from . import bindings
 from .error import VdrError, VdrErrorCode
@@ -40,6 +40,17 @@ class Request:
             raise VdrError(VdrErrorCode.WRAPPER, "no request handle")
         return bindings.request_get_signature_input(self.handle)
 
+    def set_consensus_policy(self, policy: Optional[Union[str, dict]]):
+        """Set the rules for accepting the replies to this request.
+
+        Args:
+            policy: The consensus policy as a JSON string or dict, for example
+                `{"quorum": "strong"}`, or `None` to restore the default rules
+        """
+        if not self.handle:
+            raise VdrError(VdrErrorCode.WRAPPER, "no request handle")
+        bindings.request_set_consensus_policy(self.handle, policy)
+
     def set_endorser(self, endorser: str):
         """
         Set the endorser property of an existing request.